@@ -1,5 +1,8 @@
+use std::fmt::{self, Display};
 use std::ops::{Index, IndexMut};
 
+use pretty;
+
 use typehack::data::*;
 use typehack::dim::*;
 use typehack::binary::Nat as BNat;
@@ -37,11 +40,18 @@ pub trait Dims<T>: Copy {
     fn flatten(&self, Self::Index) -> usize;
     fn flatten_safe(&self, Self::Index) -> Option<usize>;
     fn expand(&self, usize) -> Self::Index;
+
+    /// The extent of each axis, outermost first.
+    fn shape(&self) -> Vec<usize>;
+
+    /// Builds dimensions from the extent of each axis, returning `None` if the number of axes is
+    /// wrong or a static axis does not match.
+    fn from_shape(&[usize]) -> Option<Self>;
 }
 
 
 macro_rules! dims_impl {
-    (@TCons) => {
+    (@TCons $head:ty) => {
         type Length = S<L::Length>;
         type Index = TCons<usize, L::Index>;
 
@@ -71,6 +81,26 @@ macro_rules! dims_impl {
                 next: self.next.expand(idx % self.next.total()),
             }
         }
+
+        fn shape(&self) -> Vec<usize> {
+            let mut shape = self.next.shape();
+            shape.insert(0, self.elem.reify());
+            shape
+        }
+
+        fn from_shape(shape: &[usize]) -> Option<Self> {
+            match shape.split_first() {
+                Some((&n, rest)) if <$head as Dim>::compatible(n) => {
+                    L::from_shape(rest).map(|next| {
+                        TCons {
+                            elem: <$head as Dim>::from_usize(n),
+                            next: next,
+                        }
+                    })
+                }
+                _ => None,
+            }
+        }
     };
     (@TNil) => {
         type Length = Z;
@@ -97,6 +127,14 @@ macro_rules! dims_impl {
         fn expand(&self, _idx: usize) -> TNil {
             TNil
         }
+
+        fn shape(&self) -> Vec<usize> {
+            Vec::new()
+        }
+
+        fn from_shape(shape: &[usize]) -> Option<TNil> {
+            if shape.is_empty() { Some(TNil) } else { None }
+        }
     };
 }
 
@@ -111,7 +149,7 @@ impl<T, M: BNat + DimMul<N>, N: Dim + Size<T>, L: Dims<T, Product = N>> Dims<T>
         DimMul::mul(self.elem, self.next.product())
     }
 
-    dims_impl!(@TCons);
+    dims_impl!(@TCons M);
 }
 
 
@@ -125,7 +163,7 @@ impl<T, L: Dims<T>> Dims<T> for TCons<Dyn, L>
         DimMul::mul(self.elem, self.next.product())
     }
 
-    dims_impl!(@TCons);
+    dims_impl!(@TCons Dyn);
 }
 
 
@@ -251,6 +289,68 @@ impl<T, X: Dim, Y: Dim, Z: Dim> IndexMut<[usize; 3]>
 }
 
 
+impl<T: Display, X: Dim> Display for Array<T, TCons<X, TNil>>
+    where TCons<X, TNil>: Dims<T, Index = TCons<usize, TNil>>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision();
+
+        pretty::write_list(f,
+                           self.dims.elem.reify(),
+                           "[",
+                           "]",
+                           |i| pretty::elem(&self[i], precision))
+    }
+}
+
+
+impl<T: Display, X: Dim, Y: Dim> Display for Array<T, TCons<X, TCons<Y, TNil>>>
+    where TCons<X, TCons<Y, TNil>>: Dims<T, Index = TCons<usize, TCons<usize, TNil>>>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision();
+
+        pretty::write_grid(f,
+                           self.dims.elem.reify(),
+                           self.dims.next.elem.reify(),
+                           |i, j| pretty::elem(&self[[i, j]], precision))
+    }
+}
+
+
+/// Displays a three-dimensional array as a sequence of two-dimensional slices along its first
+/// axis, each headed by its index.
+impl<T: Display, X: Dim, Y: Dim, Z: Dim> Display for Array<T, TCons<X, TCons<Y, TCons<Z, TNil>>>>
+    where TCons<X, TCons<Y, TCons<Z, TNil>>>: Dims<T,
+                                                   Index = TCons<usize,
+                                                                 TCons<usize, TCons<usize, TNil>>>>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision();
+        let rows = self.dims.next.elem.reify();
+        let cols = self.dims.next.next.elem.reify();
+
+        for (n, k) in pretty::axis(self.dims.elem.reify(), f.alternate()).into_iter().enumerate() {
+            if n > 0 {
+                write!(f, "\n\n")?;
+            }
+
+            match k {
+                Some(k) => {
+                    write!(f, "[{}, :, :] =\n", k)?;
+                    pretty::write_grid(f, rows, cols, |i, j| {
+                            pretty::elem(&self[[k, i, j]], precision)
+                        })?;
+                }
+                None => write!(f, "...")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
 pub struct Array<T, D: Dims<T>>
     where D::Product: Size<T>
 {
@@ -276,6 +376,34 @@ impl<T, D: Dims<T>> Array<T, D> {
     }
 
 
+    #[inline]
+    pub fn dims(&self) -> D {
+        self.dims
+    }
+
+    /// The elements of this array in row-major order; the last index varies fastest.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+
+    /// Builds an array from its elements in row-major order.
+    pub fn from_data(dims: D, data: Data<T, D::Product>) -> Self {
+        assert_eq!(data.len(), dims.total());
+
+        Array {
+            dims: dims,
+            data: data,
+        }
+    }
+
+
     pub fn from_elem(dims: D, elem: &T) -> Self
         where T: Clone
     {
@@ -293,3 +421,22 @@ impl<T, D: Dims<T>> Array<T, D> {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typehack::binary::*;
+
+    #[test]
+    fn array_display() {
+        let a = Array::from_fn(dims![B3], |idx| idx.elem);
+        assert_eq!(format!("{}", a), "[0, 1, 2]");
+
+        let b = Array::from_fn(dims![B2, B2], |idx| idx.elem * 10 + idx.next.elem);
+        assert_eq!(format!("{}", b), "[ 0   1]\n[10  11]");
+
+        let c = Array::from_fn(dims![B2, B1, B2], |idx| idx.elem + idx.next.next.elem);
+        assert_eq!(format!("{}", c), "[0, :, :] =\n[0  1]\n\n[1, :, :] =\n[1  2]");
+    }
+}
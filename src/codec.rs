@@ -0,0 +1,293 @@
+//! A small, dependency-free binary encoding for the crate's data types, suitable for snapshots on
+//! disk or messages over local IPC.
+//!
+//! The format is stable and always little-endian, regardless of the host:
+//!
+//! * Integers are written in their two's complement representation, at their own width.
+//! * Floats are written as their IEEE 754 bit patterns, at their own width.
+//! * Lengths and dimensions are written as `u64`s.
+//! * `Data` and `Vec` are written as a length followed by their elements.
+//! * Matrices are written as their row count and column count followed by their elements in
+//!   row-major order, whatever their layout.
+//!
+//! When decoding, every dimension header is checked against the dimension being decoded into. A
+//! header of `4` will decode as a `Dyn`, or as a `B4`, but decoding it as a `B3` is an error.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use iter_exact::CollectExactExt;
+
+use typehack::data::*;
+use typehack::dim::*;
+
+
+pub trait Encode {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+
+pub trait Decode: Sized {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError>;
+}
+
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Io(io::Error),
+    /// A dimension header did not match the static dimension being decoded into.
+    DimensionMismatch(usize),
+    /// A length or dimension header is too large to be addressed on this platform.
+    LengthOverflow(u64),
+}
+
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::Io(ref err) => write!(f, "{}", err),
+            DecodeError::DimensionMismatch(n) => {
+                write!(f, "encoded dimension {} does not match the expected dimension", n)
+            }
+            DecodeError::LengthOverflow(n) => write!(f, "encoded length {} is too large", n),
+        }
+    }
+}
+
+
+impl Error for DecodeError {
+    fn description(&self) -> &str {
+        match *self {
+            DecodeError::Io(ref err) => err.description(),
+            DecodeError::DimensionMismatch(_) => "dimension mismatch",
+            DecodeError::LengthOverflow(_) => "length overflow",
+        }
+    }
+}
+
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> DecodeError {
+        DecodeError::Io(err)
+    }
+}
+
+
+fn write_bits<W: Write>(bits: u64, size: usize, writer: &mut W) -> io::Result<()> {
+    let mut bytes = [0; 8];
+
+    for (k, byte) in bytes.iter_mut().enumerate() {
+        *byte = (bits >> (8 * k)) as u8;
+    }
+
+    writer.write_all(&bytes[..size])
+}
+
+
+fn read_bits<R: Read>(size: usize, reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes[..size])?;
+
+    Ok(bytes.iter().rev().fold(0, |acc, &b| acc << 8 | b as u64))
+}
+
+
+macro_rules! impl_codec_integral {
+    ($($t:ident as $u:ident),*) => {
+        $(impl Encode for $t {
+            fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                write_bits(*self as $u as u64, ::std::mem::size_of::<$t>(), writer)
+            }
+        }
+
+        impl Decode for $t {
+            fn decode<R: Read>(reader: &mut R) -> Result<$t, DecodeError> {
+                Ok(read_bits(::std::mem::size_of::<$t>(), reader)? as $u as $t)
+            }
+        })*
+    }
+}
+
+
+macro_rules! impl_codec_float {
+    ($($t:ident as $u:ident),*) => {
+        $(impl Encode for $t {
+            fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                write_bits(self.to_bits() as u64, ::std::mem::size_of::<$t>(), writer)
+            }
+        }
+
+        impl Decode for $t {
+            fn decode<R: Read>(reader: &mut R) -> Result<$t, DecodeError> {
+                Ok($t::from_bits(read_bits(::std::mem::size_of::<$t>(), reader)? as $u))
+            }
+        })*
+    }
+}
+
+
+impl_codec_integral!(u8 as u8, u16 as u16, u32 as u32, u64 as u64,
+                     i8 as u8, i16 as u16, i32 as u32, i64 as u64);
+impl_codec_float!(f32 as u32, f64 as u64);
+
+
+/// Writes a length or dimension header.
+pub fn encode_len<W: Write>(len: usize, writer: &mut W) -> io::Result<()> {
+    (len as u64).encode(writer)
+}
+
+
+/// Reads a length or dimension header.
+pub fn decode_len<R: Read>(reader: &mut R) -> Result<usize, DecodeError> {
+    let len = u64::decode(reader)?;
+
+    if len > usize::max_value() as u64 {
+        Err(DecodeError::LengthOverflow(len))
+    } else {
+        Ok(len as usize)
+    }
+}
+
+
+/// Writes a dimension header.
+pub fn encode_dim<D: Dim, W: Write>(dim: D, writer: &mut W) -> io::Result<()> {
+    encode_len(dim.reify(), writer)
+}
+
+
+/// Reads a dimension header, checking it against `D`.
+pub fn decode_dim<D: Dim, R: Read>(reader: &mut R) -> Result<D, DecodeError> {
+    let n = decode_len(reader)?;
+
+    if D::compatible(n) {
+        Ok(D::from_usize(n))
+    } else {
+        Err(DecodeError::DimensionMismatch(n))
+    }
+}
+
+
+/// Reads `size` consecutive elements, without a header.
+pub fn decode_elems<T: Decode, N: Dim, R: Read>(size: N,
+                                                reader: &mut R)
+                                                -> Result<Data<T, N>, DecodeError> {
+    let mut elems = Vec::new();
+
+    for _ in 0..size.reify() {
+        elems.push(T::decode(reader)?);
+    }
+
+    Ok(elems.into_iter().collect_exact())
+}
+
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        encode_len(self.len(), writer)?;
+
+        for elem in self {
+            elem.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Vec<T>, DecodeError> {
+        let len = decode_len(reader)?;
+
+        // The length comes from the input, so don't trust it with an allocation up front.
+        let mut elems = Vec::new();
+
+        for _ in 0..len {
+            elems.push(T::decode(reader)?);
+        }
+
+        Ok(elems)
+    }
+}
+
+
+impl<T: Encode, N: Dim> Encode for Data<T, N> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        encode_dim(self.size(), writer)?;
+
+        for elem in self.iter() {
+            elem.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl<T: Decode, N: Dim> Decode for Data<T, N> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Data<T, N>, DecodeError> {
+        let size: N = decode_dim(reader)?;
+        decode_elems(size, reader)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typehack::binary::*;
+
+    fn round_trip<T: Encode + Decode>(x: &T) -> T {
+        let mut bytes = Vec::new();
+        x.encode(&mut bytes).unwrap();
+
+        let mut reader = &bytes[..];
+        let y = T::decode(&mut reader).unwrap();
+        assert!(reader.is_empty());
+
+        y
+    }
+
+    #[test]
+    fn codec_scalars_are_little_endian() {
+        let mut bytes = Vec::new();
+        0x0102i16.encode(&mut bytes).unwrap();
+        (-2i32).encode(&mut bytes).unwrap();
+        1f32.encode(&mut bytes).unwrap();
+
+        assert_eq!(bytes, [0x02, 0x01, 0xfe, 0xff, 0xff, 0xff, 0x00, 0x00, 0x80, 0x3f]);
+
+        assert_eq!(round_trip(&i64::min_value()), i64::min_value());
+        assert_eq!(round_trip(&-0.1f64), -0.1f64);
+        assert!(round_trip(&::std::f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn codec_data_header() {
+        let data: Data<u8, B3> = Data::from_slice(B3::as_data(), &[7, 8, 9]);
+
+        let mut bytes = Vec::new();
+        data.encode(&mut bytes).unwrap();
+        assert_eq!(bytes, [3, 0, 0, 0, 0, 0, 0, 0, 7, 8, 9]);
+
+        assert_eq!(round_trip(&data), data);
+
+        let dynamic: Data<u8, Dyn> = Data::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(&dynamic[..], &[7, 8, 9]);
+
+        match Data::<u8, B4>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(3)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn codec_truncated_input() {
+        let bytes = [2, 0, 0, 0, 0, 0, 0, 0, 1, 0];
+
+        match Vec::<u16>::decode(&mut &bytes[..]) {
+            Err(DecodeError::Io(ref err)) if err.kind() == io::ErrorKind::UnexpectedEof => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use std::cell::RefCell;
+use std::io::{self, Write};
 use std::mem;
 use std::rc::{Rc, Weak};
 
 use iter_exact::{ChainExactExt, CollectExactExt};
 
+use codec::Encode;
 use geometry::primitive::{Facet, Plane, Point, SimplexSubset};
 use linalg::{Scalar, Vect, VectorNorm};
 use num::traits::Float;
@@ -51,6 +53,15 @@ impl<'a, T: Clone + Scalar, D: Dim> ConvexHull<'a, T, D> {
 }
 
 
+/// Encodes the hull as the `Vec` of its facets, as given by `cloned_facets`. A hull borrows the
+/// points it was computed from, so the encoding is instead decoded as a `Vec<Facet<T, D>>`.
+impl<'a, T: Clone + Scalar + Encode, D: Dim> Encode for ConvexHull<'a, T, D> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.cloned_facets().encode(writer)
+    }
+}
+
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct PointIdx(pub usize);
 
@@ -645,6 +656,20 @@ mod tests {
         let _ = triangle.quick_hull(B2::as_data());
     }
 
+    #[test]
+    fn qhull_2d_codec_facets() {
+        use codec::Decode;
+
+        let points = vec![Point![0., -2.], Point![-1., 3.], Point![-2., 0.], Point![2., 2.]];
+        let hull = points.quick_hull(B2::as_data());
+
+        let mut bytes = Vec::new();
+        hull.encode(&mut bytes).unwrap();
+
+        let facets: Vec<Facet<f64, B2>> = Vec::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(facets, hull.cloned_facets());
+    }
+
     #[test]
     fn qhull_2d_trivial_nondegenerate_2() {
         let _ = env_logger::init();
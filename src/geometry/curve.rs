@@ -0,0 +1,871 @@
+//! Parametric curves over `Point`s: Bezier curves, cubic Hermite segments, Catmull-Rom splines
+//! and B-splines, all of arbitrary dimension.
+//!
+//! Every curve implements `Curve`, which provides evaluation, the first derivative, a bounding
+//! `Cuboid` and arc length. `ArcLength` builds on the latter to reparameterize a curve by distance
+//! travelled along it, which is what you want when moving something along a curve at a constant
+//! speed.
+
+use geometry::primitive::Point;
+use geometry::shape::Cuboid;
+use linalg::{Scalar, Vect, VectorNorm};
+use num::traits::{Float, One, Zero};
+use typehack::dim::Dim;
+
+
+/// The number of pieces `Curve::arc_length` splits its interval into by default.
+const ARC_LENGTH_PIECES: usize = 16;
+
+/// The number of pieces each segment of a piecewise curve is split into when finding its length.
+const SEGMENT_ARC_LENGTH_PIECES: usize = 4;
+
+/// The most iterations `ArcLength::parameter` will take to invert the arc length function.
+const ARC_LENGTH_MAX_ITERATIONS: usize = 32;
+
+
+/// A parametric curve, which maps each value of its parameter in `domain()` to a point.
+pub trait Curve {
+    type Scalar: Scalar + Float;
+    type Dims: Dim;
+
+    fn dims(&self) -> Self::Dims;
+
+    /// The range `(start, end)` of parameters over which the curve is defined.
+    fn domain(&self) -> (Self::Scalar, Self::Scalar);
+
+    fn point(&self, t: Self::Scalar) -> Point<Self::Scalar, Self::Dims>;
+
+    /// The derivative of the curve with respect to its parameter, which is tangent to the curve.
+    fn derivative(&self, t: Self::Scalar) -> Vect<Self::Scalar, Self::Dims>;
+
+    /// A `Cuboid` containing the whole curve. It is not necessarily the smallest such `Cuboid`.
+    fn bounding_cuboid(&self) -> Cuboid<Self::Scalar, Self::Dims>;
+
+    /// The length of the curve between the parameters `from` and `to`, found by Gauss-Legendre
+    /// quadrature of the norm of the derivative. Negative if `to` is less than `from`.
+    fn arc_length(&self, from: Self::Scalar, to: Self::Scalar) -> Self::Scalar {
+        gauss_legendre(|t| self.derivative(t).norm(), from, to, ARC_LENGTH_PIECES)
+    }
+}
+
+
+/// Integrates `f` from `from` to `to` using the five-point Gauss-Legendre rule on each of `pieces`
+/// equal subintervals.
+fn gauss_legendre<T: Scalar + Float, F: Fn(T) -> T>(f: F, from: T, to: T, pieces: usize) -> T {
+    let nodes = [(0., 0.5688888888888889),
+                 (-0.5384693101056831, 0.4786286704993665),
+                 (0.5384693101056831, 0.4786286704993665),
+                 (-0.9061798459386640, 0.2369268850561891),
+                 (0.9061798459386640, 0.2369268850561891)];
+
+    let two = T::one() + T::one();
+    let width = (to - from.clone()) / T::from_usize(pieces);
+    let half = width.clone() / two.clone();
+
+    (0..pieces)
+        .map(|i| {
+            let mid = from.clone() + width.clone() * T::from_usize(i) + half.clone();
+            nodes.iter()
+                .map(|&(x, w)| T::from_f64(w) * f(mid.clone() + half.clone() * T::from_f64(x)))
+                .sum::<T>() * half.clone()
+        })
+        .sum()
+}
+
+
+/// The arc length of a piecewise curve with segments joined at the sorted parameters `breaks`,
+/// integrated segment by segment so that no quadrature rule straddles a join.
+fn piecewise_arc_length<T, F>(breaks: &[T], from: T, to: T, speed: F) -> T
+    where T: Scalar + Float,
+          F: Fn(T) -> T
+{
+    if to < from {
+        return -piecewise_arc_length(breaks, to, from, speed);
+    }
+
+    breaks.windows(2)
+        .map(|pair| {
+            let lo = if pair[0] > from { pair[0].clone() } else { from.clone() };
+            let hi = if pair[1] < to { pair[1].clone() } else { to.clone() };
+
+            if lo < hi {
+                gauss_legendre(&speed, lo, hi, SEGMENT_ARC_LENGTH_PIECES)
+            } else {
+                T::zero()
+            }
+        })
+        .sum()
+}
+
+
+/// The index `i` of the interval `breaks[i]..breaks[i + 1]` containing `t`, clamped to the first
+/// and last intervals. `breaks` must be sorted and hold at least two elements.
+fn interval<T: PartialOrd>(breaks: &[T], t: &T) -> usize {
+    let (mut lo, mut hi) = (0, breaks.len() - 1);
+
+    while hi - lo > 1 {
+        let mid = (lo + hi) / 2;
+
+        if *t < breaks[mid] {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    lo
+}
+
+
+/// A Bezier curve of any degree, defined over the parameters `0` to `1`. It starts at its first
+/// control point, ends at its last, and lies inside the convex hull of all of them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bezier<T: Scalar, D: Dim> {
+    dims: D,
+    points: Vec<Point<T, D>>,
+}
+
+
+impl<T: Scalar + Float, D: Dim> Bezier<T, D> {
+    /// Creates a Bezier curve of degree `points.len() - 1`. Panics if `points` is empty.
+    pub fn new(dims: D, points: Vec<Point<T, D>>) -> Self {
+        assert!(!points.is_empty(), "A Bezier curve needs at least one control point!");
+
+        Bezier {
+            dims: dims,
+            points: points,
+        }
+    }
+
+
+    pub fn degree(&self) -> usize {
+        self.points.len() - 1
+    }
+
+
+    pub fn control_points(&self) -> &[Point<T, D>] {
+        &self.points
+    }
+
+
+    /// Splits the curve at `t` using de Casteljau's algorithm, returning the parts of the curve
+    /// before and after `t`, each reparameterized over `0` to `1`.
+    pub fn subdivide(&self, t: T) -> (Self, Self) {
+        let n = self.points.len();
+        let mut work: Vec<Vect<T, D>> = self.points.iter().cloned().map(Vect::from).collect();
+        let mut left = Vec::with_capacity(n);
+        let mut right = Vec::with_capacity(n);
+
+        left.push(work[0].clone().into());
+        right.push(work[n - 1].clone().into());
+
+        for k in 1..n {
+            for i in 0..n - k {
+                let next = work[i].lerp(&work[i + 1], t.clone());
+                work[i] = next;
+            }
+
+            left.push(work[0].clone().into());
+            right.push(work[n - k - 1].clone().into());
+        }
+
+        right.reverse();
+
+        (Bezier::new(self.dims, left), Bezier::new(self.dims, right))
+    }
+
+
+    /// The hodograph of the curve: the Bezier curve of one degree lower tracing out its
+    /// derivative, with the control points standing in for vectors. The hodograph of a single
+    /// point is the single point at the origin.
+    pub fn hodograph(&self) -> Self {
+        if self.points.len() == 1 {
+            return Bezier::new(self.dims, vec![Point::from_elem(self.dims, &T::zero())]);
+        }
+
+        let degree = T::from_usize(self.degree());
+
+        Bezier::new(self.dims,
+                    self.points
+                        .windows(2)
+                        .map(|pair| ((&pair[1] - &pair[0]) * degree.clone()).into())
+                        .collect())
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Curve for Bezier<T, D> {
+    type Scalar = T;
+    type Dims = D;
+
+    fn dims(&self) -> D {
+        self.dims
+    }
+
+
+    fn domain(&self) -> (T, T) {
+        (T::zero(), T::one())
+    }
+
+
+    fn point(&self, t: T) -> Point<T, D> {
+        let n = self.points.len();
+        let mut work: Vec<Vect<T, D>> = self.points.iter().cloned().map(Vect::from).collect();
+
+        for k in 1..n {
+            for i in 0..n - k {
+                let next = work[i].lerp(&work[i + 1], t.clone());
+                work[i] = next;
+            }
+        }
+
+        work.swap_remove(0).into()
+    }
+
+
+    fn derivative(&self, t: T) -> Vect<T, D> {
+        self.hodograph().point(t).into()
+    }
+
+
+    fn bounding_cuboid(&self) -> Cuboid<T, D> {
+        Cuboid::from_points(&self.points).unwrap()
+    }
+}
+
+
+/// A cubic Hermite curve, defined over the parameters `0` to `1` by its endpoints and the
+/// derivatives of the curve at them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Hermite<T: Scalar, D: Dim> {
+    pub start: Point<T, D>,
+    pub start_tangent: Vect<T, D>,
+    pub end: Point<T, D>,
+    pub end_tangent: Vect<T, D>,
+}
+
+
+impl<T: Scalar + Float, D: Dim> Hermite<T, D> {
+    pub fn new(start: Point<T, D>,
+               start_tangent: Vect<T, D>,
+               end: Point<T, D>,
+               end_tangent: Vect<T, D>)
+               -> Self {
+        Hermite {
+            start: start,
+            start_tangent: start_tangent,
+            end: end,
+            end_tangent: end_tangent,
+        }
+    }
+
+
+    /// The same curve as a cubic Bezier curve.
+    pub fn to_bezier(&self) -> Bezier<T, D> {
+        let three = T::from_usize(3);
+
+        Bezier::new(self.start.size(),
+                    vec![self.start.clone(),
+                         &self.start + &(&self.start_tangent / three.clone()),
+                         self.end.clone() - &self.end_tangent / three,
+                         self.end.clone()])
+    }
+
+
+    /// Sums the endpoints and tangents weighted by the given basis functions' values.
+    fn combine(&self, h00: T, h10: T, h01: T, h11: T) -> Vect<T, D> {
+        Vect::from(self.start.clone()) * h00 + &self.start_tangent * h10 +
+        Vect::from(self.end.clone()) * h01 + &self.end_tangent * h11
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Curve for Hermite<T, D> {
+    type Scalar = T;
+    type Dims = D;
+
+    fn dims(&self) -> D {
+        self.start.size()
+    }
+
+
+    fn domain(&self) -> (T, T) {
+        (T::zero(), T::one())
+    }
+
+
+    fn point(&self, t: T) -> Point<T, D> {
+        let (one, two, three) = (T::one(), T::from_usize(2), T::from_usize(3));
+        let t2 = t.clone() * t.clone();
+        let t3 = t2.clone() * t.clone();
+
+        self.combine(two.clone() * t3.clone() - three.clone() * t2.clone() + one,
+                     t3.clone() - two.clone() * t2.clone() + t,
+                     three * t2.clone() - two * t3.clone(),
+                     t3 - t2)
+            .into()
+    }
+
+
+    fn derivative(&self, t: T) -> Vect<T, D> {
+        let (one, two, three, four, six) =
+            (T::one(), T::from_usize(2), T::from_usize(3), T::from_usize(4), T::from_usize(6));
+        let t2 = t.clone() * t.clone();
+
+        self.combine(six.clone() * t2.clone() - six.clone() * t.clone(),
+                     three.clone() * t2.clone() - four * t.clone() + one,
+                     six.clone() * t.clone() - six * t2.clone(),
+                     three * t2 - two * t)
+    }
+
+
+    fn bounding_cuboid(&self) -> Cuboid<T, D> {
+        self.to_bezier().bounding_cuboid()
+    }
+}
+
+
+/// How the knots of a Catmull-Rom spline are spaced. `Uniform` knots are evenly spaced, while
+/// `Centripetal` and `Chordal` knots are spaced by the square root of the distance and the distance
+/// between consecutive control points respectively. Centripetal Catmull-Rom splines never
+/// form cusps or self-intersections within a segment, and so are usually the best choice.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Parameterization {
+    Uniform,
+    Centripetal,
+    Chordal,
+}
+
+
+/// A Catmull-Rom spline, passing through every control point but the first and last, which only
+/// shape the ends of the curve. The curve is parameterized by its knots, so that it is at
+/// `points[i]` when its parameter is `knots()[i]`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CatmullRom<T: Scalar, D: Dim> {
+    dims: D,
+    points: Vec<Point<T, D>>,
+    knots: Vec<T>,
+}
+
+
+impl<T: Scalar + Float, D: Dim> CatmullRom<T, D> {
+    /// Creates a Catmull-Rom spline through `points`. Panics if there are fewer than four points.
+    /// Under centripetal or chordal spacing, consecutive points must also be distinct.
+    pub fn new(dims: D, points: Vec<Point<T, D>>, parameterization: Parameterization) -> Self {
+        assert!(points.len() >= 4,
+                "A Catmull-Rom spline needs at least four control points!");
+
+        let mut knots = Vec::with_capacity(points.len());
+        knots.push(T::zero());
+
+        for pair in points.windows(2) {
+            let distance = (&pair[1] - &pair[0]).norm();
+            let step = match parameterization {
+                Parameterization::Uniform => T::one(),
+                Parameterization::Centripetal => distance.sqrt(),
+                Parameterization::Chordal => distance,
+            };
+            let last = knots[knots.len() - 1].clone();
+            knots.push(last + step);
+        }
+
+        CatmullRom {
+            dims: dims,
+            points: points,
+            knots: knots,
+        }
+    }
+
+
+    pub fn control_points(&self) -> &[Point<T, D>] {
+        &self.points
+    }
+
+
+    pub fn knots(&self) -> &[T] {
+        &self.knots
+    }
+
+
+    /// The number of segments, each of which runs between two consecutive interior points.
+    pub fn segments(&self) -> usize {
+        self.points.len() - 3
+    }
+
+
+    /// The `i`th segment, from `points[i + 1]` to `points[i + 2]`, as a Hermite curve over the
+    /// parameters `0` to `1`.
+    pub fn segment(&self, i: usize) -> Hermite<T, D> {
+        let p = &self.points[i..i + 4];
+        let t = &self.knots[i..i + 4];
+        let span = t[2].clone() - t[1].clone();
+
+        // The tangent of a non-uniform Catmull-Rom spline at a point, with respect to its knots,
+        // given the neighbouring points `a`, `b`, `c` and their knots `s`.
+        let tangent = |a: &Point<T, D>, b: &Point<T, D>, c: &Point<T, D>, s: &[T]| {
+            (b - a) / (s[1].clone() - s[0].clone()) - (c - a) / (s[2].clone() - s[0].clone()) +
+            (c - b) / (s[2].clone() - s[1].clone())
+        };
+
+        Hermite::new(p[1].clone(),
+                     tangent(&p[0], &p[1], &p[2], &t[0..3]) * span.clone(),
+                     p[2].clone(),
+                     tangent(&p[1], &p[2], &p[3], &t[1..4]) * span)
+    }
+
+
+    /// The segment containing the parameter `t`, and `t` relative to it.
+    fn locate(&self, t: T) -> (Hermite<T, D>, T, T) {
+        let i = interval(&self.knots[1..self.knots.len() - 1], &t);
+        let span = self.knots[i + 2].clone() - self.knots[i + 1].clone();
+        let u = (t - self.knots[i + 1].clone()) / span.clone();
+
+        (self.segment(i), u, span)
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Curve for CatmullRom<T, D> {
+    type Scalar = T;
+    type Dims = D;
+
+    fn dims(&self) -> D {
+        self.dims
+    }
+
+
+    fn domain(&self) -> (T, T) {
+        (self.knots[1].clone(), self.knots[self.knots.len() - 2].clone())
+    }
+
+
+    fn point(&self, t: T) -> Point<T, D> {
+        let (segment, u, _) = self.locate(t);
+        segment.point(u)
+    }
+
+
+    fn derivative(&self, t: T) -> Vect<T, D> {
+        let (segment, u, span) = self.locate(t);
+        segment.derivative(u) / span
+    }
+
+
+    fn bounding_cuboid(&self) -> Cuboid<T, D> {
+        (1..self.segments())
+            .fold(self.segment(0).bounding_cuboid(),
+                  |cuboid, i| cuboid.union(&self.segment(i).bounding_cuboid()))
+    }
+
+
+    fn arc_length(&self, from: T, to: T) -> T {
+        piecewise_arc_length(&self.knots[1..self.knots.len() - 1],
+                             from,
+                             to,
+                             |t| self.derivative(t).norm())
+    }
+}
+
+
+/// A B-spline of any degree with an arbitrary non-decreasing knot vector. The curve lies inside
+/// the convex hull of its control points, and moving a control point only changes the curve
+/// nearby.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BSpline<T: Scalar, D: Dim> {
+    dims: D,
+    degree: usize,
+    points: Vec<Point<T, D>>,
+    knots: Vec<T>,
+}
+
+
+impl<T: Scalar + Float, D: Dim> BSpline<T, D> {
+    /// Creates a B-spline, returning `None` unless there are more control points than the degree,
+    /// exactly `points.len() + degree + 1` knots, the knots are non-decreasing, and the domain
+    /// from `knots[degree]` to `knots[points.len()]` is not empty.
+    pub fn new(dims: D, degree: usize, points: Vec<Point<T, D>>, knots: Vec<T>) -> Option<Self> {
+        let n = points.len();
+
+        if n <= degree || knots.len() != n + degree + 1 ||
+           knots.windows(2).any(|pair| pair[1] < pair[0]) || knots[n] <= knots[degree] {
+            return None;
+        }
+
+        Some(BSpline {
+            dims: dims,
+            degree: degree,
+            points: points,
+            knots: knots,
+        })
+    }
+
+
+    /// Creates a uniform B-spline, with the knots `0, 1, 2, ...`. Panics if there are not more
+    /// control points than the degree.
+    pub fn uniform(dims: D, degree: usize, points: Vec<Point<T, D>>) -> Self {
+        let knots = (0..points.len() + degree + 1).map(T::from_usize).collect();
+        BSpline::new(dims, degree, points, knots)
+            .expect("A B-spline needs more control points than its degree!")
+    }
+
+
+    /// Creates a clamped uniform B-spline, whose end knots are repeated `degree + 1` times so that
+    /// the curve starts at its first control point and ends at its last. Panics if there are not
+    /// more control points than the degree.
+    pub fn clamped(dims: D, degree: usize, points: Vec<Point<T, D>>) -> Self {
+        let interior = points.len().saturating_sub(degree);
+        let knots = (0..points.len() + degree + 1)
+            .map(|i| {
+                if i <= degree {
+                    T::zero()
+                } else if i >= points.len() {
+                    T::from_usize(interior)
+                } else {
+                    T::from_usize(i - degree)
+                }
+            })
+            .collect();
+
+        BSpline::new(dims, degree, points, knots)
+            .expect("A B-spline needs more control points than its degree!")
+    }
+
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+
+    pub fn control_points(&self) -> &[Point<T, D>] {
+        &self.points
+    }
+
+
+    pub fn knots(&self) -> &[T] {
+        &self.knots
+    }
+
+
+    /// The B-spline of one degree lower tracing out the derivative of this one, with the control
+    /// points standing in for vectors. The hodograph of a B-spline of degree zero is zero.
+    pub fn hodograph(&self) -> Self {
+        if self.degree == 0 {
+            return BSpline {
+                dims: self.dims,
+                degree: 0,
+                points: vec![Point::from_elem(self.dims, &T::zero()); self.points.len()],
+                knots: self.knots.clone(),
+            };
+        }
+
+        let p = self.degree;
+        let degree = T::from_usize(p);
+        let points = (0..self.points.len() - 1)
+            .map(|i| {
+                let span = self.knots[i + p + 1].clone() - self.knots[i + 1].clone();
+
+                if span.eq_zero() {
+                    Point::from_elem(self.dims, &T::zero())
+                } else {
+                    ((&self.points[i + 1] - &self.points[i]) * (degree.clone() / span)).into()
+                }
+            })
+            .collect();
+
+        BSpline {
+            dims: self.dims,
+            degree: p - 1,
+            points: points,
+            knots: self.knots[1..self.knots.len() - 1].to_vec(),
+        }
+    }
+
+
+    /// The knots delimiting the polynomial pieces of the curve within its domain.
+    fn breaks(&self) -> &[T] {
+        &self.knots[self.degree..self.points.len() + 1]
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Curve for BSpline<T, D> {
+    type Scalar = T;
+    type Dims = D;
+
+    fn dims(&self) -> D {
+        self.dims
+    }
+
+
+    fn domain(&self) -> (T, T) {
+        (self.knots[self.degree].clone(), self.knots[self.points.len()].clone())
+    }
+
+
+    /// Evaluates the curve using de Boor's algorithm.
+    fn point(&self, t: T) -> Point<T, D> {
+        let p = self.degree;
+        let k = p + interval(self.breaks(), &t);
+        let mut work: Vec<Vect<T, D>> =
+            self.points[k - p..k + 1].iter().cloned().map(Vect::from).collect();
+
+        for r in 1..p + 1 {
+            for j in (r..p + 1).rev() {
+                let i = j + k - p;
+                let alpha = (t.clone() - self.knots[i].clone()) /
+                            (self.knots[i + p + 1 - r].clone() - self.knots[i].clone());
+                let next = work[j - 1].lerp(&work[j], alpha);
+                work[j] = next;
+            }
+        }
+
+        work.swap_remove(p).into()
+    }
+
+
+    fn derivative(&self, t: T) -> Vect<T, D> {
+        if self.degree == 0 {
+            return Vect::from_elem(self.dims, &T::zero());
+        }
+
+        self.hodograph().point(t).into()
+    }
+
+
+    fn bounding_cuboid(&self) -> Cuboid<T, D> {
+        Cuboid::from_points(&self.points).unwrap()
+    }
+
+
+    fn arc_length(&self, from: T, to: T) -> T {
+        let hodograph = self.hodograph();
+        piecewise_arc_length(self.breaks(),
+                             from,
+                             to,
+                             |t| Vect::from(hodograph.point(t)).norm())
+    }
+}
+
+
+/// An arc length parameterization of a curve, mapping distances along the curve to the curve's
+/// own parameters. The arc length is tabulated at evenly spaced parameters when the
+/// parameterization is created, and inverted between them with safeguarded Newton iterations.
+pub struct ArcLength<'a, C: Curve + 'a> {
+    curve: &'a C,
+    params: Vec<C::Scalar>,
+    lengths: Vec<C::Scalar>,
+}
+
+
+impl<'a, C: Curve> ArcLength<'a, C> {
+    /// Tabulates the arc length of `curve` at `samples + 1` evenly spaced parameters. More samples
+    /// cost more up front but make finding parameters cheaper. Panics if `samples` is zero.
+    pub fn new(curve: &'a C, samples: usize) -> Self {
+        assert!(samples > 0, "An arc length table needs at least one sample!");
+
+        let (start, end) = curve.domain();
+        let step = (end.clone() - start.clone()) / C::Scalar::from_usize(samples);
+        let params: Vec<C::Scalar> = (0..samples + 1)
+            .map(|i| if i == samples {
+                end.clone()
+            } else {
+                start.clone() + step.clone() * C::Scalar::from_usize(i)
+            })
+            .collect();
+
+        let mut lengths = Vec::with_capacity(params.len());
+        lengths.push(C::Scalar::zero());
+
+        for pair in params.windows(2) {
+            let last = lengths[lengths.len() - 1].clone();
+            lengths.push(last + curve.arc_length(pair[0].clone(), pair[1].clone()));
+        }
+
+        ArcLength {
+            curve: curve,
+            params: params,
+            lengths: lengths,
+        }
+    }
+
+
+    pub fn curve(&self) -> &'a C {
+        self.curve
+    }
+
+
+    /// The length of the whole curve.
+    pub fn length(&self) -> C::Scalar {
+        self.lengths[self.lengths.len() - 1].clone()
+    }
+
+
+    /// The parameter of the point at distance `s` along the curve from its start. Distances
+    /// outside of the curve are clamped to its ends.
+    pub fn parameter(&self, s: C::Scalar) -> C::Scalar {
+        let total = self.length();
+
+        if s <= C::Scalar::zero() {
+            return self.params[0].clone();
+        } else if s >= total {
+            return self.params[self.params.len() - 1].clone();
+        }
+
+        let i = interval(&self.lengths, &s);
+        let start = self.params[i].clone();
+        let target = s - self.lengths[i].clone();
+        let tolerance = C::Scalar::epsilon().sqrt() * total;
+
+        let (mut lo, mut hi) = (start.clone(), self.params[i + 1].clone());
+        let mut t = lo.clone() +
+                    (hi.clone() - lo.clone()) * target.clone() /
+                    (self.lengths[i + 1].clone() - self.lengths[i].clone());
+
+        for _ in 0..ARC_LENGTH_MAX_ITERATIONS {
+            let error = self.curve.arc_length(start.clone(), t.clone()) - target.clone();
+
+            if error.abs() <= tolerance {
+                break;
+            }
+
+            if error > C::Scalar::zero() {
+                hi = t.clone();
+            } else {
+                lo = t.clone();
+            }
+
+            // Take a Newton step if it stays within the bracket, and bisect otherwise.
+            let speed = self.curve.derivative(t.clone()).norm();
+            let newton = t - error / speed.clone();
+
+            t = if speed > C::Scalar::zero() && lo < newton && newton < hi {
+                newton
+            } else {
+                (lo.clone() + hi.clone()) / (C::Scalar::one() + C::Scalar::one())
+            };
+        }
+
+        t
+    }
+
+
+    /// The point at distance `s` along the curve from its start.
+    pub fn point(&self, s: C::Scalar) -> Point<C::Scalar, C::Dims> {
+        self.curve.point(self.parameter(s))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typehack::binary::*;
+
+    fn assert_close(p: Point<f64, B2>, q: Point<f64, B2>) {
+        assert!((&p - &q).norm() < 1e-9, "{:?} != {:?}", p, q);
+    }
+
+    fn assert_derivative<C: Curve<Scalar = f64, Dims = B2>>(curve: &C, t: f64) {
+        let h = 1e-6;
+        let estimate = (curve.point(t + h) - curve.point(t - h)) / (2. * h);
+        assert!((curve.derivative(t) - estimate).norm() < 1e-6);
+    }
+
+    #[test]
+    fn bezier_evaluation_and_subdivision() {
+        let points = vec![Point![0., 0.], Point![1., 2.], Point![3., 2.], Point![4., 0.]];
+        let curve = Bezier::new(B2::as_data(), points);
+
+        assert_close(curve.point(0.), Point![0., 0.]);
+        assert_close(curve.point(1.), Point![4., 0.]);
+        assert_close(curve.point(0.5), Point![2., 1.5]);
+
+        let (left, right) = curve.subdivide(0.25);
+        assert_close(left.point(0.5), curve.point(0.125));
+        assert_close(right.point(0.5), curve.point(0.625));
+
+        assert_derivative(&curve, 0.3);
+
+        let bounds = curve.bounding_cuboid();
+        assert!((0..11).all(|i| bounds.contains(&curve.point(i as f64 / 10.))));
+    }
+
+    #[test]
+    fn hermite_matches_its_bezier() {
+        let curve = Hermite::new(Point![0., 0.], Vect![3., 0.], Point![1., 1.], Vect![0., 3.]);
+        let bezier = curve.to_bezier();
+
+        assert_eq!(bezier.control_points()[1], Point![1., 0.]);
+        assert_eq!(bezier.control_points()[2], Point![1., 0.]);
+
+        for i in 0..11 {
+            let t = i as f64 / 10.;
+            assert_close(curve.point(t), bezier.point(t));
+            assert_close(curve.derivative(t).into(), bezier.derivative(t).into());
+        }
+    }
+
+    #[test]
+    fn catmull_rom_interpolates() {
+        let points = vec![Point![0., 0.], Point![1., 1.], Point![3., 1.], Point![4., 3.],
+                          Point![6., 3.]];
+
+        for &parameterization in &[Parameterization::Uniform,
+                                   Parameterization::Centripetal,
+                                   Parameterization::Chordal] {
+            let curve = CatmullRom::new(B2::as_data(), points.clone(), parameterization);
+
+            for i in 1..4 {
+                assert_close(curve.point(curve.knots()[i]), points[i].clone());
+            }
+
+            let (start, end) = curve.domain();
+            let t = (start + end) / 2.;
+            assert_derivative(&curve, t);
+        }
+
+        let uniform = CatmullRom::new(B2::as_data(), points.clone(), Parameterization::Uniform);
+        assert_close(uniform.derivative(2.).into(), Point![1.5, 1.]);
+    }
+
+    #[test]
+    fn b_spline_evaluation() {
+        let points = vec![Point![0., 0.], Point![1., 2.], Point![2., -1.], Point![4., 1.],
+                          Point![5., 0.]];
+
+        let clamped = BSpline::clamped(B2::as_data(), 3, points.clone());
+        assert_eq!(clamped.domain(), (0., 2.));
+        assert_close(clamped.point(0.), points[0].clone());
+        assert_close(clamped.point(2.), points[4].clone());
+
+        for &t in &[0.3, 1., 1.7] {
+            assert_derivative(&clamped, t);
+        }
+
+        // A uniform B-spline over evenly spaced collinear points moves at a constant speed.
+        let line = BSpline::uniform(B2::as_data(),
+                                    2,
+                                    (0..5).map(|i| Point![i as f64, 0.]).collect());
+        assert_eq!(line.domain(), (2., 5.));
+        assert_close(line.point(3.5), Point![2., 0.]);
+        assert_close(line.derivative(2.7).into(), Point![1., 0.]);
+        assert!((line.arc_length(2., 5.) - 3.).abs() < 1e-9);
+
+        assert!(BSpline::new(B2::as_data(), 3, points, vec![0.; 8]).is_none());
+    }
+
+    #[test]
+    fn arc_length_parameterization() {
+        // A straight line which speeds up along its length.
+        let curve = Bezier::new(B2::as_data(),
+                                vec![Point![0., 0.], Point![0.3, 0.4], Point![3., 4.]]);
+        let arc = ArcLength::new(&curve, 8);
+
+        assert!((arc.length() - 5.).abs() < 1e-9);
+
+        for &s in &[0., 0.7, 2.5, 4.9, 5.] {
+            let p = arc.point(s);
+            assert!((Vect::from(p).norm() - s).abs() < 1e-6);
+        }
+    }
+}
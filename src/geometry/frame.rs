@@ -0,0 +1,253 @@
+//! Coordinate frames, tracked in the type system.
+//!
+//! Dimensions are already checked at compile time; frames extend the same idea to *which* space a
+//! point or vector lives in. `Point` and `Vect` take an optional frame parameter, which defaults
+//! to `Unframed`. Points and vectors can only be combined within a single frame, and the only way
+//! to move between frames is a `Transform` whose source and destination frames match. Mixing
+//! frames is a compile error:
+//!
+//! ```compile_fail
+//! # extern crate leviathan;
+//! # use leviathan::geometry::frame::Frame;
+//! # use leviathan::geometry::primitive::Point;
+//! # use leviathan::linalg::Vect;
+//! # use leviathan::typehack::binary::B2;
+//! #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+//! struct World;
+//! impl Frame for World {}
+//!
+//! #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+//! struct Local;
+//! impl Frame for Local {}
+//!
+//! # fn main() {
+//! let p: Point<f64, B2, World> = Point::from(Vect::<f64, B2>::from(&[1., 2.][..])).in_frame();
+//! let v: Vect<f64, B2, Local> = Vect::<f64, B2>::from(&[0., 1.][..]).in_frame();
+//!
+//! let _ = p + v; // Error: `Local` is not `World`.
+//! # }
+//! ```
+//!
+//! A `Transform<T, N, Local, World>` moves `v` into `World`, after which `p + to_world * v` is
+//! fine.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::Mul;
+
+use geometry::primitive::Point;
+use geometry::transform::Affine;
+use linalg::{Scalar, Vect};
+use typehack::dim::*;
+
+
+/// A coordinate frame: a zero-sized marker type. Frames derive `Clone`, `Copy`, `PartialEq`, `Eq`
+/// and `Debug` so that framed points and vectors can too.
+pub trait Frame: Copy + Eq + Debug {}
+
+
+/// The default frame, for points and vectors whose frame is not tracked.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Unframed;
+
+impl Frame for Unframed {}
+
+
+/// An affine transformation taking points and vectors in the frame `From` to the frame `To`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Transform<T: Scalar, N: Dim, From: Frame, To: Frame> {
+    affine: Affine<T, N>,
+    frames: PhantomData<(From, To)>,
+}
+
+
+impl<T: Scalar, N: Dim, F: Frame> Transform<T, N, F, F> {
+    pub fn identity(dim: N) -> Self {
+        Transform::new(Affine::identity(dim))
+    }
+}
+
+
+impl<T: Scalar, N: Dim, From: Frame, To: Frame> Transform<T, N, From, To> {
+    /// Tags a transformation with the frames it maps between. Isometries and similarities convert
+    /// into affine transformations, and so may be used here too.
+    pub fn new<X: Into<Affine<T, N>>>(transform: X) -> Self {
+        Transform {
+            affine: transform.into(),
+            frames: PhantomData,
+        }
+    }
+
+
+    pub fn as_affine(&self) -> &Affine<T, N> {
+        &self.affine
+    }
+
+
+    pub fn into_affine(self) -> Affine<T, N> {
+        self.affine
+    }
+
+
+    /// Transforms a point in the source frame. Points in any other frame are rejected:
+    ///
+    /// ```compile_fail
+    /// # extern crate leviathan;
+    /// # use leviathan::geometry::frame::{Frame, Transform};
+    /// # use leviathan::geometry::primitive::Point;
+    /// # use leviathan::linalg::Vect;
+    /// # use leviathan::typehack::binary::{Nat, B2};
+    /// # #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    /// # struct World;
+    /// # impl Frame for World {}
+    /// # #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    /// # struct Local;
+    /// # impl Frame for Local {}
+    /// # fn main() {
+    /// let in_local: Transform<f64, B2, Local, Local> = Transform::identity(B2::as_data());
+    /// let p: Point<f64, B2, World> = Point::from(Vect::<f64, B2>::from(&[1., 2.][..])).in_frame();
+    ///
+    /// let _ = in_local.transform_point(&p); // Error: `p` is in `World`, not `Local`.
+    /// # }
+    /// ```
+    pub fn transform_point(&self, p: &Point<T, N, From>) -> Point<T, N, To> {
+        self.affine.transform_point(&p.clone().in_frame()).in_frame()
+    }
+
+
+    pub fn transform_vect(&self, v: &Vect<T, N, From>) -> Vect<T, N, To> {
+        self.affine.transform_vect(&v.clone().in_frame()).in_frame()
+    }
+
+
+    /// The transformation back from `To` to `From`, or `None` if this one is not invertible.
+    pub fn inverse(&self) -> Option<Transform<T, N, To, From>> {
+        match self.affine.inverse() {
+            Some(affine) => Some(Transform::new(affine)),
+            None => None,
+        }
+    }
+}
+
+
+/// Composition: `b * a` applies `a`, taking `A` to `B`, then `b`, taking `B` to `C`. Transforms
+/// which do not meet at a shared frame cannot be composed:
+///
+/// ```compile_fail
+/// # extern crate leviathan;
+/// # use leviathan::geometry::frame::{Frame, Transform};
+/// # use leviathan::typehack::binary::{Nat, B2};
+/// # #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// # struct World;
+/// # impl Frame for World {}
+/// # #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// # struct Sensor;
+/// # impl Frame for Sensor {}
+/// # fn main() {
+/// let in_world: Transform<f64, B2, World, World> = Transform::identity(B2::as_data());
+/// let in_sensor: Transform<f64, B2, Sensor, Sensor> = Transform::identity(B2::as_data());
+///
+/// let _ = in_world * in_sensor; // Error: `Sensor` is not `World`.
+/// # }
+/// ```
+impl<T: Scalar, N: Dim, A: Frame, B: Frame, C: Frame> Mul<Transform<T, N, A, B>>
+    for Transform<T, N, B, C>
+{
+    type Output = Transform<T, N, A, C>;
+
+    fn mul(self, rhs: Transform<T, N, A, B>) -> Transform<T, N, A, C> {
+        Transform::new(self.affine * rhs.affine)
+    }
+}
+
+
+impl<T: Scalar, N: Dim, From: Frame, To: Frame> Mul<Point<T, N, From>>
+    for Transform<T, N, From, To>
+{
+    type Output = Point<T, N, To>;
+
+    fn mul(self, rhs: Point<T, N, From>) -> Point<T, N, To> {
+        self.transform_point(&rhs)
+    }
+}
+
+
+impl<T: Scalar, N: Dim, From: Frame, To: Frame> Mul<Vect<T, N, From>>
+    for Transform<T, N, From, To>
+{
+    type Output = Vect<T, N, To>;
+
+    fn mul(self, rhs: Vect<T, N, From>) -> Vect<T, N, To> {
+        self.transform_vect(&rhs)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geometry::transform::Isometry;
+    use linalg::Rotation;
+    use typehack::binary::{B2, B3};
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    struct World;
+    impl Frame for World {}
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    struct Body;
+    impl Frame for Body {}
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    struct Sensor;
+    impl Frame for Sensor {}
+
+    fn shift(x: f64, y: f64) -> Affine<f64, B2> {
+        Isometry::from_translation(Vect![x, y]).into()
+    }
+
+    #[test]
+    fn transform_changes_frame() {
+        let body_to_world: Transform<f64, B2, Body, World> = Transform::new(shift(10., 0.));
+        let p: Point<f64, B2, Body> = Point::from(Vect![1., 2.]).in_frame();
+        let v: Vect<f64, B2, Body> = Vect![0., 1.].in_frame();
+
+        let q: Point<f64, B2, World> = body_to_world.transform_point(&p);
+        assert_eq!(q.clone().in_frame::<Unframed>(), Point::from(Vect![11., 2.]));
+        assert_eq!(body_to_world.transform_vect(&v).in_frame::<Unframed>(), Vect![0., 1.]);
+
+        // Points and vectors in the same frame combine as usual.
+        let r = q + body_to_world.clone() * v;
+        assert_eq!(r.in_frame::<Unframed>(), Point::from(Vect![11., 3.]));
+
+        let q = body_to_world.transform_point(&p);
+        let back = body_to_world.inverse().unwrap().transform_point(&q);
+        assert_eq!(back, p);
+    }
+
+    #[test]
+    fn transforms_compose_through_shared_frame() {
+        let sensor_to_body: Transform<f64, B2, Sensor, Body> =
+            Transform::new(Isometry::new(Rotation::from_angle(0.), Vect![0., 5.]));
+        let body_to_world: Transform<f64, B2, Body, World> = Transform::new(shift(10., 0.));
+
+        let sensor_to_world = body_to_world * sensor_to_body;
+        let p: Point<f64, B2, Sensor> = Point::from(Vect![1., 1.]).in_frame();
+
+        assert_eq!(sensor_to_world.transform_point(&p).in_frame::<Unframed>(),
+                   Point::from(Vect![11., 6.]));
+    }
+
+    #[test]
+    fn framed_vectors_keep_their_frame() {
+        let a: Vect<f64, B3, World> = Vect![3., 0., 0.].in_frame();
+        let b: Vect<f64, B3, World> = Vect![0., 4., 0.].in_frame();
+
+        let c: Vect<f64, B3, World> = a.cross(&b).normalize();
+        assert_eq!(c.in_frame::<Unframed>(), Vect![0., 0., 1.]);
+
+        let mid: Vect<f64, B2, World> = a.lerp(&b, 0.5).xy();
+        assert_eq!(mid.in_frame::<Unframed>(), Vect![1.5, 2.]);
+        assert_eq!(b.y, 4.);
+    }
+}
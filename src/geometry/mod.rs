@@ -2,3 +2,7 @@
 pub mod primitive;
 pub mod shape;
 pub mod algorithm;
+pub mod frame;
+pub mod transform;
+pub mod projection;
+pub mod curve;
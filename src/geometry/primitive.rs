@@ -1,10 +1,14 @@
-use std::ops::{Add, Sub, Index, IndexMut};
+use std::fmt::{self, Display};
+use std::io::{self, Read, Write};
+use std::ops::{Add, Sub, Mul, Index, IndexMut};
 
 use iter_exact::{CollectExactExt, FromExactSizeIterator};
 
+use codec::{Decode, DecodeError, Encode};
+use geometry::frame::{Frame, Unframed};
 use linalg::*;
-use linalg::algorithm::solve::gaussian::GaussianNullspaceExt;
 use num::traits::Float;
+use pretty;
 use typehack::prelude::*;
 use typehack::data;
 
@@ -28,12 +32,12 @@ macro_rules! Facet {
 
 
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct Point<T: Scalar, N: Dim> {
-    vect: Vect<T, N>,
+pub struct Point<T: Scalar, N: Dim, F: Frame = Unframed> {
+    vect: Vect<T, N, F>,
 }
 
 
-impl<T: Copy + Scalar, N: Dim> Copy for Point<T, N> where Vect<T, N>: Copy {}
+impl<T: Copy + Scalar, N: Dim, F: Frame> Copy for Point<T, N, F> where Vect<T, N, F>: Copy {}
 
 
 impl<T: Scalar, N: Dim> Point<T, N> {
@@ -42,128 +46,161 @@ impl<T: Scalar, N: Dim> Point<T, N> {
     {
         Point { vect: Vect::from_elem(size, elem) }
     }
+}
 
 
+impl<T: Scalar, N: Dim, F: Frame> Point<T, N, F> {
     pub fn size(&self) -> N {
         self.vect.size()
     }
+
+
+    /// Reinterprets the point as belonging to another frame. This is an escape hatch around the
+    /// frame checks; frames are normally changed by applying a `Transform`.
+    pub fn in_frame<G: Frame>(self) -> Point<T, N, G> {
+        Point { vect: self.vect.in_frame() }
+    }
+}
+
+
+/// Displays the point as a parenthesized coordinate list, e.g. `(1, 2, 3)`.
+impl<T: Scalar + Display, N: Dim, F: Frame> Display for Point<T, N, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision();
+
+        pretty::write_list(f, self.vect.len(), "(", ")", |i| pretty::elem(&self[i], precision))
+    }
+}
+
+
+impl<T: Scalar + Encode, N: Dim> Encode for Point<T, N> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.vect.encode(writer)
+    }
 }
 
 
-impl<T: Scalar, N: Dim> From<Vect<T, N>> for Point<T, N> {
-    fn from(vect: Vect<T, N>) -> Point<T, N> {
+impl<T: Scalar + Decode, N: Dim> Decode for Point<T, N> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        Vect::decode(reader).map(Point::from)
+    }
+}
+
+
+impl<T: Scalar, N: Dim, F: Frame> From<Vect<T, N, F>> for Point<T, N, F> {
+    fn from(vect: Vect<T, N, F>) -> Point<T, N, F> {
         Point { vect: vect }
     }
 }
 
 
-impl<T: Scalar, N: Dim> From<Point<T, N>> for Vect<T, N> {
-    fn from(pt: Point<T, N>) -> Vect<T, N> {
+impl<T: Scalar, N: Dim, F: Frame> From<Point<T, N, F>> for Vect<T, N, F> {
+    fn from(pt: Point<T, N, F>) -> Vect<T, N, F> {
         pt.vect
     }
 }
 
 
-impl<T: Scalar, N: Dim> Add<Vect<T, N>> for Point<T, N> {
-    type Output = Point<T, N>;
+impl<T: Scalar, N: Dim, F: Frame> Add<Vect<T, N, F>> for Point<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn add(self, rhs: Vect<T, N>) -> Point<T, N> {
+    fn add(self, rhs: Vect<T, N, F>) -> Point<T, N, F> {
         Point { vect: self.vect + rhs }
     }
 }
 
 
-impl<'a, T: Scalar, N: Dim> Add<Vect<T, N>> for &'a Point<T, N> {
-    type Output = Point<T, N>;
+impl<'a, T: Scalar, N: Dim, F: Frame> Add<Vect<T, N, F>> for &'a Point<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn add(self, rhs: Vect<T, N>) -> Point<T, N> {
+    fn add(self, rhs: Vect<T, N, F>) -> Point<T, N, F> {
         Point { vect: &self.vect + rhs }
     }
 }
 
 
-impl<'b, T: Scalar, N: Dim> Add<&'b Vect<T, N>> for Point<T, N> {
-    type Output = Point<T, N>;
+impl<'b, T: Scalar, N: Dim, F: Frame> Add<&'b Vect<T, N, F>> for Point<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn add(self, rhs: &'b Vect<T, N>) -> Point<T, N> {
+    fn add(self, rhs: &'b Vect<T, N, F>) -> Point<T, N, F> {
         Point { vect: self.vect + rhs }
     }
 }
 
 
-impl<'a, 'b, T: Scalar, N: Dim> Add<&'b Vect<T, N>> for &'a Point<T, N> {
-    type Output = Point<T, N>;
+impl<'a, 'b, T: Scalar, N: Dim, F: Frame> Add<&'b Vect<T, N, F>> for &'a Point<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn add(self, rhs: &'b Vect<T, N>) -> Point<T, N> {
+    fn add(self, rhs: &'b Vect<T, N, F>) -> Point<T, N, F> {
         Point { vect: &self.vect + rhs }
     }
 }
 
 
-impl<T: Scalar, N: Dim> Add<Point<T, N>> for Vect<T, N> {
-    type Output = Point<T, N>;
+impl<T: Scalar, N: Dim, F: Frame> Add<Point<T, N, F>> for Vect<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn add(self, rhs: Point<T, N>) -> Point<T, N> {
+    fn add(self, rhs: Point<T, N, F>) -> Point<T, N, F> {
         Point { vect: self + rhs.vect }
     }
 }
 
 
-impl<T: Scalar, N: Dim> Sub<Vect<T, N>> for Point<T, N> {
-    type Output = Point<T, N>;
+impl<T: Scalar, N: Dim, F: Frame> Sub<Vect<T, N, F>> for Point<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn sub(self, rhs: Vect<T, N>) -> Point<T, N> {
+    fn sub(self, rhs: Vect<T, N, F>) -> Point<T, N, F> {
         Point { vect: self.vect - rhs }
     }
 }
 
 
-impl<T: Scalar, N: Dim> Sub<Point<T, N>> for Vect<T, N> {
-    type Output = Point<T, N>;
+impl<T: Scalar, N: Dim, F: Frame> Sub<Point<T, N, F>> for Vect<T, N, F> {
+    type Output = Point<T, N, F>;
 
-    fn sub(self, rhs: Point<T, N>) -> Point<T, N> {
+    fn sub(self, rhs: Point<T, N, F>) -> Point<T, N, F> {
         Point { vect: self - rhs.vect }
     }
 }
 
 
-impl<T: Scalar, N: Dim> Sub for Point<T, N> {
-    type Output = Vect<T, N>;
+impl<T: Scalar, N: Dim, F: Frame> Sub for Point<T, N, F> {
+    type Output = Vect<T, N, F>;
 
-    fn sub(self, rhs: Self) -> Vect<T, N> {
+    fn sub(self, rhs: Self) -> Vect<T, N, F> {
         self.vect - rhs.vect
     }
 }
 
 
-impl<'a, T: Clone + Scalar, N: Dim> Sub<&'a Point<T, N>> for Point<T, N> {
-    type Output = Vect<T, N>;
+impl<'a, T: Clone + Scalar, N: Dim, F: Frame> Sub<&'a Point<T, N, F>> for Point<T, N, F> {
+    type Output = Vect<T, N, F>;
 
-    fn sub(self, rhs: &Self) -> Vect<T, N> {
+    fn sub(self, rhs: &Self) -> Vect<T, N, F> {
         self.vect - &rhs.vect
     }
 }
 
 
-impl<'a, T: Clone + Scalar, N: Dim> Sub<Point<T, N>> for &'a Point<T, N> {
-    type Output = Vect<T, N>;
+impl<'a, T: Clone + Scalar, N: Dim, F: Frame> Sub<Point<T, N, F>> for &'a Point<T, N, F> {
+    type Output = Vect<T, N, F>;
 
-    fn sub(self, rhs: Point<T, N>) -> Vect<T, N> {
+    fn sub(self, rhs: Point<T, N, F>) -> Vect<T, N, F> {
         &self.vect - rhs.vect
     }
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Dim> Sub<&'b Point<T, N>> for &'a Point<T, N> {
-    type Output = Vect<T, N>;
+impl<'a, 'b, T: Clone + Scalar, N: Dim, F: Frame> Sub<&'b Point<T, N, F>> for &'a Point<T, N, F> {
+    type Output = Vect<T, N, F>;
 
-    fn sub(self, rhs: &'b Point<T, N>) -> Vect<T, N> {
+    fn sub(self, rhs: &'b Point<T, N, F>) -> Vect<T, N, F> {
         &self.vect - &rhs.vect
     }
 }
 
 
-impl<T: Scalar, N: Dim> Index<usize> for Point<T, N> {
+impl<T: Scalar, N: Dim, F: Frame> Index<usize> for Point<T, N, F> {
     type Output = T;
 
     fn index(&self, idx: usize) -> &T {
@@ -172,13 +209,55 @@ impl<T: Scalar, N: Dim> Index<usize> for Point<T, N> {
 }
 
 
-impl<T: Scalar, N: Dim> IndexMut<usize> for Point<T, N> {
+impl<T: Scalar, N: Dim, F: Frame> IndexMut<usize> for Point<T, N, F> {
     fn index_mut(&mut self, idx: usize) -> &mut T {
         &mut self.vect[idx]
     }
 }
 
 
+impl<T: Scalar, N: Dim, F: Frame> Point<T, N, F> {
+    /// The homogeneous coordinates `(p, 1)` of the point.
+    pub fn to_homogeneous(self) -> Vect<T, N::Succ, F> {
+        self.vect.extend(T::one())
+    }
+
+
+    /// The point with homogeneous coordinates `(v, w)`, which is `v / w`. Returns `None` if
+    /// `w = 0`, since the coordinates then describe a direction, or a point at infinity.
+    pub fn from_homogeneous(coords: Vect<T, N::Succ, F>) -> Option<Point<T, N, F>> {
+        let n = coords.len() - 1;
+        let w = coords[n].clone();
+
+        if w.eq_zero() {
+            return None;
+        }
+
+        Some(Point::from(Vect::from_fn(N::from_usize(n), |i| coords[i].clone() / w.clone())))
+    }
+}
+
+
+/// Applies a homogeneous transformation matrix to a point, dividing through by the resulting `w`.
+/// The result is `None` if the point is sent to infinity.
+impl<T: Scalar, N: Dim, L: Layout> Mul<Point<T, N>> for Mat<T, N::Succ, N::Succ, L> {
+    type Output = Option<Point<T, N>>;
+
+    fn mul(self, rhs: Point<T, N>) -> Option<Point<T, N>> {
+        &self * &rhs
+    }
+}
+
+
+impl<'a, T: Scalar, N: Dim, L: Layout> Mul<&'a Point<T, N>> for &'a Mat<T, N::Succ, N::Succ, L> {
+    type Output = Option<Point<T, N>>;
+
+    fn mul(self, rhs: &'a Point<T, N>) -> Option<Point<T, N>> {
+        Point::from_homogeneous(self * &rhs.clone().to_homogeneous())
+    }
+}
+
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Plane<T: Scalar, N: Dim> {
     pub n: Vect<T, N>,
@@ -189,6 +268,29 @@ pub struct Plane<T: Scalar, N: Dim> {
 impl<T: Copy + Scalar, N: Dim> Copy for Plane<T, N> where Vect<T, N>: Copy {}
 
 
+/// Encodes the plane as its normal followed by its point.
+impl<T: Scalar + Encode, N: Dim> Encode for Plane<T, N> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.n.encode(writer)?;
+        self.p0.encode(writer)
+    }
+}
+
+
+impl<T: Scalar + Decode, N: Dim> Decode for Plane<T, N> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let n: Vect<T, N> = Vect::decode(reader)?;
+        let p0: Point<T, N> = Point::decode(reader)?;
+
+        if n.size() != p0.size() {
+            return Err(DecodeError::DimensionMismatch(p0.size().reify()));
+        }
+
+        Ok(Plane { n: n, p0: p0 })
+    }
+}
+
+
 impl<T: Clone + Scalar + Float, N: Dim> Plane<T, N>
     where Vect<T, N>: Clone
 {
@@ -203,8 +305,8 @@ impl<T: Clone + Scalar, N: Dim> From<Facet<T, N>> for Plane<T, N> {
         let mut iter = facet.into_iter();
         let p0 = iter.next().unwrap();
 
-        let mat = Mat::<T, N::Pred, N, Row>::from_rows(iter.map(|p| p - p0.clone()));
-        let n = mat.ge_null_elem();
+        let edges: Vec<_> = iter.map(|p| p - p0.clone()).collect();
+        let n = Vect::generalized_cross(p0.size(), &edges);
 
         Plane { n: n, p0: p0 }
     }
@@ -220,12 +322,8 @@ impl<T: Clone + Scalar> From<Facet<T, B3>> for Plane<T, B3> {
         let a = p1 - p0.clone();
         let b = p2 - p0.clone();
 
-        // We perform the cross product of `a` and `b` to get the normal vector.
-
         Plane {
-            n: Vect![a[1].clone() * b[2].clone() - a[2].clone() * b[1].clone(),
-                     a[2].clone() * b[0].clone() - a[0].clone() * b[2].clone(),
-                     a[0].clone() * b[1].clone() - a[1].clone() * b[0].clone()],
+            n: a.cross(&b),
             p0: p0,
         }
     }
@@ -271,6 +369,26 @@ pub struct Facet<T: Scalar, N: Dim> {
 impl<T: Copy + Scalar, N: Dim> Copy for Facet<T, N> where Data<Point<T, N>, N>: Copy {}
 
 
+impl<T: Scalar + Encode, N: Dim> Encode for Facet<T, N> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.points.encode(writer)
+    }
+}
+
+
+impl<T: Scalar + Decode, N: Dim> Decode for Facet<T, N> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let points: Data<Point<T, N>, N> = Data::decode(reader)?;
+
+        if let Some(p) = points.iter().find(|p| p.size() != points.size()) {
+            return Err(DecodeError::DimensionMismatch(p.size().reify()));
+        }
+
+        Ok(Facet { points: points })
+    }
+}
+
+
 impl<T: Scalar, N: Dim> IntoIterator for Facet<T, N> {
     type Item = Point<T, N>;
     type IntoIter = data::IntoIter<Point<T, N>, N>;
@@ -393,6 +511,82 @@ impl<'a, T: Scalar + Float, N: Dim> SimplexSubset<'a, T, N> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use typehack::dim::Dyn;
+
+    #[test]
+    fn point_display() {
+        assert_eq!(format!("{}", Point![1, 2, 3]), "(1, 2, 3)");
+        assert_eq!(format!("{:.2}", Point![0.5, -1.]), "(0.50, -1.00)");
+        assert_eq!(format!("{}", Vect![1, 2, 3]), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn point_homogeneous_coordinates() {
+        let p = Point![1., -2., 3.];
+        assert_eq!(p.clone().to_homogeneous(), Vect![1., -2., 3., 1.]);
+        assert_eq!(Vect![1., -2., 3.].to_homogeneous(), Vect![1., -2., 3., 0.]);
+
+        assert_eq!(Point::from_homogeneous(Vect![2., -4., 6., 2.]), Some(p));
+        assert_eq!(Point::<f64, B3>::from_homogeneous(Vect![2., -4., 6., 0.]), None);
+    }
+
+    #[test]
+    fn mat_times_point() {
+        let translate = Mat![[1., 0., 5.], [0., 1., -1.], [0., 0., 1.]];
+        assert_eq!(&translate * &Point![1., 1.], Some(Point![6., 0.]));
+
+        // `w = x`, which divides through by the first coordinate.
+        let project = Mat![[1., 0., 0.], [0., 1., 0.], [1., 0., 0.]];
+        assert_eq!(&project * &Point![2., 3.], Some(Point![1., 1.5]));
+        assert_eq!(project * Point![0., 3.], None);
+    }
+
+    #[test]
+    fn plane_codec_round_trip() {
+        let plane = Plane {
+            n: Vect![0., 0., 1.],
+            p0: Point![1.5, -2., 0.25],
+        };
+
+        let mut bytes = Vec::new();
+        plane.encode(&mut bytes).unwrap();
+        assert_eq!(Plane::decode(&mut &bytes[..]).unwrap(), plane);
+
+        let dynamic: Plane<f64, Dyn> = Plane::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(dynamic.p0[1], -2.);
+
+        match Plane::<f64, B2>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(3)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn plane_and_facet_decode_check_dims() {
+        let mut bytes = Vec::new();
+        Vect![0., 1.].encode(&mut bytes).unwrap();
+        Point![1., 2., 3.].encode(&mut bytes).unwrap();
+
+        match Plane::<f64, Dyn>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(3)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let mut bytes = Vec::new();
+        2u64.encode(&mut bytes).unwrap();
+        Point![0., 1.].encode(&mut bytes).unwrap();
+        Point![1., 0., 0.].encode(&mut bytes).unwrap();
+
+        match Facet::<f64, Dyn>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(3)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let facet = Facet![Point![0., 1.], Point![1., 0.]];
+        let mut bytes = Vec::new();
+        facet.encode(&mut bytes).unwrap();
+        assert_eq!(Facet::decode(&mut &bytes[..]).unwrap(), facet);
+    }
 
     #[test]
     fn facet_to_plane_2d_1() {
@@ -413,4 +607,15 @@ mod tests {
         assert!(plane.signed_distance(&Point![0.38620424999999997, 0.44893725000000007, 0.239815]) *
                 plane.signed_distance(&Point![0.554433, 0.549847, 0.032239]) < 0.);
     }
+
+    #[test]
+    fn facet_to_plane_4d_1() {
+        let facet = Facet![Point![1, 0, 0, 0],
+                           Point![0, 1, 0, 0],
+                           Point![0, 0, 1, 0],
+                           Point![0, 0, 0, 1]];
+        let plane: Plane<_, _> = facet.into();
+
+        assert_eq!(plane.n, Vect![-1, -1, -1, -1]);
+    }
 }
@@ -0,0 +1,417 @@
+//! Camera matrices: perspective and orthographic projections, view matrices, and unprojection of
+//! screen coordinates back into the world.
+//!
+//! Graphics APIs disagree on two conventions, both captured by `ClipSpace`. In a right-handed view
+//! space the camera looks down `-z`; in a left-handed one, down `+z`. OpenGL maps depth to
+//! `[-1, 1]` in normalized device coordinates, while Direct3D, Vulkan and Metal map it to `[0, 1]`.
+//! Either range may also be reversed, sending the near plane to `1`, which spreads floating-point
+//! depth precision far more evenly.
+
+use geometry::primitive::Point;
+use linalg::{Dot, Mat4x4, Scalar, Vect, Vect3, VectorNorm};
+use linalg::algorithm::solve::lu::LuDecompositionExt;
+use num::traits::Float;
+use typehack::binary::{B2, B3};
+use typehack::dim::*;
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Handedness {
+    /// The camera looks down `-z`, as in OpenGL and Vulkan.
+    Right,
+    /// The camera looks down `+z`, as in Direct3D.
+    Left,
+}
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DepthRange {
+    /// Depth in normalized device coordinates runs from `-1` to `1`, as in OpenGL.
+    NegOneToOne,
+    /// Depth in normalized device coordinates runs from `0` to `1`, as in Direct3D and Vulkan.
+    ZeroToOne,
+}
+
+
+/// The conventions of the clip space a projection targets.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ClipSpace {
+    pub handedness: Handedness,
+    pub depth: DepthRange,
+    /// Whether the near plane is sent to a depth of `1` and the far plane to the low end of the
+    /// depth range.
+    pub reversed_z: bool,
+}
+
+
+impl ClipSpace {
+    pub fn new(handedness: Handedness, depth: DepthRange) -> Self {
+        ClipSpace {
+            handedness: handedness,
+            depth: depth,
+            reversed_z: false,
+        }
+    }
+
+
+    /// Right-handed with a depth range of `[-1, 1]`.
+    pub fn opengl() -> Self {
+        ClipSpace::new(Handedness::Right, DepthRange::NegOneToOne)
+    }
+
+
+    /// Right-handed with a depth range of `[0, 1]`.
+    pub fn vulkan() -> Self {
+        ClipSpace::new(Handedness::Right, DepthRange::ZeroToOne)
+    }
+
+
+    /// Left-handed with a depth range of `[0, 1]`.
+    pub fn direct3d() -> Self {
+        ClipSpace::new(Handedness::Left, DepthRange::ZeroToOne)
+    }
+
+
+    /// The same clip space, with the depth range reversed.
+    pub fn reversed(self) -> Self {
+        ClipSpace { reversed_z: !self.reversed_z, ..self }
+    }
+
+
+    /// The sign of the view-space `z` coordinate of points in front of the camera.
+    fn forward<T: Scalar>(&self) -> T {
+        match self.handedness {
+            Handedness::Right => -T::one(),
+            Handedness::Left => T::one(),
+        }
+    }
+
+
+    /// The depths of the near and far planes in normalized device coordinates.
+    pub fn depths<T: Scalar>(&self) -> (T, T) {
+        let low = match self.depth {
+            DepthRange::NegOneToOne => -T::one(),
+            DepthRange::ZeroToOne => T::zero(),
+        };
+
+        if self.reversed_z {
+            (T::one(), low)
+        } else {
+            (low, T::one())
+        }
+    }
+}
+
+
+/// A rectangle of the screen, in pixels. Screen coordinates grow rightward and downward from the
+/// top-left corner, as window and mouse coordinates conventionally do.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Viewport<T: Scalar> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+
+impl<T: Scalar> Viewport<T> {
+    pub fn new(x: T, y: T, width: T, height: T) -> Self {
+        Viewport {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }
+    }
+
+
+    /// The normalized device `x` and `y` coordinates of a screen point.
+    pub fn to_ndc(&self, screen: &Point<T, B2>) -> (T, T) {
+        let two = T::one() + T::one();
+
+        (two.clone() * (screen[0].clone() - self.x.clone()) / self.width.clone() - T::one(),
+         T::one() - two * (screen[1].clone() - self.y.clone()) / self.height.clone())
+    }
+
+
+    /// The screen point at the given normalized device `x` and `y` coordinates.
+    pub fn from_ndc(&self, x: T, y: T) -> Point<T, B2> {
+        let two = T::one() + T::one();
+
+        Point::from(Vect![self.x.clone() + (x + T::one()) * self.width.clone() / two.clone(),
+                          self.y.clone() + (T::one() - y) * self.height.clone() / two])
+    }
+}
+
+
+/// A half-line, `origin + t * direction` for `t >= 0`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ray<T: Scalar, N: Dim> {
+    pub origin: Point<T, N>,
+    pub direction: Vect<T, N>,
+}
+
+
+impl<T: Scalar, N: Dim> Ray<T, N> {
+    pub fn new(origin: Point<T, N>, direction: Vect<T, N>) -> Self {
+        Ray {
+            origin: origin,
+            direction: direction,
+        }
+    }
+
+
+    pub fn at(&self, t: T) -> Point<T, N> {
+        Point::from(Vect::from(self.origin.clone()) + self.direction.clone() * t)
+    }
+}
+
+
+/// A perspective projection with a vertical field of view of `fovy` radians and a width to height
+/// ratio of `aspect`. `near` and `far` are the positive distances to the clipping planes.
+pub fn perspective<T: Scalar + Float>(clip: ClipSpace,
+                                      fovy: T,
+                                      aspect: T,
+                                      near: T,
+                                      far: T)
+                                      -> Mat4x4<T> {
+    let (zn, zf) = clip.depths::<T>();
+
+    // Depth is `a + b / d` at a distance `d` in front of the camera.
+    let range = far.clone() - near.clone();
+    let b = (zn.clone() - zf.clone()) * near.clone() * far.clone() / range.clone();
+    let a = (zf * far - zn * near) / range;
+
+    perspective_from_depth(clip, fovy, aspect, a, b)
+}
+
+
+/// A perspective projection with the far plane at infinity.
+pub fn perspective_infinite<T: Scalar + Float>(clip: ClipSpace,
+                                               fovy: T,
+                                               aspect: T,
+                                               near: T)
+                                               -> Mat4x4<T> {
+    let (zn, zf) = clip.depths::<T>();
+    let b = (zn - zf.clone()) * near;
+
+    perspective_from_depth(clip, fovy, aspect, zf, b)
+}
+
+
+fn perspective_from_depth<T: Scalar + Float>(clip: ClipSpace,
+                                             fovy: T,
+                                             aspect: T,
+                                             a: T,
+                                             b: T)
+                                             -> Mat4x4<T> {
+    let two = T::one() + T::one();
+    let f = (fovy / two).tan().recip();
+    let s = clip.forward::<T>();
+
+    let z = T::zero;
+    Mat![[f.clone() / aspect, z(), z(), z()],
+         [z(), f, z(), z()],
+         [z(), z(), a * s.clone(), b],
+         [z(), z(), s, z()]]
+}
+
+
+/// An orthographic projection of the box between `left` and `right`, `bottom` and `top`, and the
+/// positive distances `near` and `far`.
+pub fn orthographic<T: Scalar>(clip: ClipSpace,
+                               left: T,
+                               right: T,
+                               bottom: T,
+                               top: T,
+                               near: T,
+                               far: T)
+                               -> Mat4x4<T> {
+    let two = T::one() + T::one();
+    let (zn, zf) = clip.depths::<T>();
+    let s = clip.forward::<T>();
+
+    let width = right.clone() - left.clone();
+    let height = top.clone() - bottom.clone();
+
+    // Depth is `alpha * d + beta` at a distance `d` in front of the camera.
+    let alpha = (zf - zn.clone()) / (far - near.clone());
+    let beta = zn - alpha.clone() * near;
+
+    let z = T::zero;
+    Mat![[two.clone() / width.clone(), z(), z(), -(right + left) / width],
+         [z(), two / height.clone(), z(), -(top + bottom) / height],
+         [z(), z(), alpha * s, beta],
+         [z(), z(), z(), T::one()]]
+}
+
+
+/// The view matrix of a camera at `eye` looking toward `target`, with `up` pointing roughly
+/// upward on screen. It moves `eye` to the origin and `target` onto the viewing axis, which is
+/// `-z` or `+z` depending on handedness. Returns `None` if `eye` and `target` coincide or `up` is
+/// parallel to the line of sight.
+pub fn look_at<T: Scalar + Float>(handedness: Handedness,
+                                  eye: &Point<T, B3>,
+                                  target: &Point<T, B3>,
+                                  up: &Vect3<T>)
+                                  -> Option<Mat4x4<T>> {
+    let eye = Vect::from(eye.clone());
+    let forward = Vect::from(target.clone()) - eye.clone();
+    let side = forward.cross(up);
+
+    let (forward_len, side_len) = (forward.norm(), side.norm());
+    if forward_len <= T::epsilon() || side_len <= T::epsilon() * forward_len.clone() * up.norm() {
+        return None;
+    }
+
+    let forward = forward / forward_len;
+    let side = side / side_len;
+    let y = side.cross(&forward);
+
+    // The view-space `z` axis points backward in a right-handed system and forward in a
+    // left-handed one, and the `x` axis flips with it.
+    let (x, z) = match handedness {
+        Handedness::Right => (side, -forward),
+        Handedness::Left => (-side, forward),
+    };
+
+    let zero = T::zero;
+    Some(Mat![[x[0].clone(), x[1].clone(), x[2].clone(), -(&x).dot(&eye)],
+              [y[0].clone(), y[1].clone(), y[2].clone(), -(&y).dot(&eye)],
+              [z[0].clone(), z[1].clone(), z[2].clone(), -(&z).dot(&eye)],
+              [zero(), zero(), zero(), T::one()]])
+}
+
+
+/// The world-space ray through a screen point, given the combined `projection * view` matrix it
+/// was rendered with. The ray starts on the near plane and has unit length. Returns `None` if the
+/// matrix is singular.
+pub fn unproject<T: Scalar + Float>(clip: ClipSpace,
+                                    view_projection: &Mat4x4<T>,
+                                    viewport: &Viewport<T>,
+                                    screen: &Point<T, B2>)
+                                    -> Option<Ray<T, B3>> {
+    let inverse = match view_projection.clone().lu().inverse() {
+        Some(inverse) => inverse,
+        None => return None,
+    };
+
+    let (x, y) = viewport.to_ndc(screen);
+    let (zn, zf) = clip.depths::<T>();
+
+    // The far plane may be at infinity, but the middle of the depth range never is.
+    let middle = (zn.clone() + zf) / (T::one() + T::one());
+
+    let near = match &inverse * &Point::from(Vect3!(x.clone(), y.clone(), zn)) {
+        Some(near) => near,
+        None => return None,
+    };
+    let beyond = match &inverse * &Point::from(Vect3!(x, y, middle)) {
+        Some(beyond) => beyond,
+        None => return None,
+    };
+
+    let direction = beyond - near.clone();
+    let len = direction.norm();
+    if len.eq_zero() {
+        return None;
+    }
+
+    Some(Ray::new(near, direction / len))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn ndc(m: &Mat4x4<f64>, x: f64, y: f64, z: f64) -> Vect3<f64> {
+        Vect::from((m * &Point::from(Vect3!(x, y, z))).unwrap())
+    }
+
+    fn assert_close(u: &Vect3<f64>, v: &Vect3<f64>) {
+        assert!((0..3).all(|i| (u[i] - v[i]).abs() < 1e-9), "{:?} != {:?}", u, v);
+    }
+
+    #[test]
+    fn perspective_depth_ranges() {
+        let gl = perspective(ClipSpace::opengl(), PI / 2., 2., 1., 10.);
+        assert_close(&ndc(&gl, 0., 0., -1.), &Vect3!(0., 0., -1.));
+        assert_close(&ndc(&gl, 0., 0., -10.), &Vect3!(0., 0., 1.));
+        assert_close(&ndc(&gl, 2., 1., -1.), &Vect3!(1., 1., -1.));
+
+        let d3d = perspective(ClipSpace::direct3d(), PI / 2., 1., 1., 10.);
+        assert_close(&ndc(&d3d, 0., 0., 1.), &Vect3!(0., 0., 0.));
+        assert_close(&ndc(&d3d, -5., 5., 5.), &Vect3!(-1., 1., 10. / 9. * 0.8));
+
+        let reversed = perspective(ClipSpace::vulkan().reversed(), PI / 2., 1., 1., 10.);
+        assert_close(&ndc(&reversed, 0., 0., -1.), &Vect3!(0., 0., 1.));
+        assert_close(&ndc(&reversed, 0., 0., -10.), &Vect3!(0., 0., 0.));
+    }
+
+    #[test]
+    fn perspective_infinite_far_plane() {
+        let standard = perspective_infinite(ClipSpace::opengl(), PI / 3., 1., 0.5);
+        assert_close(&ndc(&standard, 0., 0., -0.5), &Vect3!(0., 0., -1.));
+        assert!(1. - ndc(&standard, 0., 0., -1e9)[2] < 1e-8);
+
+        let reversed = perspective_infinite(ClipSpace::vulkan().reversed(), PI / 3., 1., 0.5);
+        assert_close(&ndc(&reversed, 0., 0., -0.5), &Vect3!(0., 0., 1.));
+        assert!(ndc(&reversed, 0., 0., -1e9)[2] < 1e-8);
+    }
+
+    #[test]
+    fn orthographic_box() {
+        let gl = orthographic(ClipSpace::opengl(), -2., 2., -1., 3., 1., 5.);
+        assert_close(&ndc(&gl, -2., -1., -1.), &Vect3!(-1., -1., -1.));
+        assert_close(&ndc(&gl, 2., 3., -5.), &Vect3!(1., 1., 1.));
+
+        let d3d = orthographic(ClipSpace::direct3d().reversed(), -2., 2., -1., 3., 1., 5.);
+        assert_close(&ndc(&d3d, 0., 1., 1.), &Vect3!(0., 0., 1.));
+        assert_close(&ndc(&d3d, 0., 1., 5.), &Vect3!(0., 0., 0.));
+    }
+
+    #[test]
+    fn look_at_moves_target_onto_axis() {
+        let eye = Point![1., 2., 3.];
+        let target = Point![1., 2., 0.];
+        let up = Vect3!(0., 1., 0.);
+
+        let rh = look_at(Handedness::Right, &eye, &target, &up).unwrap();
+        assert_close(&ndc(&rh, 1., 2., 3.), &Vect3!(0., 0., 0.));
+        assert_close(&ndc(&rh, 1., 2., 0.), &Vect3!(0., 0., -3.));
+        assert_close(&ndc(&rh, 2., 3., 3.), &Vect3!(1., 1., 0.));
+
+        let lh = look_at(Handedness::Left, &eye, &target, &up).unwrap();
+        assert_close(&ndc(&lh, 1., 2., 0.), &Vect3!(0., 0., 3.));
+        assert_close(&ndc(&lh, 2., 3., 3.), &Vect3!(-1., 1., 0.));
+
+        assert!(look_at(Handedness::Right, &eye, &eye, &up).is_none());
+        assert!(look_at(Handedness::Right, &eye, &Point![1., 5., 3.], &up).is_none());
+    }
+
+    #[test]
+    fn unproject_round_trip() {
+        let viewport = Viewport::new(0., 0., 800., 600.);
+        let eye = Point![0., 0., 5.];
+
+        for &clip in &[ClipSpace::opengl(), ClipSpace::vulkan().reversed(), ClipSpace::direct3d()] {
+            let view = look_at(clip.handedness, &eye, &Point![0., 0., 0.], &Vect3!(0., 1., 0.))
+                .unwrap();
+            let proj = perspective_infinite(clip, PI / 2., 4. / 3., 0.1);
+            let view_proj = proj * view;
+
+            let center = unproject(clip, &view_proj, &viewport, &Point![400., 300.]).unwrap();
+            assert_close(&center.direction, &Vect3!(0., 0., -1.));
+            assert!((center.origin[2] - 4.9).abs() < 1e-9);
+
+            let corner = unproject(clip, &view_proj, &viewport, &Point![0., 0.]).unwrap();
+            let p = Vect::from(corner.at(3.));
+            let screen = ndc(&view_proj, p[0], p[1], p[2]);
+            let back = viewport.from_ndc(screen[0], screen[1]);
+            assert!(back[0].abs() < 1e-6 && back[1].abs() < 1e-6);
+        }
+    }
+}
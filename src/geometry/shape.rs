@@ -1,3 +1,6 @@
+use std::io::{self, Read, Write};
+
+use codec::{self, Decode, DecodeError, Encode};
 use geometry::primitive::Point;
 use geometry::algorithm::quickhull::QuickHullExt;
 use linalg::{Dot, VectorNorm, Scalar, Vect};
@@ -45,13 +48,88 @@ pub trait SupportMapping: Convex {
 /// points, called here as its "corners". The `Cuboid` maintains an invariant on its corners: it
 /// expects that for every element in its corner points, the first corner's element is less than
 /// the second corner's element.
-/// TODO: A constructor which checks this invariant, and an unsafe constructor which does not.
 pub struct Cuboid<T: Scalar, D: Dim> {
     dims: D,
     corners: [Point<T, D>; 2],
 }
 
 
+impl<T: Clone + Scalar, D: Dim> Cuboid<T, D> {
+    /// Creates a `Cuboid` from its lower and upper corners, returning `None` if the corners differ
+    /// in dimension or any element of `lower` is greater than the corresponding element of `upper`.
+    pub fn new(lower: Point<T, D>, upper: Point<T, D>) -> Option<Self> {
+        if lower.size() != upper.size() ||
+           (0..lower.size().reify()).any(|i| lower[i] > upper[i]) {
+            return None;
+        }
+
+        Some(Cuboid {
+            dims: lower.size(),
+            corners: [lower, upper],
+        })
+    }
+
+
+    pub unsafe fn from_raw_corners(lower: Point<T, D>, upper: Point<T, D>) -> Self {
+        Cuboid {
+            dims: lower.size(),
+            corners: [lower, upper],
+        }
+    }
+
+
+    /// The smallest `Cuboid` containing every point in `points`, or `None` if there are none.
+    pub fn from_points<'a, I: IntoIterator<Item = &'a Point<T, D>>>(points: I) -> Option<Self>
+        where T: 'a,
+              D: 'a
+    {
+        let mut iter = points.into_iter();
+
+        let first = match iter.next() {
+            Some(p) => Vect::from(p.clone()),
+            None => return None,
+        };
+
+        let (lower, upper) = iter.fold((first.clone(), first), |(lo, hi), p| {
+            let p = Vect::from(p.clone());
+            (lo.min(&p), hi.max(&p))
+        });
+
+        Some(Cuboid {
+            dims: lower.size(),
+            corners: [lower.into(), upper.into()],
+        })
+    }
+
+
+    pub fn lower(&self) -> &Point<T, D> {
+        &self.corners[0]
+    }
+
+
+    pub fn upper(&self) -> &Point<T, D> {
+        &self.corners[1]
+    }
+
+
+    /// The smallest `Cuboid` containing both `self` and `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let lower = Vect::from(self.corners[0].clone()).min(&other.corners[0].clone().into());
+        let upper = Vect::from(self.corners[1].clone()).max(&other.corners[1].clone().into());
+
+        Cuboid {
+            dims: self.dims,
+            corners: [lower.into(), upper.into()],
+        }
+    }
+
+
+    pub fn contains(&self, p: &Point<T, D>) -> bool {
+        (0..self.dims.reify()).all(|i| self.corners[0][i] <= p[i] && p[i] <= self.corners[1][i])
+    }
+}
+
+
 impl<T: Clone + Scalar, D: Dim> Shape for Cuboid<T, D> {
     type Scalar = T;
     type Dims = D;
@@ -100,6 +178,32 @@ impl<T: Clone + Scalar, D: Dim> SupportMapping for Cuboid<T, D> {
 }
 
 
+/// Encodes the cuboid as its two corners.
+impl<T: Scalar + Encode, D: Dim> Encode for Cuboid<T, D> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.corners[0].encode(writer)?;
+        self.corners[1].encode(writer)
+    }
+}
+
+
+impl<T: Scalar + Decode, D: Dim> Decode for Cuboid<T, D> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let lo: Point<T, D> = Point::decode(reader)?;
+        let hi: Point<T, D> = Point::decode(reader)?;
+
+        if lo.size() != hi.size() {
+            return Err(DecodeError::DimensionMismatch(hi.size().reify()));
+        }
+
+        Ok(Cuboid {
+            dims: lo.size(),
+            corners: [lo, hi],
+        })
+    }
+}
+
+
 /// The `Sphere` represents an n-sphere with a radius and center. All shapes have their own local
 /// coordinate systems, so the center is stored as a point with respect to its local origin.
 pub struct Sphere<T: Scalar, D: Dim> {
@@ -134,6 +238,28 @@ impl<T: Clone + Scalar + Float, D: Dim> SupportMapping for Sphere<T, D> {
 }
 
 
+/// Encodes the sphere as its center followed by its radius.
+impl<T: Scalar + Encode, D: Dim> Encode for Sphere<T, D> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.center.encode(writer)?;
+        self.radius.encode(writer)
+    }
+}
+
+
+impl<T: Scalar + Decode, D: Dim> Decode for Sphere<T, D> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let center: Point<T, D> = Point::decode(reader)?;
+
+        Ok(Sphere {
+            dims: center.size(),
+            center: center,
+            radius: T::decode(reader)?,
+        })
+    }
+}
+
+
 /// The `Polygon` represents a convex n-polytope. Like the `Cuboid`, it too maintains an invariant
 /// - its points must be *wound*, such that on iterating through all of them,
 pub struct Polygon<T: Scalar, D: Dim> {
@@ -166,6 +292,34 @@ impl<T: Scalar + Float, D: Dim> Polygon<T, D> {
 }
 
 
+/// Encodes the polygon as its dimension followed by its vertices. The cached centroid, if any, is
+/// not encoded.
+impl<T: Scalar + Encode, D: Dim> Encode for Polygon<T, D> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        codec::encode_dim(self.dims, writer)?;
+        self.points.encode(writer)
+    }
+}
+
+
+impl<T: Scalar + Decode, D: Dim> Decode for Polygon<T, D> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let dims: D = codec::decode_dim(reader)?;
+        let points: Vec<Point<T, D>> = Vec::decode(reader)?;
+
+        if let Some(p) = points.iter().find(|p| p.size() != dims) {
+            return Err(DecodeError::DimensionMismatch(p.size().reify()));
+        }
+
+        Ok(Polygon {
+            dims: dims,
+            points: points,
+            centroid: None,
+        })
+    }
+}
+
+
 impl<T: Scalar, D: Dim> Shape for Polygon<T, D> {
     type Scalar = T;
     type Dims = D;
@@ -297,3 +451,60 @@ impl<'a, 'b, T: Clone + Scalar, D: Dim, A: Shape<Scalar = T, Dims = D> + Support
         self.a.support(dir) - Vect::from(self.b.support(&-dir))
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typehack::binary::*;
+    use typehack::dim::Dyn;
+
+    fn encode<T: Encode>(x: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        x.encode(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn shape_codec_round_trip() {
+        let cuboid = Cuboid {
+            dims: B2::as_data(),
+            corners: [Point![0., 1.], Point![2., 3.]],
+        };
+        let decoded: Cuboid<f64, B2> = Cuboid::decode(&mut &encode(&cuboid)[..]).unwrap();
+        assert_eq!(decoded.corners, cuboid.corners);
+
+        let sphere = Sphere {
+            dims: B3::as_data(),
+            center: Point![1., 2., 3.],
+            radius: 0.5,
+        };
+        let decoded: Sphere<f64, Dyn> = Sphere::decode(&mut &encode(&sphere)[..]).unwrap();
+        assert_eq!(decoded.dims, Dyn(3));
+        assert_eq!(decoded.radius, 0.5);
+
+        let polygon = unsafe {
+            Polygon::from_raw_vertices(B2::as_data(),
+                                       vec![Point![0., 0.], Point![1., 0.], Point![0., 1.]])
+        };
+        let decoded: Polygon<f64, B2> = Polygon::decode(&mut &encode(&polygon)[..]).unwrap();
+        assert_eq!(decoded.points, polygon.points);
+    }
+
+    #[test]
+    fn polygon_codec_checks_vertex_dims() {
+        let polygon = unsafe {
+            Polygon::from_raw_vertices(B2::as_data(), vec![Point![0., 0.], Point![1., 0.]])
+        };
+        let mut bytes = encode(&polygon);
+        assert!(Polygon::<f64, Dyn>::decode(&mut &bytes[..]).is_ok());
+
+        // Rewrite the polygon's dimension header without touching its vertices.
+        bytes[0] = 3;
+        match Polygon::<f64, Dyn>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(2)) => {}
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("decoded a polygon with mismatched vertices"),
+        }
+    }
+}
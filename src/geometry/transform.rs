@@ -0,0 +1,621 @@
+//! Transformations of points and vectors, from the most to the least constrained:
+//!
+//! * An `Isometry` is a rotation followed by a translation. It preserves distances and angles.
+//! * A `Similarity` additionally scales uniformly. It preserves angles and ratios of distances.
+//! * An `Affine` transformation is any linear map followed by a translation. It preserves
+//!   parallel lines.
+//! * A `Projective` transformation is any invertible matrix acting on homogeneous coordinates. It
+//!   preserves only straight lines.
+//!
+//! Every transformation has a homogeneous matrix of size `D + 1`. Points are transformed as
+//! homogeneous coordinates with `w = 1`, and so are translated; vectors, as differences of points,
+//! have `w = 0` and are not. Each kind converts losslessly into the kinds below it; the reverse
+//! conversions check that the matrix has the required structure.
+
+use std::ops::Mul;
+
+use geometry::primitive::Point;
+use linalg::{DenseMat, Mat, MatrixIdentity, Rotation, Scalar, Square, Vect};
+use linalg::algorithm::solve::lu::LuDecompositionExt;
+use num::traits::Float;
+use typehack::dim::*;
+
+
+/// A rotation followed by a translation: `x -> R x + t`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Isometry<T: Scalar, D: Dim> {
+    pub rotation: Rotation<T, D>,
+    pub translation: Vect<T, D>,
+}
+
+
+/// A uniform scaling, rotation and translation: `x -> s R x + t`. The scale is positive.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Similarity<T: Scalar, D: Dim> {
+    isometry: Isometry<T, D>,
+    scale: T,
+}
+
+
+/// A linear map followed by a translation: `x -> A x + t`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Affine<T: Scalar, D: Dim> {
+    dim: D,
+    mat: Mat<T, D::Succ, D::Succ>,
+}
+
+
+/// An invertible linear map of homogeneous coordinates.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Projective<T: Scalar, D: Dim> {
+    dim: D,
+    mat: Mat<T, D::Succ, D::Succ>,
+}
+
+
+/// Builds the homogeneous matrix `[A t; 0 1]`: the columns of `A` are lifted as directions and
+/// `t` as a point.
+fn homogeneous<T: Scalar, D: Dim>(linear: &Mat<T, D, D>,
+                                  translation: &Vect<T, D>)
+                                  -> Mat<T, D::Succ, D::Succ> {
+    let dim = linear.side();
+
+    let mut cols: Vec<Vect<T, D::Succ>> = (0..dim.reify())
+        .map(|j| Vect::from_fn(dim, |i| linear[[i, j]].clone()).to_homogeneous())
+        .collect();
+    cols.push(Point::from(translation.clone()).to_homogeneous());
+
+    DenseMat::from_cols(cols)
+}
+
+
+impl<T: Scalar, D: Dim> Isometry<T, D> {
+    pub fn new(rotation: Rotation<T, D>, translation: Vect<T, D>) -> Self {
+        assert_eq!(rotation.dim(), translation.size());
+
+        Isometry {
+            rotation: rotation,
+            translation: translation,
+        }
+    }
+
+
+    pub fn identity(dim: D) -> Self {
+        Isometry::new(Rotation::identity(dim), Vect::from_elem(dim, &T::zero()))
+    }
+
+
+    pub fn from_translation(translation: Vect<T, D>) -> Self {
+        Isometry::new(Rotation::identity(translation.size()), translation)
+    }
+
+
+    pub fn dim(&self) -> D {
+        self.rotation.dim()
+    }
+
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        let translation = -(&rotation * &self.translation);
+
+        Isometry::new(rotation, translation)
+    }
+
+
+    pub fn transform_point(&self, p: &Point<T, D>) -> Point<T, D> {
+        Point::from(&self.rotation * &Vect::from(p.clone()) + &self.translation)
+    }
+
+
+    pub fn transform_vect(&self, v: &Vect<T, D>) -> Vect<T, D> {
+        &self.rotation * v
+    }
+
+
+    pub fn to_homogeneous(&self) -> Mat<T, D::Succ, D::Succ> {
+        homogeneous(self.rotation.as_mat(), &self.translation)
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Similarity<T, D> {
+    pub fn new(isometry: Isometry<T, D>, scale: T) -> Self {
+        assert!(scale.gt_zero(), "similarities must have a positive scale");
+
+        Similarity {
+            isometry: isometry,
+            scale: scale,
+        }
+    }
+
+
+    pub fn identity(dim: D) -> Self {
+        Similarity::new(Isometry::identity(dim), T::one())
+    }
+
+
+    pub fn from_scale(dim: D, scale: T) -> Self {
+        Similarity::new(Isometry::identity(dim), scale)
+    }
+
+
+    pub fn dim(&self) -> D {
+        self.isometry.dim()
+    }
+
+
+    pub fn isometry(&self) -> &Isometry<T, D> {
+        &self.isometry
+    }
+
+
+    pub fn scale(&self) -> &T {
+        &self.scale
+    }
+
+
+    pub fn inverse(&self) -> Self {
+        let scale = T::one() / self.scale.clone();
+        let rotation = self.isometry.rotation.inverse();
+        let translation = -(&rotation * &self.isometry.translation) * scale.clone();
+
+        Similarity::new(Isometry::new(rotation, translation), scale)
+    }
+
+
+    pub fn transform_point(&self, p: &Point<T, D>) -> Point<T, D> {
+        self.isometry.transform_point(&Point::from(Vect::from(p.clone()) * self.scale.clone()))
+    }
+
+
+    pub fn transform_vect(&self, v: &Vect<T, D>) -> Vect<T, D> {
+        self.isometry.transform_vect(v) * self.scale.clone()
+    }
+
+
+    pub fn to_homogeneous(&self) -> Mat<T, D::Succ, D::Succ> {
+        let linear = self.isometry.rotation.as_mat().clone().map(|x| x * self.scale.clone());
+        homogeneous(&linear, &self.isometry.translation)
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Similarity<T, D> {
+    /// The isometry, if the scale is one to within machine epsilon.
+    pub fn to_isometry(&self) -> Option<Isometry<T, D>> {
+        if (self.scale.clone() - T::one()).abs() <= T::epsilon() {
+            Some(self.isometry.clone())
+        } else {
+            None
+        }
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Affine<T, D> {
+    pub fn new(linear: Mat<T, D, D>, translation: Vect<T, D>) -> Self {
+        assert_eq!(linear.side(), translation.size());
+
+        Affine {
+            dim: linear.side(),
+            mat: homogeneous(&linear, &translation),
+        }
+    }
+
+
+    pub fn identity(dim: D) -> Self {
+        Affine {
+            dim: dim,
+            mat: MatrixIdentity::eye(dim.succ()),
+        }
+    }
+
+
+    /// Wraps a homogeneous matrix, if its last row is `[0 ... 0 1]`.
+    pub fn from_homogeneous(mat: Mat<T, D::Succ, D::Succ>) -> Option<Self> {
+        // A `0 x 0` matrix has no `w` row, so it is not the homogeneous matrix of any dimension.
+        let n = match mat.side().reify().checked_sub(1) {
+            Some(n) => n,
+            None => return None,
+        };
+
+        if !mat[[n, n]].eq_one() || (0..n).any(|j| !mat[[n, j]].eq_zero()) {
+            return None;
+        }
+
+        Some(Affine {
+            dim: D::from_usize(n),
+            mat: mat,
+        })
+    }
+
+
+    pub fn dim(&self) -> D {
+        self.dim
+    }
+
+
+    /// The linear part `A`.
+    pub fn linear(&self) -> Mat<T, D, D> {
+        self.mat.view(0, 0, self.dim, self.dim).to_mat()
+    }
+
+
+    /// The translation `t`, read off the last column `(t, 1)`.
+    pub fn translation(&self) -> Vect<T, D> {
+        let n = self.dim.reify();
+        Vect::from_fn(self.dim, |i| self.mat[[i, n]].clone())
+    }
+
+
+    /// The inverse transformation, or `None` if the linear part is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let translation = self.translation();
+
+        let inv = match self.linear().lu().inverse() {
+            Some(inv) => inv,
+            None => return None,
+        };
+        let translation = -(&inv * &translation);
+
+        Some(Affine::new(inv, translation))
+    }
+
+
+    pub fn transform_point(&self, p: &Point<T, D>) -> Point<T, D> {
+        (&self.mat * p).expect("affine transformations should keep `w = 1`!")
+    }
+
+
+    pub fn transform_vect(&self, v: &Vect<T, D>) -> Vect<T, D> {
+        // Directions have `w = 0`, which the last row of an affine matrix preserves.
+        let image = &self.mat * &v.clone().to_homogeneous();
+        Vect::from_fn(self.dim, |i| image[i].clone())
+    }
+
+
+    pub fn to_homogeneous(&self) -> Mat<T, D::Succ, D::Succ> {
+        self.mat.clone()
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Affine<T, D> {
+    /// The similarity with this matrix, if the linear part is a positive multiple of a rotation.
+    pub fn to_similarity(&self) -> Option<Similarity<T, D>> {
+        let (linear, translation) = (self.linear(), self.translation());
+        let n = self.dim.reify();
+
+        // The scale of a similarity is the length of any column of its linear part.
+        let scale = (0..n)
+            .map(|i| linear[[i, 0]].clone() * linear[[i, 0]].clone())
+            .sum::<T>()
+            .sqrt();
+        if !scale.gt_zero() {
+            return None;
+        }
+
+        let inv_scale = scale.clone().recip();
+        match Rotation::from_mat(linear.map(|x| x * inv_scale.clone())) {
+            Some(rotation) => Some(Similarity::new(Isometry::new(rotation, translation), scale)),
+            None => None,
+        }
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Projective<T, D> {
+    pub fn identity(dim: D) -> Self {
+        Projective {
+            dim: dim,
+            mat: MatrixIdentity::eye(dim.succ()),
+        }
+    }
+
+
+    /// Wraps a homogeneous matrix, if it is invertible.
+    pub fn from_homogeneous(mat: Mat<T, D::Succ, D::Succ>) -> Option<Self> {
+        // A `0 x 0` matrix has no `w` row, so it is not the homogeneous matrix of any dimension.
+        let n = match mat.side().reify().checked_sub(1) {
+            Some(n) => n,
+            None => return None,
+        };
+
+        if mat.clone().lu().is_singular() {
+            return None;
+        }
+
+        Some(Projective {
+            dim: D::from_usize(n),
+            mat: mat,
+        })
+    }
+
+
+    pub fn dim(&self) -> D {
+        self.dim
+    }
+
+
+    pub fn inverse(&self) -> Self {
+        Projective {
+            dim: self.dim,
+            mat: self.mat
+                .clone()
+                .lu()
+                .inverse()
+                .expect("projective transformations should be invertible!"),
+        }
+    }
+
+
+    /// Transforms a point, dividing through by the resulting `w`. Returns `None` if the point is
+    /// sent to infinity.
+    pub fn transform_point(&self, p: &Point<T, D>) -> Option<Point<T, D>> {
+        &self.mat * p
+    }
+
+
+    pub fn to_homogeneous(&self) -> Mat<T, D::Succ, D::Succ> {
+        self.mat.clone()
+    }
+
+
+    /// The affine transformation with this matrix, if its last row is a multiple of
+    /// `[0 ... 0 1]`.
+    pub fn to_affine(&self) -> Option<Affine<T, D>> {
+        let n = self.dim.reify();
+        let w = self.mat[[n, n]].clone();
+
+        if w.eq_zero() {
+            return None;
+        }
+
+        Affine::from_homogeneous(self.mat.clone().map(|x| x / w.clone()))
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Rotation<T, D>> for Isometry<T, D> {
+    fn from(rotation: Rotation<T, D>) -> Self {
+        let dim = rotation.dim();
+        Isometry::new(rotation, Vect::from_elem(dim, &T::zero()))
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Isometry<T, D>> for Similarity<T, D> {
+    fn from(isometry: Isometry<T, D>) -> Self {
+        Similarity::new(isometry, T::one())
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Isometry<T, D>> for Affine<T, D> {
+    fn from(isometry: Isometry<T, D>) -> Self {
+        Affine {
+            dim: isometry.dim(),
+            mat: isometry.to_homogeneous(),
+        }
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Similarity<T, D>> for Affine<T, D> {
+    fn from(similarity: Similarity<T, D>) -> Self {
+        Affine {
+            dim: similarity.dim(),
+            mat: similarity.to_homogeneous(),
+        }
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Isometry<T, D>> for Projective<T, D> {
+    fn from(isometry: Isometry<T, D>) -> Self {
+        Affine::from(isometry).into()
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Similarity<T, D>> for Projective<T, D> {
+    fn from(similarity: Similarity<T, D>) -> Self {
+        Affine::from(similarity).into()
+    }
+}
+
+
+/// Affine transformations are projective only when invertible; this conversion panics otherwise.
+impl<T: Scalar, D: Dim> From<Affine<T, D>> for Projective<T, D> {
+    fn from(affine: Affine<T, D>) -> Self {
+        Projective::from_homogeneous(affine.mat)
+            .expect("only invertible affine transformations are projective!")
+    }
+}
+
+
+/// Composition: `a * b` applies `b`, then `a`.
+impl<T: Scalar, D: Dim> Mul for Isometry<T, D> {
+    type Output = Isometry<T, D>;
+
+    fn mul(self, rhs: Isometry<T, D>) -> Isometry<T, D> {
+        let translation = &self.rotation * &rhs.translation + self.translation;
+        Isometry::new(self.rotation * rhs.rotation, translation)
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Mul for Similarity<T, D> {
+    type Output = Similarity<T, D>;
+
+    fn mul(self, rhs: Similarity<T, D>) -> Similarity<T, D> {
+        let Similarity { isometry: a, scale: s } = self;
+        let Similarity { isometry: b, scale: r } = rhs;
+
+        let translation = (&a.rotation * &b.translation) * s.clone() + a.translation;
+        Similarity::new(Isometry::new(a.rotation * b.rotation, translation), s * r)
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Mul for Affine<T, D> {
+    type Output = Affine<T, D>;
+
+    fn mul(self, rhs: Affine<T, D>) -> Affine<T, D> {
+        Affine {
+            dim: self.dim,
+            mat: self.mat * rhs.mat,
+        }
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Mul for Projective<T, D> {
+    type Output = Projective<T, D>;
+
+    fn mul(self, rhs: Projective<T, D>) -> Projective<T, D> {
+        Projective {
+            dim: self.dim,
+            mat: self.mat * rhs.mat,
+        }
+    }
+}
+
+
+macro_rules! impl_transform_mul {
+    ($($name:ident),*) => {
+        $(impl<T: Scalar, D: Dim> Mul<Point<T, D>> for $name<T, D> {
+            type Output = Point<T, D>;
+
+            fn mul(self, rhs: Point<T, D>) -> Point<T, D> {
+                self.transform_point(&rhs)
+            }
+        }
+
+        impl<T: Scalar, D: Dim> Mul<Vect<T, D>> for $name<T, D> {
+            type Output = Vect<T, D>;
+
+            fn mul(self, rhs: Vect<T, D>) -> Vect<T, D> {
+                self.transform_vect(&rhs)
+            }
+        })*
+    }
+}
+
+impl_transform_mul!(Isometry, Similarity, Affine);
+
+
+/// Projective transformations may send points to infinity, so the product is optional, as for
+/// `Projective::transform_point`.
+impl<T: Scalar, D: Dim> Mul<Point<T, D>> for Projective<T, D> {
+    type Output = Option<Point<T, D>>;
+
+    fn mul(self, rhs: Point<T, D>) -> Option<Point<T, D>> {
+        self.transform_point(&rhs)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use linalg::Vect2;
+
+    fn assert_close(u: &Vect2<f64>, v: &Vect2<f64>) {
+        assert!((u[0] - v[0]).abs() < 1e-12 && (u[1] - v[1]).abs() < 1e-12,
+                "{:?} != {:?}",
+                u,
+                v);
+    }
+
+    fn quarter_turn() -> Isometry<f64, ::typehack::binary::B2> {
+        Isometry::new(Rotation::from_angle(PI / 2.), Vect![1., 0.])
+    }
+
+    #[test]
+    fn isometry_points_and_vects() {
+        let iso = quarter_turn();
+
+        assert_close(&(iso.clone() * Point::from(Vect![1., 0.])).into(), &Vect![1., 1.]);
+        assert_close(&(iso.clone() * Vect![1., 0.]), &Vect![0., 1.]);
+
+        let back = iso.inverse() * iso.transform_point(&Point::from(Vect![3., -2.]));
+        assert_close(&back.into(), &Vect![3., -2.]);
+
+        let twice = iso.clone() * iso;
+        assert_close(&twice.translation, &Vect![1., 1.]);
+        assert!((twice.rotation.angle() - PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn similarity_inverse_and_composition() {
+        let sim = Similarity::new(quarter_turn(), 2.);
+        let p = Point::from(Vect![0.5, 4.]);
+
+        let q = sim.transform_point(&p);
+        assert_close(&q.clone().into(), &Vect![-7., 1.]);
+        assert_close(&sim.inverse().transform_point(&q).into(), &Vect![0.5, 4.]);
+
+        let both = sim.clone() * sim.inverse();
+        assert!((both.scale() - 1.).abs() < 1e-12);
+        assert_close(&both.isometry().translation, &Vect![0., 0.]);
+        assert!(both.to_isometry().is_some() && sim.to_isometry().is_none());
+
+        assert_close(&(sim * Vect![1., 0.]), &Vect![0., 2.]);
+    }
+
+    #[test]
+    fn affine_conversions() {
+        let sim = Similarity::new(quarter_turn(), 3.);
+        let affine = Affine::from(sim.clone());
+        let p = Point::from(Vect![2., 5.]);
+
+        assert_close(&affine.transform_point(&p).into(), &sim.transform_point(&p).into());
+
+        let recovered = affine.to_similarity().unwrap();
+        assert!((recovered.scale() - 3.).abs() < 1e-12);
+        assert_close(&recovered.isometry().translation, &Vect![1., 0.]);
+
+        let shear = Affine::new(Mat![[1., 1.], [0., 1.]], Vect![0., 2.]);
+        assert!(shear.to_similarity().is_none());
+        assert_close(&(shear.clone() * Vect![0., 1.]), &Vect![1., 1.]);
+
+        let inv = shear.inverse().unwrap();
+        assert_close(&(inv * shear.clone()).transform_point(&p).into(), &Vect![2., 5.]);
+
+        assert!(Affine::new(Mat![[1., 2.], [2., 4.]], Vect![0., 0.]).inverse().is_none());
+    }
+
+    #[test]
+    fn projective_divides_by_w() {
+        // `w = x + 1`, which sends `x = -1` to infinity.
+        let mat = Mat![[2., 0., 0.], [0., 1., 0.], [1., 0., 1.]];
+        let proj: Projective<f64, ::typehack::binary::B2> = Projective::from_homogeneous(mat)
+            .unwrap();
+
+        let p = proj.transform_point(&Point::from(Vect![1., 4.])).unwrap();
+        assert_close(&p.into(), &Vect![1., 2.]);
+        assert!(proj.transform_point(&Point::from(Vect![-1., 4.])).is_none());
+        assert!(proj.to_affine().is_none());
+
+        let q = proj.inverse().transform_point(&Point::from(Vect![1., 2.])).unwrap();
+        assert_close(&q.into(), &Vect![1., 4.]);
+
+        let affine = Projective::from(quarter_turn()).to_affine().unwrap();
+        assert_eq!(affine.to_homogeneous(), quarter_turn().to_homogeneous());
+
+        let r = (proj * Point::from(Vect![1., 4.])).unwrap();
+        assert_close(&r.into(), &Vect![1., 2.]);
+    }
+
+    #[test]
+    fn empty_homogeneous_matrices() {
+        let empty: Mat<f64, Dyn, Dyn> = DenseMat::from_elem(Dyn(0), Dyn(0), 0.);
+
+        assert!(Affine::<f64, Dyn>::from_homogeneous(empty.clone()).is_none());
+        assert!(Projective::<f64, Dyn>::from_homogeneous(empty).is_none());
+    }
+}
@@ -20,3 +20,7 @@ pub mod linalg;
 pub mod geometry;
 
 pub mod num;
+
+pub mod codec;
+
+mod pretty;
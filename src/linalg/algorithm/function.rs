@@ -0,0 +1,322 @@
+use linalg::{DenseMat, Layout, Mat, MatrixIdentity, MatrixNorm, Scalar, Square};
+use linalg::algorithm::solve::lu::LuDecompositionExt;
+use num::traits::Float;
+use typehack::dim::*;
+
+
+/// The maximum number of Denman–Beavers iterations before `sqrtm` gives up.
+const SQRTM_MAX_ITERATIONS: usize = 100;
+
+/// The maximum number of square roots `logm` will take to bring its argument near the identity.
+const LOGM_MAX_SQUARE_ROOTS: usize = 64;
+
+/// The maximum number of terms of the series for `log(I + X)` that `logm` will sum.
+const LOGM_MAX_TERMS: usize = 100;
+
+
+/// Functions of square matrices.
+pub trait MatrixFunctionExt: Square + Sized {
+    /// The matrix exponential, computed by scaling and squaring with a degree 13 Padé
+    /// approximant.
+    fn expm(&self) -> Self;
+
+    /// The principal matrix logarithm, computed by inverse scaling and squaring. Returns `None`
+    /// if the matrix is singular or has no real principal logarithm.
+    fn logm(&self) -> Option<Self>;
+
+    /// The principal matrix square root, computed by the Denman–Beavers iteration. Returns `None`
+    /// if the matrix is singular or the iteration fails to converge, as it will when the matrix
+    /// has no real principal square root.
+    fn sqrtm(&self) -> Option<Self>;
+
+    /// Raises the matrix to an integer power by repeated squaring. Returns `None` if the power is
+    /// negative and the matrix is singular.
+    fn powi(&self, i32) -> Option<Self>;
+}
+
+
+fn add<T: Scalar, N: Dim, L: Layout>(a: Mat<T, N, N, L>, b: Mat<T, N, N, L>) -> Mat<T, N, N, L> {
+    a.zip_map(b, |x, y| x + y)
+}
+
+
+fn sub<T: Scalar, N: Dim, L: Layout>(a: Mat<T, N, N, L>, b: Mat<T, N, N, L>) -> Mat<T, N, N, L> {
+    a.zip_map(b, |x, y| x - y)
+}
+
+
+fn scale<T: Scalar, N: Dim, L: Layout>(a: Mat<T, N, N, L>, c: T) -> Mat<T, N, N, L> {
+    a.map(|x| x * c.clone())
+}
+
+
+/// The sum of `c_k * a_k` over every term.
+fn lincomb<T: Scalar, N: Dim, L: Layout + Clone>(side: N,
+                                                 terms: &[(T, &Mat<T, N, N, L>)])
+                                                 -> Mat<T, N, N, L> {
+    terms.iter().fold(DenseMat::from_elem(side, side, T::zero()),
+                      |acc, &(ref c, a)| add(acc, scale(a.clone(), c.clone())))
+}
+
+
+/// The degree of the Padé approximant to `exp(x)` used by `expm`.
+const PADE_DEGREE: usize = 13;
+
+/// A bound on the 1-norm below which the degree 13 Padé approximant is accurate to double
+/// precision without scaling. Higham, "The Scaling and Squaring Method for the Matrix Exponential
+/// Revisited" (2005), gives `5.37`; we round down to stay on the safe side.
+const PADE_THETA: usize = 5;
+
+
+/// The coefficients of the degree `m` Padé approximant to `exp(x)`, normalized so that the
+/// constant term is one. Each is computed from the last through the ratio
+/// `c_k / c_(k-1) = (m - k + 1) / (k * (2m - k + 1))`, which keeps every intermediate small.
+fn pade_coefficients<T: Scalar>(m: usize) -> Vec<T> {
+    let mut coeffs = vec![T::one()];
+
+    for k in 1..m + 1 {
+        let c = coeffs[k - 1].clone() * T::from_usize(m - k + 1) /
+                T::from_usize(k * (2 * m - k + 1));
+        coeffs.push(c);
+    }
+
+    coeffs
+}
+
+
+impl<T: Scalar + Float, N: Dim, L: Layout + Clone> MatrixFunctionExt for Mat<T, N, N, L> {
+    fn expm(&self) -> Self {
+        let side = self.side();
+        let half = T::one() / (T::one() + T::one());
+
+        // Scale `A` by `2^-s` so that its norm is within the range where the approximant is
+        // accurate, then square the result `s` times.
+        let mut a = self.clone();
+        let mut norm = a.one_norm();
+        let mut s = 0;
+
+        while norm > T::from_usize(PADE_THETA) {
+            norm = norm * half.clone();
+            a = scale(a, half.clone());
+            s += 1;
+        }
+
+        let coeffs = pade_coefficients::<T>(PADE_DEGREE);
+        let b = |k: usize| coeffs[k].clone();
+        let eye: Self = MatrixIdentity::eye(side);
+
+        let a2 = a.clone() * a.clone();
+        let a4 = a2.clone() * a2.clone();
+        let a6 = a4.clone() * a2.clone();
+
+        let u = a6.clone() * lincomb(side, &[(b(13), &a6), (b(11), &a4), (b(9), &a2)]);
+        let u = add(u, lincomb(side, &[(b(7), &a6), (b(5), &a4), (b(3), &a2), (b(1), &eye)]));
+        let u = a * u;
+
+        let v = a6.clone() * lincomb(side, &[(b(12), &a6), (b(10), &a4), (b(8), &a2)]);
+        let v = add(v, lincomb(side, &[(b(6), &a6), (b(4), &a4), (b(2), &a2), (b(0), &eye)]));
+
+        // `V - U` is always invertible for norms below theta.
+        let denom = sub(v.clone(), u.clone())
+            .lu()
+            .inverse()
+            .expect("Padé denominator should be nonsingular after scaling!");
+        let mut r = denom * add(v, u);
+
+        for _ in 0..s {
+            r = r.clone() * r;
+        }
+
+        r
+    }
+
+
+    fn logm(&self) -> Option<Self> {
+        let side = self.side();
+        let eye: Self = MatrixIdentity::eye(side);
+        let quarter = T::one() / T::from_usize(4);
+
+        // Take square roots until `X` is close enough to the identity for the series for
+        // `log(I + (X - I))` to converge quickly; then `log(A) = 2^s * log(X)`.
+        let mut x = self.clone();
+        let mut s = 0;
+
+        while sub(x.clone(), eye.clone()).one_norm() > quarter {
+            if s >= LOGM_MAX_SQUARE_ROOTS {
+                return None;
+            }
+
+            x = match x.sqrtm() {
+                Some(root) => root,
+                None => return None,
+            };
+            s += 1;
+        }
+
+        let e = sub(x, eye);
+        let mut power = e.clone();
+        let mut sum = e.clone();
+
+        for k in 2..LOGM_MAX_TERMS {
+            power = power * e.clone();
+
+            let term = scale(power.clone(), T::one() / T::from_usize(k));
+            let done = term.one_norm() <= T::epsilon() * sum.one_norm();
+
+            sum = if k % 2 == 0 { sub(sum, term) } else { add(sum, term) };
+
+            if done {
+                break;
+            }
+        }
+
+        let two = T::one() + T::one();
+        for _ in 0..s {
+            sum = scale(sum, two.clone());
+        }
+
+        Some(sum)
+    }
+
+
+    fn sqrtm(&self) -> Option<Self> {
+        let side = self.side();
+        let n = side.reify();
+        let half = T::one() / (T::one() + T::one());
+        let tolerance = T::epsilon() * T::from_usize(100 * n.max(1));
+
+        let mut y = self.clone();
+        let mut z: Self = MatrixIdentity::eye(side);
+
+        for _ in 0..SQRTM_MAX_ITERATIONS {
+            let (y_inv, z_inv) = match (y.clone().lu().inverse(), z.clone().lu().inverse()) {
+                (Some(y_inv), Some(z_inv)) => (y_inv, z_inv),
+                _ => return None,
+            };
+
+            let next = scale(add(y.clone(), z_inv), half.clone());
+            z = scale(add(z, y_inv), half.clone());
+
+            let delta = sub(next.clone(), y).one_norm();
+            let converged = delta <= tolerance.clone() * next.one_norm();
+            y = next;
+
+            if converged {
+                return Some(y);
+            }
+        }
+
+        None
+    }
+
+
+    fn powi(&self, k: i32) -> Option<Self> {
+        let mut base = if k < 0 {
+            match self.clone().lu().inverse() {
+                Some(inv) => inv,
+                None => return None,
+            }
+        } else {
+            self.clone()
+        };
+
+        let mut k = (k as i64).abs() as u64;
+        let mut acc: Self = MatrixIdentity::eye(self.side());
+
+        while k > 0 {
+            if k & 1 == 1 {
+                acc = acc * base.clone();
+            }
+
+            k >>= 1;
+
+            if k > 0 {
+                base = base.clone() * base;
+            }
+        }
+
+        Some(acc)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use linalg::{Mat2x2, Row};
+
+    fn max_diff<L1: Layout, L2: Layout>(a: Mat2x2<f64, L1>, b: Mat2x2<f64, L2>) -> f64 {
+        a.zip_map(b, |x, y| (x - y).abs()).fold(0., |m, &e| if e > m { e } else { m })
+    }
+
+    fn rotation(theta: f64) -> Mat2x2<f64> {
+        Mat![[theta.cos(), -theta.sin()],
+             [theta.sin(),  theta.cos()]]
+    }
+
+    fn generator(theta: f64) -> Mat2x2<f64> {
+        Mat![[   0., -theta],
+             [theta,     0.]]
+    }
+
+    #[test]
+    fn expm_diagonal() {
+        let a = Mat![#row [1., 0.], [0., -2.]];
+        let expected = Mat![[1f64.exp(), 0.], [0., (-2f64).exp()]];
+
+        assert!(max_diff(a.expm(), expected) < 1e-12);
+    }
+
+    #[test]
+    fn expm_large_norm_needs_scaling() {
+        let a = Mat![[10., 0.], [0., 3.]];
+        let expected = Mat![[10f64.exp(), 0.], [0., 3f64.exp()]];
+
+        assert!(max_diff(a.expm(), expected) / 10f64.exp() < 1e-12);
+    }
+
+    #[test]
+    fn expm_rotation() {
+        for &theta in &[0.1, 1., PI / 2., 3.] {
+            assert!(max_diff(generator(theta).expm(), rotation(theta)) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn logm_diagonal_and_rotation() {
+        let a = Mat![[4., 0.], [0., 0.5]];
+        let expected = Mat![[4f64.ln(), 0.], [0., 0.5f64.ln()]];
+        assert!(max_diff(a.logm().unwrap(), expected) < 1e-10);
+
+        for &theta in &[0.1, 1., 2.5] {
+            assert!(max_diff(rotation(theta).logm().unwrap(), generator(theta)) < 1e-10);
+        }
+    }
+
+    #[test]
+    fn sqrtm_diagonal_and_rotation() {
+        let a = Mat![[9., 0.], [0., 0.25]];
+        assert!(max_diff(a.sqrtm().unwrap(), Mat![[3., 0.], [0., 0.5]]) < 1e-12);
+
+        for &theta in &[0.1, 1., 2.5] {
+            assert!(max_diff(rotation(theta).sqrtm().unwrap(), rotation(theta / 2.)) < 1e-12);
+        }
+
+        assert!(Mat![[0., 0.], [0., 1.]].sqrtm().is_none());
+    }
+
+    #[test]
+    fn powi_rotation() {
+        let r = rotation(0.3);
+
+        assert!(max_diff(r.powi(5).unwrap(), rotation(1.5)) < 1e-12);
+        assert!(max_diff(r.powi(-3).unwrap(), rotation(-0.9)) < 1e-12);
+        let eye: Mat2x2<f64> = MatrixIdentity::eye(r.side());
+        assert!(max_diff(r.powi(0).unwrap(), eye) < 1e-12);
+
+        let singular: Mat2x2<f64, Row> = Mat![#row [1., 2.], [2., 4.]];
+        assert!(singular.powi(-1).is_none());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod function;
 pub mod optimize;
 pub mod solve;
@@ -0,0 +1,354 @@
+use linalg::{DenseMat, Layout, Mat, Matrix, Scalar, Square, Vect};
+use typehack::dim::*;
+
+
+/// An LU factorization with partial pivoting, `P * A = L * U`. `L` (unit lower triangular, with
+/// its diagonal left implicit) and `U` are packed into a single matrix, and `P` is stored as a
+/// permutation of row indices.
+#[derive(Clone, Debug)]
+pub struct Lu<T: Scalar, N: Dim, L: Layout> {
+    lu: Mat<T, N, N, L>,
+    perm: Vec<usize>,
+    swaps: usize,
+    singular: bool,
+    norm1: T,
+}
+
+
+pub trait LuDecompositionExt: Square {
+    type Output;
+
+    fn lu(self) -> <Self as LuDecompositionExt>::Output;
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> LuDecompositionExt for Mat<T, N, N, L> {
+    type Output = Lu<T, N, L>;
+
+    fn lu(mut self) -> Lu<T, N, L> {
+        let n = self.side().reify();
+
+        // The 1-norm of the original matrix is needed for condition estimation later on, and is
+        // cheap to compute now while we still have it.
+        let mut norm1 = T::zero();
+        for j in 0..n {
+            let mut sum = T::zero();
+            for i in 0..n {
+                sum += self[[i, j]].abs();
+            }
+
+            if sum > norm1 {
+                norm1 = sum;
+            }
+        }
+
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+        let mut singular = false;
+
+        for i in 0..n {
+            let mut max = (i, self[[i, i]].abs());
+            for j in i + 1..n {
+                let abs = self[[j, i]].abs();
+                if abs > max.1 {
+                    max = (j, abs);
+                }
+            }
+
+            if max.0 != i {
+                self.row_switch_mut(i, max.0);
+                perm.swap(i, max.0);
+                swaps += 1;
+            }
+
+            if self[[i, i]].eq_zero() {
+                singular = true;
+                continue;
+            }
+
+            for j in i + 1..n {
+                let k = self[[j, i]].clone() / self[[i, i]].clone();
+
+                for c in i + 1..n {
+                    let u = self[[i, c]].clone();
+                    self[[j, c]] -= k.clone() * u;
+                }
+
+                self[[j, i]] = k;
+            }
+        }
+
+        Lu {
+            lu: self,
+            perm: perm,
+            swaps: swaps,
+            singular: singular,
+            norm1: norm1,
+        }
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> Lu<T, N, L> {
+    /// The packed `L` and `U` factors.
+    pub fn factors(&self) -> &Mat<T, N, N, L> {
+        &self.lu
+    }
+
+
+    /// The row permutation: row `i` of `P * A` is row `permutation()[i]` of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+
+    pub fn determinant(&self) -> T {
+        let n = self.lu.side().reify();
+        let det: T = (0..n).map(|i| self.lu[[i, i]].clone()).product();
+
+        if self.swaps % 2 == 0 { det } else { -det }
+    }
+
+
+    /// Solves `A * x = b`, returning `None` if `A` is singular.
+    pub fn solve(&self, b: &Vect<T, N>) -> Option<Vect<T, N>> {
+        if self.singular {
+            return None;
+        }
+
+        let n = self.lu.side().reify();
+        let mut x = Vect::from_fn(self.lu.side(), |i| b[self.perm[i]].clone());
+
+        for i in 0..n {
+            for j in 0..i {
+                let l = self.lu[[i, j]].clone() * x[j].clone();
+                x[i] -= l;
+            }
+        }
+
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                let u = self.lu[[i, j]].clone() * x[j].clone();
+                x[i] -= u;
+            }
+            x[i] /= self.lu[[i, i]].clone();
+        }
+
+        Some(x)
+    }
+
+
+    /// Solves `A^T * x = b`, returning `None` if `A` is singular.
+    pub fn solve_transpose(&self, b: &Vect<T, N>) -> Option<Vect<T, N>> {
+        if self.singular {
+            return None;
+        }
+
+        // A^T = U^T * L^T * P, so we solve with U^T (lower triangular) first, then L^T (unit upper
+        // triangular), and finally undo the permutation.
+
+        let n = self.lu.side().reify();
+        let mut z = b.clone();
+
+        for i in 0..n {
+            for j in 0..i {
+                let u = self.lu[[j, i]].clone() * z[j].clone();
+                z[i] -= u;
+            }
+            z[i] /= self.lu[[i, i]].clone();
+        }
+
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                let l = self.lu[[j, i]].clone() * z[j].clone();
+                z[i] -= l;
+            }
+        }
+
+        let mut x = z.clone();
+        for i in 0..n {
+            x[self.perm[i]] = z[i].clone();
+        }
+
+        Some(x)
+    }
+
+
+    /// Computes `A^-1` by solving against each column of the identity, returning `None` if `A`
+    /// is singular.
+    pub fn inverse(&self) -> Option<Mat<T, N, N, L>> {
+        if self.singular {
+            return None;
+        }
+
+        let side = self.lu.side();
+        let mut out = DenseMat::from_elem(side, side, T::zero());
+
+        for j in 0..side.reify() {
+            let mut e = Vect::from_elem(side, &T::zero());
+            e[j] = T::one();
+
+            let x = self.solve(&e).unwrap();
+            for i in 0..side.reify() {
+                out[[i, j]] = x[i].clone();
+            }
+        }
+
+        Some(out)
+    }
+
+
+    /// Estimates `||A^-1||_1` using Hager's method as refined by Higham (the algorithm behind
+    /// LAPACK's `xLACON`), at the cost of a handful of triangular solves. The estimate is a lower
+    /// bound, and is almost always within a factor of 3 of the true value. Returns `None` if `A`
+    /// is singular.
+    pub fn inverse_norm1_estimate(&self) -> Option<T> {
+        if self.singular {
+            return None;
+        }
+
+        let side = self.lu.side();
+        let n = side.reify();
+
+        if n == 0 {
+            return Some(T::zero());
+        }
+
+        let norm1 = |v: &Vect<T, N>| v.as_slice().iter().map(|x| x.abs()).sum::<T>();
+
+        let mut x = Vect::from_elem(side, &(T::one() / T::from_usize(n)));
+        let mut estimate = T::zero();
+        let mut last = None;
+
+        for _ in 0..5 {
+            let y = self.solve(&x).unwrap();
+            estimate = norm1(&y);
+
+            let xi = y.map(|y_i| if y_i.gte_zero() { T::one() } else { -T::one() });
+            let z = self.solve_transpose(&xi).unwrap();
+
+            let mut max = (0, z[0].abs());
+            for j in 1..n {
+                let abs = z[j].abs();
+                if abs > max.1 {
+                    max = (j, abs);
+                }
+            }
+
+            let ztx: T = (0..n).map(|j| z[j].clone() * x[j].clone()).sum();
+            if max.1 <= ztx || last == Some(max.0) {
+                break;
+            }
+
+            x = Vect::from_elem(side, &T::zero());
+            x[max.0] = T::one();
+            last = Some(max.0);
+        }
+
+        // Higham's safeguard: an alternating vector which catches the matrices on which Hager's
+        // iteration is known to badly underestimate.
+        if n > 1 {
+            let denom = T::from_usize(n - 1);
+            let b = Vect::from_fn(side, |i| {
+                let v = T::one() + T::from_usize(i) / denom.clone();
+                if i % 2 == 0 { v } else { -v }
+            });
+
+            let two = T::one() + T::one();
+            let alt = two * norm1(&self.solve(&b).unwrap()) / T::from_usize(3 * n);
+
+            if alt > estimate {
+                estimate = alt;
+            }
+        }
+
+        Some(estimate)
+    }
+
+
+    /// Estimates the 1-norm condition number `||A||_1 * ||A^-1||_1`. Returns `None` if `A` is
+    /// singular.
+    pub fn cond1_estimate(&self) -> Option<T> {
+        self.inverse_norm1_estimate().map(|inv| self.norm1.clone() * inv)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linalg::{Row, VectorNorm};
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn lu_solve_3x3() {
+        let a = Mat![#row [ 2.,  1., -1.],
+                          [-3., -1.,  2.],
+                          [-2.,  1.,  2.]];
+
+        let lu = a.lu();
+
+        assert!(!lu.is_singular());
+        assert!((lu.determinant() - -1.).abs() < 0.000001);
+
+        let x = lu.solve(&Vect![8., -11., -3.]).unwrap();
+        assert!((x - Vect![2., 3., -1.]).norm() < 0.000001);
+    }
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn lu_solve_transpose_3x3() {
+        let a = Mat![[ 2.,  1., -1.],
+                     [-3., -1.,  2.],
+                     [-2.,  1.,  2.]];
+        let at = Mat![#row [ 2., -3., -2.],
+                           [ 1., -1.,  1.],
+                           [-1.,  2.,  2.]];
+
+        let x = a.lu().solve_transpose(&Vect![8., -11., -3.]).unwrap();
+        let y = at.lu().solve(&Vect![8., -11., -3.]).unwrap();
+
+        assert!((x - y).norm() < 0.000001);
+    }
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn lu_inverse_2x2() {
+        let a = Mat![[1., 2.],
+                     [3., 4.]];
+
+        let inv = a.lu().inverse().unwrap();
+
+        let expected = Mat![[-2.,  1. ],
+                            [ 1.5, -0.5]];
+
+        assert!(inv.zip_map(expected, |x, y| (x - y).abs()).fold(0., |m, &e| m + e) < 0.000001);
+    }
+
+    #[test]
+    fn lu_singular() {
+        let a: Mat<f64, _, _, Row> = Mat![#row [1., 2.], [2., 4.]];
+        let lu = a.lu();
+
+        assert!(lu.is_singular());
+        assert!(lu.solve(&Vect![1., 1.]).is_none());
+        assert!(lu.cond1_estimate().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn lu_cond1_estimate() {
+        // A^-1 = [[-2, 1], [1.5, -0.5]], so ||A||_1 = 6, ||A^-1||_1 = 3.5 and cond_1(A) = 21.
+        let a = Mat![[1., 2.],
+                     [3., 4.]];
+
+        let cond = a.lu().cond1_estimate().unwrap();
+
+        assert!((cond - 21.).abs() < 0.000001);
+    }
+}
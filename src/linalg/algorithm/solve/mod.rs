@@ -1 +1,2 @@
 pub mod gaussian;
+pub mod lu;
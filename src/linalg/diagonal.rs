@@ -0,0 +1,322 @@
+use std::ops::{Mul, Index, IndexMut};
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use linalg::vector::*;
+use typehack::dim::*;
+
+
+/// A square matrix whose only nonzero elements lie on its main diagonal. Only the diagonal is
+/// stored; off-diagonal elements read as zero and cannot be mutated, so the invariant is
+/// guaranteed by construction.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiagonalMat<T: Scalar, N: Dim> {
+    diag: DenseVec<T, N>,
+    zero: T,
+}
+
+
+impl<T: Scalar, N: Dim> DiagonalMat<T, N> {
+    pub fn from_diagonal(diag: DenseVec<T, N>) -> Self {
+        DiagonalMat {
+            diag: diag,
+            zero: T::zero(),
+        }
+    }
+
+
+    pub fn from_elem(side: N, elem: &T) -> Self {
+        DiagonalMat::from_diagonal(DenseVec::from_elem(side, elem))
+    }
+
+
+    pub fn from_fn<F: Fn(usize) -> T>(side: N, f: F) -> Self {
+        DiagonalMat::from_diagonal(DenseVec::from_fn(side, f))
+    }
+
+
+    pub fn diagonal(&self) -> &DenseVec<T, N> {
+        &self.diag
+    }
+
+
+    pub fn diagonal_mut(&mut self) -> &mut DenseVec<T, N> {
+        &mut self.diag
+    }
+
+
+    pub fn into_diagonal(self) -> DenseVec<T, N> {
+        self.diag
+    }
+
+
+    pub fn to_dense<L: Layout>(&self) -> DenseMat<T, N, N, L> {
+        let n = self.diag.size();
+        let mut out = DenseMat::from_elem(n, n, T::zero());
+
+        for i in 0..n.reify() {
+            out[[i, i]] = self.diag[i].clone();
+        }
+
+        out
+    }
+
+
+    /// Returns the inverse of this matrix, or `None` if any element of the diagonal is zero.
+    pub fn inverse(&self) -> Option<Self> {
+        if self.diag.as_slice().iter().any(|x| x.eq_zero()) {
+            return None;
+        }
+
+        Some(DiagonalMat::from_fn(self.diag.size(), |i| T::one() / self.diag[i].clone()))
+    }
+
+
+    /// Solves `self * x = b` for `x` by dividing through by the diagonal.
+    pub fn solve(&self, mut b: DenseVec<T, N>) -> DenseVec<T, N> {
+        assert_eq!(self.diag.size(), b.size());
+
+        for i in 0..b.len() {
+            b[i] /= self.diag[i].clone();
+        }
+
+        b
+    }
+}
+
+
+impl<T: Scalar, N: Dim> Matrix for DiagonalMat<T, N> {
+    type Rows = N;
+    type Cols = N;
+
+    type Scalar = T;
+
+
+    fn rows(&self) -> N {
+        self.diag.size()
+    }
+
+    fn cols(&self) -> N {
+        self.diag.size()
+    }
+
+
+    fn get(&self, row: usize, col: usize) -> &T {
+        let n = self.diag.len();
+
+        assert!(row < n && col < n,
+                "Matrix index out of bounds: [[{}, {}]] is out of bounds of [[{}, {}]]!",
+                row,
+                col,
+                n,
+                n);
+
+        if row == col { &self.diag[row] } else { &self.zero }
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        assert!(row == col,
+                "Cannot mutably index off-diagonal element [[{}, {}]] of a diagonal matrix!",
+                row,
+                col);
+
+        &mut self.diag[row]
+    }
+
+    fn swap(&mut self, a: [usize; 2], b: [usize; 2]) {
+        match (a[0] == a[1], b[0] == b[1]) {
+            (true, true) => self.diag.as_mut_slice().swap(a[0], b[0]),
+            (false, false) => {}
+            _ => {
+                panic!("Swapping [[{}, {}]] and [[{}, {}]] would break the diagonal invariant!",
+                       a[0],
+                       a[1],
+                       b[0],
+                       b[1])
+            }
+        }
+    }
+
+
+    fn row_switch_mut(&mut self, i: usize, j: usize) {
+        assert!(i == j,
+                "Switching rows {} and {} would break the diagonal invariant!",
+                i,
+                j);
+    }
+
+    fn row_mul_mut(&mut self, i: usize, c: &T) {
+        self.diag[i] *= c.clone();
+    }
+
+    fn row_add_mut(&mut self, i: usize, j: usize, c: &T) {
+        if i == j {
+            let k = self.diag[i].clone() * c.clone();
+            self.diag[i] += k;
+        } else {
+            assert!(c.eq_zero(),
+                    "Adding a multiple of row {} to row {} would break the diagonal invariant!",
+                    j,
+                    i);
+        }
+    }
+
+
+    fn col_switch_mut(&mut self, i: usize, j: usize) {
+        assert!(i == j,
+                "Switching columns {} and {} would break the diagonal invariant!",
+                i,
+                j);
+    }
+
+    fn col_mul_mut(&mut self, i: usize, c: &T) {
+        self.diag[i] *= c.clone();
+    }
+
+    fn col_add_mut(&mut self, i: usize, j: usize, c: &T) {
+        self.row_add_mut(i, j, c);
+    }
+}
+
+
+impl<T: Scalar, N: Dim> Square for DiagonalMat<T, N> {
+    type Side = N;
+
+    fn side(&self) -> N {
+        self.diag.size()
+    }
+}
+
+
+impl<T: Scalar, N: Dim> MatrixIdentity for DiagonalMat<T, N> {
+    fn eye(n: N) -> Self {
+        DiagonalMat::from_elem(n, &T::one())
+    }
+}
+
+
+impl<T: Scalar, N: Dim> MatrixTranspose<DiagonalMat<T, N>> for DiagonalMat<T, N> {
+    fn transpose(self) -> Self {
+        self
+    }
+}
+
+
+impl<T: Scalar, N: Dim> Mul for DiagonalMat<T, N> {
+    type Output = DiagonalMat<T, N>;
+
+    fn mul(self, rhs: DiagonalMat<T, N>) -> DiagonalMat<T, N> {
+        DiagonalMat::from_diagonal(self.diag * rhs.diag)
+    }
+}
+
+
+impl<T: Scalar, N: Dim> Mul<DenseVec<T, N>> for DiagonalMat<T, N> {
+    type Output = DenseVec<T, N>;
+
+    fn mul(self, rhs: DenseVec<T, N>) -> DenseVec<T, N> {
+        self.diag * rhs
+    }
+}
+
+
+impl<'a, T: Scalar, N: Dim> Mul<&'a DenseVec<T, N>> for &'a DiagonalMat<T, N> {
+    type Output = DenseVec<T, N>;
+
+    fn mul(self, rhs: &'a DenseVec<T, N>) -> DenseVec<T, N> {
+        &self.diag * rhs
+    }
+}
+
+
+/// Multiplying on the left by a diagonal matrix scales each row of the right-hand side.
+impl<T: Scalar, N: Dim, P: Dim, L: Layout> Mul<DenseMat<T, N, P, L>> for DiagonalMat<T, N> {
+    type Output = DenseMat<T, N, P, L>;
+
+    fn mul(self, mut rhs: DenseMat<T, N, P, L>) -> DenseMat<T, N, P, L> {
+        assert_eq!(self.diag.size(), rhs.rows());
+
+        for i in 0..self.diag.len() {
+            rhs.row_mul_mut(i, &self.diag[i]);
+        }
+
+        rhs
+    }
+}
+
+
+/// Multiplying on the right by a diagonal matrix scales each column of the left-hand side.
+impl<T: Scalar, M: Dim, N: Dim, L: Layout> Mul<DiagonalMat<T, N>> for DenseMat<T, M, N, L> {
+    type Output = DenseMat<T, M, N, L>;
+
+    fn mul(mut self, rhs: DiagonalMat<T, N>) -> DenseMat<T, M, N, L> {
+        assert_eq!(self.cols(), rhs.diag.size());
+
+        for j in 0..rhs.diag.len() {
+            self.col_mul_mut(j, &rhs.diag[j]);
+        }
+
+        self
+    }
+}
+
+
+impl<T: Scalar, N: Dim> Index<[usize; 2]> for DiagonalMat<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, idx: [usize; 2]) -> &T {
+        self.get(idx[0], idx[1])
+    }
+}
+
+
+impl<T: Scalar, N: Dim> IndexMut<[usize; 2]> for DiagonalMat<T, N> {
+    #[inline]
+    fn index_mut(&mut self, idx: [usize; 2]) -> &mut T {
+        self.get_mut(idx[0], idx[1])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diag_index_off_diagonal_is_zero() {
+        let d = DiagonalMat::from_diagonal(Vect![1, 2, 3]);
+
+        assert_eq!(d[[0, 0]], 1);
+        assert_eq!(d[[1, 1]], 2);
+        assert_eq!(d[[2, 2]], 3);
+        assert_eq!(d[[0, 2]], 0);
+        assert_eq!(d[[2, 1]], 0);
+    }
+
+
+    #[test]
+    #[should_panic]
+    fn diag_index_mut_off_diagonal_panics() {
+        let mut d = DiagonalMat::from_diagonal(Vect![1, 2, 3]);
+        d[[0, 1]] = 4;
+    }
+
+
+    #[test]
+    fn diag_mul_dense_scales_rows_and_cols() {
+        let d = DiagonalMat::from_diagonal(Vect![2, 3]);
+        let a = Mat![[1, 1], [1, 1]];
+
+        assert_eq!(d.clone() * a.clone(), Mat![[2, 2], [3, 3]]);
+        assert_eq!(a * d, Mat![[2, 3], [2, 3]]);
+    }
+
+
+    #[test]
+    fn diag_solve() {
+        let d = DiagonalMat::from_diagonal(Vect![2., 4., 8.]);
+
+        assert_eq!(d.solve(Vect![2., 2., 2.]), Vect![1., 0.5, 0.25]);
+    }
+}
@@ -0,0 +1,390 @@
+use std::ops::{Add, Mul, Neg};
+
+use geometry::primitive::Point;
+use geometry::transform::Isometry;
+use linalg::{Layout, Mat4x4, Quat, Rotation, Scalar, Vect3, VectorNorm};
+use num::traits::Float;
+use typehack::binary::B3;
+
+
+/// A dual quaternion `real + dual ε`, with `ε² = 0`. Unit dual quaternions represent rigid
+/// motions in three dimensions: the rotation `r` followed by the translation `t` is
+/// `r + (t r / 2) ε`, where `t` is read as a pure quaternion.
+///
+/// Unlike matrices, unit dual quaternions can be blended and renormalized without shearing, which
+/// makes them well suited to skinning. Motions compose like matrices: `(p * q).transform_point(x)`
+/// applies `q`, then `p`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DualQuat<T: Scalar> {
+    pub real: Quat<T>,
+    pub dual: Quat<T>,
+}
+
+
+impl<T: Scalar> DualQuat<T> {
+    pub fn new(real: Quat<T>, dual: Quat<T>) -> Self {
+        DualQuat {
+            real: real,
+            dual: dual,
+        }
+    }
+
+
+    pub fn identity() -> Self {
+        DualQuat::new(Quat::identity(), Quat::new(T::zero(), T::zero(), T::zero(), T::zero()))
+    }
+
+
+    /// The rotation `rotation`, which should be of unit length, followed by the translation
+    /// `translation`.
+    pub fn from_rotation_translation(rotation: Quat<T>, translation: Vect3<T>) -> Self {
+        let half = T::one() / (T::one() + T::one());
+        let dual = (Quat::from_parts(T::zero(), translation) * rotation.clone()).scale(half);
+
+        DualQuat::new(rotation, dual)
+    }
+
+
+    pub fn from_translation(translation: Vect3<T>) -> Self {
+        DualQuat::from_rotation_translation(Quat::identity(), translation)
+    }
+
+
+    /// The quaternion conjugate of both parts, which is the inverse of a unit dual quaternion.
+    pub fn conjugate(&self) -> Self {
+        DualQuat::new(self.real.conjugate(), self.dual.conjugate())
+    }
+
+
+    /// The rotation, assuming unit length.
+    pub fn rotation(&self) -> Quat<T> {
+        self.real.clone()
+    }
+
+
+    /// The translation, assuming unit length.
+    pub fn translation(&self) -> Vect3<T> {
+        let two = T::one() + T::one();
+        (self.dual.clone() * self.real.conjugate()).vector_part() * two
+    }
+
+
+    fn scale(&self, c: T) -> Self {
+        DualQuat::new(self.real.scale(c.clone()), self.dual.scale(c))
+    }
+}
+
+
+impl<T: Scalar + Float> DualQuat<T> {
+    /// The inverse motion, assuming unit length.
+    pub fn inverse(&self) -> Self {
+        self.conjugate()
+    }
+
+
+    /// The norm of the real part. A unit dual quaternion also has its dual part orthogonal to its
+    /// real part.
+    pub fn norm(&self) -> T {
+        self.real.norm()
+    }
+
+
+    /// Scales the dual quaternion so that its real part has unit length, and removes any component
+    /// of the dual part parallel to the real part, making it a unit dual quaternion.
+    pub fn normalize(&self) -> Self {
+        let unit = self.scale(self.norm().recip());
+        let parallel = unit.real.dot(&unit.dual);
+
+        DualQuat::new(unit.real.clone(), unit.dual - unit.real.scale(parallel))
+    }
+
+
+    pub fn transform_point(&self, p: &Point<T, B3>) -> Point<T, B3> {
+        Point::from(self.real.rotate(&Vect3::from(p.clone())) + self.translation())
+    }
+
+
+    /// Rotates a vector; translation does not apply to vectors.
+    pub fn transform_vect(&self, v: &Vect3<T>) -> Vect3<T> {
+        self.real.rotate(v)
+    }
+
+
+    /// Screw linear interpolation: the motion a fraction `t` of the way along the screw motion
+    /// from `self` to `rhs`, taking the shorter path. Interpolated motions rotate at a constant
+    /// angular velocity about, and translate at a constant speed along, a single fixed axis. Both
+    /// dual quaternions are assumed to be of unit length.
+    pub fn sclerp(&self, rhs: &Self, t: T) -> Self {
+        let two = T::one() + T::one();
+
+        let diff = self.conjugate() * rhs.clone();
+        let diff = if diff.real.w.lt_zero() { -diff } else { diff };
+
+        let v = diff.real.vector_part();
+        let sin = v.norm();
+
+        // Without rotation the screw axis is undefined and the motion is a translation; for
+        // rotations too small to find the axis of accurately, interpolating the rotation and the
+        // translation separately is just as good.
+        if sin <= T::epsilon().sqrt() {
+            let rotation = Quat::identity().nlerp(&diff.real, t.clone());
+            let step = DualQuat::from_rotation_translation(rotation, diff.translation() * t);
+
+            return self.clone() * step;
+        }
+
+        // Decompose the relative motion into a rotation by `2 * half_angle` about the line with direction
+        // `axis` and moment `moment`, and a translation by `pitch` along it.
+        let half_angle = sin.clone().atan2(diff.real.w.clone());
+        let axis = v / sin.clone();
+        let pitch = -two.clone() * diff.dual.w.clone() / sin.clone();
+        let moment = (diff.dual.vector_part() -
+                      axis.clone() * (pitch.clone() * diff.real.w.clone() / two.clone())) /
+                     sin;
+
+        let half_angle = half_angle * t.clone();
+        let half_pitch = pitch * t / two;
+        let (sin, cos) = (half_angle.clone().sin(), half_angle.cos());
+
+        let step = DualQuat::new(Quat::from_parts(cos.clone(), axis.clone() * sin.clone()),
+                                 Quat::from_parts(-half_pitch.clone() * sin.clone(),
+                                                  moment * sin + axis * (half_pitch * cos)));
+
+        self.clone() * step
+    }
+
+
+    /// Dual quaternion linear blending: the normalized weighted sum of unit dual quaternions.
+    /// Each dual quaternion is first negated if needed to lie in the same hemisphere as the first,
+    /// so that the blend takes the shorter path. Returns `None` if there is nothing to blend or
+    /// the weights cancel out.
+    pub fn blend(weighted: &[(T, DualQuat<T>)]) -> Option<Self> {
+        let pivot = match weighted.first() {
+            Some(&(_, ref first)) => first.real.clone(),
+            None => return None,
+        };
+
+        let zero = Quat::new(T::zero(), T::zero(), T::zero(), T::zero());
+        let sum = weighted.iter().fold(DualQuat::new(zero.clone(), zero), |sum, &(ref w, ref q)| {
+            if pivot.dot(&q.real).lt_zero() {
+                sum + q.scale(-w.clone())
+            } else {
+                sum + q.scale(w.clone())
+            }
+        });
+
+        if sum.real.squared_norm() <= T::epsilon() {
+            None
+        } else {
+            Some(sum.normalize())
+        }
+    }
+}
+
+
+/// Composition: `a * b` applies `b`, then `a`.
+impl<T: Scalar> Mul for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    fn mul(self, rhs: DualQuat<T>) -> DualQuat<T> {
+        let dual = self.real.clone() * rhs.dual + self.dual * rhs.real.clone();
+        DualQuat::new(self.real * rhs.real, dual)
+    }
+}
+
+
+impl<T: Scalar + Float> Mul<Vect3<T>> for DualQuat<T> {
+    type Output = Vect3<T>;
+
+    fn mul(self, rhs: Vect3<T>) -> Vect3<T> {
+        self.transform_vect(&rhs)
+    }
+}
+
+
+impl<T: Scalar + Float> Mul<Point<T, B3>> for DualQuat<T> {
+    type Output = Point<T, B3>;
+
+    fn mul(self, rhs: Point<T, B3>) -> Point<T, B3> {
+        self.transform_point(&rhs)
+    }
+}
+
+
+impl<T: Scalar> Add for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    fn add(self, rhs: DualQuat<T>) -> DualQuat<T> {
+        DualQuat::new(self.real + rhs.real, self.dual + rhs.dual)
+    }
+}
+
+
+impl<T: Scalar> Neg for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    fn neg(self) -> DualQuat<T> {
+        DualQuat::new(-self.real, -self.dual)
+    }
+}
+
+
+impl<T: Scalar> From<Quat<T>> for DualQuat<T> {
+    fn from(rotation: Quat<T>) -> Self {
+        DualQuat::from_rotation_translation(rotation,
+                                            Vect3!(T::zero(), T::zero(), T::zero()))
+    }
+}
+
+
+impl<T: Scalar + Float> From<Rotation<T, B3>> for DualQuat<T> {
+    fn from(rotation: Rotation<T, B3>) -> Self {
+        Quat::from(rotation).into()
+    }
+}
+
+
+impl<T: Scalar + Float> From<Isometry<T, B3>> for DualQuat<T> {
+    fn from(isometry: Isometry<T, B3>) -> Self {
+        DualQuat::from_rotation_translation(isometry.rotation.into(), isometry.translation)
+    }
+}
+
+
+impl<T: Scalar + Float> From<DualQuat<T>> for Isometry<T, B3> {
+    fn from(dq: DualQuat<T>) -> Self {
+        let translation = dq.translation();
+        Isometry::new(dq.real.into(), translation)
+    }
+}
+
+
+/// The homogeneous transformation matrix.
+impl<T: Scalar + Float, L: Layout> From<DualQuat<T>> for Mat4x4<T, L> {
+    fn from(dq: DualQuat<T>) -> Mat4x4<T, L> {
+        let translation = dq.translation();
+        let mut m: Mat4x4<T, L> = dq.real.into();
+
+        for i in 0..3 {
+            m[[i, 3]] = translation[i].clone();
+        }
+
+        m
+    }
+}
+
+
+/// The rigid motion of a homogeneous transformation matrix, which is assumed to have an
+/// orthogonal upper-left `3 x 3` block with a determinant of one. Any projection is ignored.
+impl<'a, T: Scalar + Float, L: Layout> From<&'a Mat4x4<T, L>> for DualQuat<T> {
+    fn from(m: &Mat4x4<T, L>) -> DualQuat<T> {
+        DualQuat::from_rotation_translation(Quat::from(m),
+                                            Vect3!(m[[0, 3]].clone(),
+                                                   m[[1, 3]].clone(),
+                                                   m[[2, 3]].clone()))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn assert_vect_eq(u: &Vect3<f64>, v: &Vect3<f64>) {
+        assert!((0..3).all(|i| (u[i] - v[i]).abs() < 1e-12), "{:?} != {:?}", u, v);
+    }
+
+    fn screw(angle: f64, lift: f64) -> DualQuat<f64> {
+        DualQuat::from_rotation_translation(Quat::from_axis_angle(&Vect3!(0., 0., 1.), angle),
+                                            Vect3!(0., 0., lift))
+    }
+
+    #[test]
+    fn dual_quat_composition() {
+        let a = DualQuat::from_rotation_translation(Quat::from_axis_angle(&Vect3!(1., 2., 0.), 0.7),
+                                                    Vect3!(1., -1., 3.));
+        let b = DualQuat::from_rotation_translation(Quat::from_axis_angle(&Vect3!(0., 1., 1.), 2.),
+                                                    Vect3!(0., 4., 0.5));
+        let p = Point::from(Vect3!(0.5, 2., -1.));
+
+        let expected = a.transform_point(&b.transform_point(&p));
+        assert_vect_eq(&Vect3::from((a * b).transform_point(&p)), &Vect3::from(expected));
+
+        let back = (a.inverse() * a).transform_point(&p);
+        assert_vect_eq(&Vect3::from(back), &Vect3!(0.5, 2., -1.));
+
+        assert_vect_eq(&(a * Vect3!(0., 0., 0.)), &Vect3!(0., 0., 0.));
+        assert_vect_eq(&a.translation(), &Vect3!(1., -1., 3.));
+    }
+
+    #[test]
+    fn dual_quat_normalize() {
+        let a = screw(1., 2.);
+        let skewed = DualQuat::new(a.real.scale(3.), a.dual.scale(3.) + a.real.scale(0.25));
+        let n = skewed.normalize();
+
+        assert!((n.norm() - 1.).abs() < 1e-12);
+        assert!(n.real.dot(&n.dual).abs() < 1e-12);
+        assert_vect_eq(&n.translation(), &Vect3!(0., 0., 2.));
+    }
+
+    #[test]
+    fn dual_quat_sclerp() {
+        let a = DualQuat::identity();
+        let b = screw(PI / 2., 2.);
+
+        let mid = a.sclerp(&b, 0.5);
+        assert_vect_eq(&mid.translation(), &Vect3!(0., 0., 1.));
+        assert!((mid.rotation().to_axis_angle().1 - PI / 4.).abs() < 1e-12);
+
+        let end = a.sclerp(&b, 1.);
+        assert!((end.real - b.real).squared_norm() < 1e-24);
+        assert_vect_eq(&end.translation(), &b.translation());
+
+        // A rotation about an axis off the origin moves the origin along an arc, not a chord.
+        let offset = DualQuat::from_translation(Vect3!(1., 0., 0.));
+        let c = offset * screw(PI / 2., 0.) * offset.inverse();
+        let half = a.sclerp(&c, 0.5).transform_point(&Point::from(Vect3!(0., 0., 0.)));
+        let r = 0.5f64.sqrt();
+        assert_vect_eq(&Vect3::from(half), &Vect3!(1. - r, -r, 0.));
+
+        let shift = DualQuat::from_translation(Vect3!(4., 0., 0.));
+        assert_vect_eq(&a.sclerp(&shift, 0.25).translation(), &Vect3!(1., 0., 0.));
+    }
+
+    #[test]
+    fn dual_quat_blend() {
+        let a = screw(0.4, 1.);
+        let b = screw(0.8, 3.);
+
+        let blended = DualQuat::blend(&[(0.5, a), (0.5, -b)]).unwrap();
+        assert!((blended.rotation().to_axis_angle().1 - 0.6).abs() < 1e-12);
+        assert_vect_eq(&blended.translation(), &Vect3!(0., 0., 2.));
+
+        assert!(DualQuat::<f64>::blend(&[]).is_none());
+        assert!(DualQuat::blend(&[(1., a), (-1., a)]).is_none());
+    }
+
+    #[test]
+    fn dual_quat_conversions() {
+        let rotation = Quat::from_axis_angle(&Vect3!(1., 1., 1.), 1.1);
+        let dq = DualQuat::from_rotation_translation(rotation, Vect3!(2., 0., -1.));
+        let p = Point::from(Vect3!(1., 2., 3.));
+
+        let iso = Isometry::from(dq);
+        assert_vect_eq(&Vect3::from(iso.transform_point(&p)),
+                       &Vect3::from(dq.transform_point(&p)));
+        let round = DualQuat::from(iso);
+        assert_vect_eq(&round.translation(), &dq.translation());
+
+        let m: Mat4x4<f64> = dq.into();
+        let from_mat = DualQuat::from(&m);
+        assert!((from_mat.real.dot(&dq.real).abs() - 1.).abs() < 1e-12);
+        assert_vect_eq(&from_mat.translation(), &Vect3!(2., 0., -1.));
+
+        let pure = DualQuat::from(Rotation::from(rotation));
+        assert_vect_eq(&pure.translation(), &Vect3!(0., 0., 0.));
+    }
+}
@@ -0,0 +1,490 @@
+//! Reading and writing the [Matrix Market](http://math.nist.gov/MatrixMarket/formats.html)
+//! exchange format.
+//!
+//! Both the `coordinate` (sparse) and `array` (dense) formats are supported, with `real`,
+//! `integer` and `pattern` fields and `general`, `symmetric` and `skew-symmetric` storage.
+//! Matrices are always read into a dense `DenseMat<T, Dyn, Dyn, L>`.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use typehack::dim::*;
+
+
+/// A scalar which can be stored in a Matrix Market file.
+pub trait MarketScalar: Scalar + FromStr + Display {
+    /// The name of the Matrix Market field used when writing matrices of this type.
+    fn field() -> &'static str;
+}
+
+
+macro_rules! impl_market_scalar {
+    ($field:expr => $($t:ident),*) => {
+        $(impl MarketScalar for $t {
+            fn field() -> &'static str {
+                $field
+            }
+        })*
+    }
+}
+
+impl_market_scalar!("integer" => i8, i16, i32, i64);
+impl_market_scalar!("real" => f32, f64);
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Only nonzero entries are stored, one `row col value` triple per line.
+    Coordinate,
+    /// Every entry is stored, in column-major order.
+    Array,
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    General,
+    /// Only the lower triangle is stored; `a[[j, i]] == a[[i, j]]`.
+    Symmetric,
+    /// Only the strict lower triangle is stored; `a[[j, i]] == -a[[i, j]]`.
+    SkewSymmetric,
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Real,
+    Integer,
+    Pattern,
+}
+
+
+#[derive(Debug)]
+pub enum MatrixMarketError {
+    Io(io::Error),
+    /// The banner or size line is missing or malformed, or gives a symmetric matrix which is not
+    /// square. Carries the one-based line number.
+    InvalidHeader(usize),
+    /// The banner names a format, field or symmetry which is not supported, such as `complex`.
+    Unsupported(String),
+    /// An entry could not be parsed. Carries the one-based line number.
+    InvalidEntry(usize),
+    /// An entry lies outside the matrix, or in the upper triangle of a symmetric matrix. Carries
+    /// the one-based line number.
+    IndexOutOfBounds(usize),
+    /// The file ended before all of the entries announced by its size line were read.
+    MissingEntries { expected: usize, found: usize },
+}
+
+
+impl Display for MatrixMarketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MatrixMarketError::Io(ref err) => write!(f, "{}", err),
+            MatrixMarketError::InvalidHeader(line) => write!(f, "line {}: invalid header", line),
+            MatrixMarketError::Unsupported(ref what) => write!(f, "unsupported {:?}", what),
+            MatrixMarketError::InvalidEntry(line) => write!(f, "line {}: invalid entry", line),
+            MatrixMarketError::IndexOutOfBounds(line) => {
+                write!(f, "line {}: index out of bounds", line)
+            }
+            MatrixMarketError::MissingEntries { expected, found } => {
+                write!(f, "expected {} entries, found {}", expected, found)
+            }
+        }
+    }
+}
+
+
+impl Error for MatrixMarketError {
+    fn description(&self) -> &str {
+        match *self {
+            MatrixMarketError::Io(ref err) => err.description(),
+            _ => "invalid Matrix Market file",
+        }
+    }
+}
+
+
+impl From<io::Error> for MatrixMarketError {
+    fn from(err: io::Error) -> MatrixMarketError {
+        MatrixMarketError::Io(err)
+    }
+}
+
+
+struct Header {
+    format: Format,
+    field: Field,
+    symmetry: Symmetry,
+}
+
+
+fn parse_banner(line: &str) -> Result<Header, MatrixMarketError> {
+    let words: Vec<String> = line.split_whitespace().map(|w| w.to_lowercase()).collect();
+
+    if words.len() != 5 || words[0] != "%%matrixmarket" {
+        return Err(MatrixMarketError::InvalidHeader(1));
+    }
+
+    if words[1] != "matrix" {
+        return Err(MatrixMarketError::Unsupported(words[1].clone()));
+    }
+
+    let format = match &words[2][..] {
+        "coordinate" => Format::Coordinate,
+        "array" => Format::Array,
+        other => return Err(MatrixMarketError::Unsupported(other.to_string())),
+    };
+
+    let field = match &words[3][..] {
+        "real" | "double" => Field::Real,
+        "integer" => Field::Integer,
+        "pattern" if format == Format::Coordinate => Field::Pattern,
+        other => return Err(MatrixMarketError::Unsupported(other.to_string())),
+    };
+
+    let symmetry = match &words[4][..] {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        "skew-symmetric" => Symmetry::SkewSymmetric,
+        other => return Err(MatrixMarketError::Unsupported(other.to_string())),
+    };
+
+    Ok(Header {
+        format: format,
+        field: field,
+        symmetry: symmetry,
+    })
+}
+
+
+fn parse_usize(word: Option<&str>, line: usize) -> Result<usize, MatrixMarketError> {
+    word.and_then(|w| w.parse().ok()).ok_or(MatrixMarketError::InvalidEntry(line))
+}
+
+
+fn parse_value<T: MarketScalar>(word: Option<&str>, line: usize) -> Result<T, MatrixMarketError> {
+    word.and_then(|w| w.parse().ok()).ok_or(MatrixMarketError::InvalidEntry(line))
+}
+
+
+/// Stores an entry, mirroring it across the diagonal as the symmetry demands.
+fn store<T: Scalar, L: Layout>(mat: &mut DenseMat<T, Dyn, Dyn, L>,
+                               symmetry: Symmetry,
+                               i: usize,
+                               j: usize,
+                               x: T,
+                               line: usize)
+                               -> Result<(), MatrixMarketError> {
+    let (m, n) = (mat.rows().reify(), mat.cols().reify());
+
+    if i >= m || j >= n {
+        return Err(MatrixMarketError::IndexOutOfBounds(line));
+    }
+
+    match symmetry {
+        Symmetry::General => {}
+        Symmetry::Symmetric if i >= j => mat[[j, i]] = x.clone(),
+        Symmetry::SkewSymmetric if i > j => mat[[j, i]] = -x.clone(),
+        _ => return Err(MatrixMarketError::IndexOutOfBounds(line)),
+    }
+
+    mat[[i, j]] = x;
+
+    Ok(())
+}
+
+
+/// Reads a matrix in Matrix Market format. Entries not present in a coordinate file are zero,
+/// and entries of a pattern file are one.
+pub fn read<T: MarketScalar, L: Layout, R: BufRead>
+    (reader: R)
+     -> Result<DenseMat<T, Dyn, Dyn, L>, MatrixMarketError> {
+    let mut lines = reader.lines().enumerate().map(|(k, line)| (k + 1, line));
+
+    let header = match lines.next() {
+        Some((_, line)) => parse_banner(&line?)?,
+        None => return Err(MatrixMarketError::InvalidHeader(1)),
+    };
+
+    // Everything after the banner is either a comment, a blank line, or data.
+    let mut data = lines.filter(|&(_, ref line)| match *line {
+        Ok(ref line) => {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.starts_with('%')
+        }
+        Err(_) => true,
+    });
+
+    let (size_line, size) = match data.next() {
+        Some((k, line)) => (k, line?),
+        None => return Err(MatrixMarketError::InvalidHeader(2)),
+    };
+
+    let mut words = size.split_whitespace();
+    let bad_size = |_| MatrixMarketError::InvalidHeader(size_line);
+    let m = parse_usize(words.next(), size_line).map_err(&bad_size)?;
+    let n = parse_usize(words.next(), size_line).map_err(&bad_size)?;
+
+    // Mirroring an entry across the diagonal only stays in bounds when the matrix is square.
+    if header.symmetry != Symmetry::General && m != n {
+        return Err(MatrixMarketError::InvalidHeader(size_line));
+    }
+
+    // The matrix, and the index list of an array file, both hold up to `m * n` entries.
+    if m.checked_mul(n).is_none() {
+        return Err(MatrixMarketError::InvalidHeader(size_line));
+    }
+
+    let mut mat = DenseMat::from_elem(Dyn(m), Dyn(n), T::zero());
+
+    match header.format {
+        Format::Coordinate => {
+            let nnz = parse_usize(words.next(), size_line).map_err(&bad_size)?;
+
+            for found in 0..nnz {
+                let (k, line) = match data.next() {
+                    Some((k, line)) => (k, line?),
+                    None => {
+                        return Err(MatrixMarketError::MissingEntries {
+                            expected: nnz,
+                            found: found,
+                        })
+                    }
+                };
+
+                let mut words = line.split_whitespace();
+                let i = parse_usize(words.next(), k)?;
+                let j = parse_usize(words.next(), k)?;
+
+                if i == 0 || j == 0 {
+                    return Err(MatrixMarketError::IndexOutOfBounds(k));
+                }
+
+                let x = match header.field {
+                    Field::Pattern => T::one(),
+                    Field::Real | Field::Integer => parse_value(words.next(), k)?,
+                };
+
+                store(&mut mat, header.symmetry, i - 1, j - 1, x, k)?;
+            }
+        }
+
+        Format::Array => {
+            // Only the lower triangle of a symmetric matrix is stored, and skew-symmetric
+            // matrices omit the diagonal as well.
+            let mut indices = Vec::new();
+            for j in 0..n {
+                let first = match header.symmetry {
+                    Symmetry::General => 0,
+                    Symmetry::Symmetric => j,
+                    Symmetry::SkewSymmetric => j + 1,
+                };
+
+                for i in first..m {
+                    indices.push((i, j));
+                }
+            }
+
+            let expected = indices.len();
+            for (found, (i, j)) in indices.into_iter().enumerate() {
+                let (k, line) = match data.next() {
+                    Some((k, line)) => (k, line?),
+                    None => {
+                        return Err(MatrixMarketError::MissingEntries {
+                            expected: expected,
+                            found: found,
+                        })
+                    }
+                };
+
+                let x = parse_value(line.split_whitespace().next(), k)?;
+                store(&mut mat, header.symmetry, i, j, x, k)?;
+            }
+        }
+    }
+
+    Ok(mat)
+}
+
+
+/// Writes a matrix in Matrix Market format. Coordinate files list only nonzero entries. Asking
+/// for symmetric or skew-symmetric storage of a matrix which lacks that symmetry is an error of
+/// kind `InvalidInput`.
+pub fn write<T: MarketScalar, M: Dim, N: Dim, L: Layout, W: Write>(mut writer: W,
+                                                                   mat: &DenseMat<T, M, N, L>,
+                                                                   format: Format,
+                                                                   symmetry: Symmetry)
+                                                                   -> io::Result<()> {
+    let (m, n) = (mat.rows().reify(), mat.cols().reify());
+
+    let first_row = |j: usize| match symmetry {
+        Symmetry::General => 0,
+        Symmetry::Symmetric => j,
+        Symmetry::SkewSymmetric => j + 1,
+    };
+
+    if symmetry != Symmetry::General {
+        let symmetric = m == n &&
+                        (0..n).all(|j| {
+            (j..m).all(|i| match symmetry {
+                Symmetry::Symmetric => mat[[j, i]] == mat[[i, j]],
+                _ => mat[[j, i]] == -mat[[i, j]].clone(),
+            })
+        });
+
+        if !symmetric {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "matrix does not have the requested symmetry"));
+        }
+    }
+
+    let (format_name, symmetry_name) = (match format {
+                                            Format::Coordinate => "coordinate",
+                                            Format::Array => "array",
+                                        },
+                                        match symmetry {
+                                            Symmetry::General => "general",
+                                            Symmetry::Symmetric => "symmetric",
+                                            Symmetry::SkewSymmetric => "skew-symmetric",
+                                        });
+
+    writeln!(writer,
+             "%%MatrixMarket matrix {} {} {}",
+             format_name,
+             T::field(),
+             symmetry_name)?;
+
+    match format {
+        Format::Coordinate => {
+            let mut entries = Vec::new();
+            for j in 0..n {
+                for i in first_row(j)..m {
+                    if mat[[i, j]] != T::zero() {
+                        entries.push((i, j));
+                    }
+                }
+            }
+
+            writeln!(writer, "{} {} {}", m, n, entries.len())?;
+            for (i, j) in entries {
+                writeln!(writer, "{} {} {}", i + 1, j + 1, mat[[i, j]])?;
+            }
+        }
+
+        Format::Array => {
+            writeln!(writer, "{} {}", m, n)?;
+            for j in 0..n {
+                for i in first_row(j)..m {
+                    writeln!(writer, "{}", mat[[i, j]])?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linalg::{Column, Row};
+
+    const GENERAL_COORDINATE: &'static str =
+        include_str!("../../../tests/matrix_market/general_coordinate.mtx");
+    const SYMMETRIC_ARRAY: &'static str =
+        include_str!("../../../tests/matrix_market/symmetric_array.mtx");
+    const PATTERN_SYMMETRIC: &'static str =
+        include_str!("../../../tests/matrix_market/pattern_symmetric.mtx");
+    const TRUNCATED: &'static str = include_str!("../../../tests/matrix_market/truncated.mtx");
+
+    #[test]
+    fn read_general_coordinate() {
+        let a: DenseMat<f64, Dyn, Dyn, Column> = read(GENERAL_COORDINATE.as_bytes()).unwrap();
+
+        assert_eq!(a.rows(), Dyn(3));
+        assert_eq!(a.cols(), Dyn(4));
+        assert_eq!(a[[0, 0]], 1.5);
+        assert_eq!(a[[1, 1]], -2.);
+        assert_eq!(a[[2, 2]], 32.5);
+        assert_eq!(a[[0, 3]], 4.);
+        assert_eq!(a[[2, 0]], 0.5);
+        assert_eq!(a[[1, 0]], 0.);
+    }
+
+    #[test]
+    fn read_symmetric_array() {
+        let a: DenseMat<i32, Dyn, Dyn, Row> = read(SYMMETRIC_ARRAY.as_bytes()).unwrap();
+
+        assert_eq!(a.as_slice(), &[1, 2, 3, 2, 4, 5, 3, 5, 6]);
+    }
+
+    #[test]
+    fn read_pattern_symmetric() {
+        let a: DenseMat<f32, Dyn, Dyn, Column> = read(PATTERN_SYMMETRIC.as_bytes()).unwrap();
+
+        assert_eq!(a.as_slice(), &[1., 0., 1., 0., 0., 1., 1., 1., 0.]);
+    }
+
+    #[test]
+    fn read_errors() {
+        match read::<f64, Column, _>(TRUNCATED.as_bytes()) {
+            Err(MatrixMarketError::MissingEntries { expected: 3, found: 2 }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let complex = "%%MatrixMarket matrix coordinate complex general\n";
+        match read::<f64, Column, _>(complex.as_bytes()) {
+            Err(MatrixMarketError::Unsupported(ref what)) if what == "complex" => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let out_of_bounds = "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n";
+        match read::<f64, Column, _>(out_of_bounds.as_bytes()) {
+            Err(MatrixMarketError::IndexOutOfBounds(3)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let non_square = "%%MatrixMarket matrix coordinate real symmetric\n3 2 1\n3 1 1.0\n";
+        match read::<f64, Column, _>(non_square.as_bytes()) {
+            Err(MatrixMarketError::InvalidHeader(2)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let huge = format!("%%MatrixMarket matrix array real general\n{} 2\n",
+                           usize::max_value());
+        match read::<f64, Column, _>(huge.as_bytes()) {
+            Err(MatrixMarketError::InvalidHeader(2)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+
+    #[test]
+    fn write_read_round_trip() {
+        let a: DenseMat<i32, Dyn, Dyn, Row> = read(SYMMETRIC_ARRAY.as_bytes()).unwrap();
+
+        for &format in &[Format::Coordinate, Format::Array] {
+            for &symmetry in &[Symmetry::General, Symmetry::Symmetric] {
+                let mut buf = Vec::new();
+                write(&mut buf, &a, format, symmetry).unwrap();
+
+                let b: DenseMat<i32, Dyn, Dyn, Row> = read(&buf[..]).unwrap();
+                assert_eq!(a, b);
+            }
+        }
+
+        let mut buf = Vec::new();
+        write(&mut buf, &a, Format::Array, Symmetry::Symmetric).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(),
+                   "%%MatrixMarket matrix array integer symmetric\n3 3\n1\n2\n3\n4\n5\n6\n");
+
+        let b = Mat![[1., 2.], [3., 4.]];
+        assert!(write(Vec::new(), &b, Format::Coordinate, Symmetry::Symmetric).is_err());
+    }
+}
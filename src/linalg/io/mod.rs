@@ -0,0 +1,2 @@
+pub mod matrix_market;
+pub mod npy;
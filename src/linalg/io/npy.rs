@@ -0,0 +1,626 @@
+//! Reading and writing NumPy's [`.npy`](https://numpy.org/neps/nep-0001-npy-format.html) binary
+//! format, versions 1.0 and 2.0 (and 3.0, which differs from 2.0 only in header encoding).
+//!
+//! Matrices are stored as two-dimensional arrays. A `Column` matrix is written with
+//! `fortran_order: True` so that its memory can be written out as is, and either ordering can be
+//! read into either layout. `array::container::Array`s are always written in C order.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use array::container::{Array, Dims};
+use iter_exact::CollectExactExt;
+use linalg::matrix::*;
+use linalg::traits::*;
+use typehack::dim::*;
+
+
+const MAGIC: &'static [u8] = b"\x93NUMPY";
+
+
+/// A scalar with a fixed-size binary representation which NumPy understands.
+pub trait NpyScalar: Scalar {
+    /// The NumPy type kind: `f` for floats and `i` for signed integers.
+    fn kind() -> char;
+
+    /// The size in bytes.
+    fn size() -> usize;
+
+    /// Reinterprets the low `size()` bytes of `bits` as a value.
+    fn from_raw_bits(u64) -> Self;
+    fn to_raw_bits(&self) -> u64;
+}
+
+
+macro_rules! impl_npy_scalar_integral {
+    ($($t:ident as $u:ident),*) => {
+        $(impl NpyScalar for $t {
+            fn kind() -> char {
+                'i'
+            }
+
+            fn size() -> usize {
+                ::std::mem::size_of::<$t>()
+            }
+
+            fn from_raw_bits(bits: u64) -> $t {
+                bits as $u as $t
+            }
+
+            fn to_raw_bits(&self) -> u64 {
+                *self as $u as u64
+            }
+        })*
+    }
+}
+
+
+macro_rules! impl_npy_scalar_float {
+    ($($t:ident as $u:ident),*) => {
+        $(impl NpyScalar for $t {
+            fn kind() -> char {
+                'f'
+            }
+
+            fn size() -> usize {
+                ::std::mem::size_of::<$t>()
+            }
+
+            fn from_raw_bits(bits: u64) -> $t {
+                $t::from_bits(bits as $u)
+            }
+
+            fn to_raw_bits(&self) -> u64 {
+                $t::to_bits(*self) as u64
+            }
+        })*
+    }
+}
+
+
+impl_npy_scalar_integral!(i8 as u8, i16 as u16, i32 as u32, i64 as u64);
+impl_npy_scalar_float!(f32 as u32, f64 as u64);
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+
+impl Endian {
+    fn native() -> Endian {
+        if cfg!(target_endian = "big") {
+            Endian::Big
+        } else {
+            Endian::Little
+        }
+    }
+}
+
+
+#[derive(Debug)]
+pub enum NpyError {
+    Io(io::Error),
+    /// The file does not begin with the `.npy` magic string.
+    BadMagic,
+    UnsupportedVersion(u8, u8),
+    /// The header dictionary is missing a key or could not be parsed.
+    InvalidHeader,
+    /// The stored dtype is not the one requested, e.g. `<f4` when reading `f64`s.
+    DtypeMismatch { expected: String, found: String },
+    /// The stored shape does not fit the requested type.
+    ShapeMismatch(Vec<usize>),
+    /// The stored shape describes more bytes than can be addressed on this platform.
+    ShapeOverflow(Vec<usize>),
+}
+
+
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NpyError::Io(ref err) => write!(f, "{}", err),
+            NpyError::BadMagic => write!(f, "not a .npy file"),
+            NpyError::UnsupportedVersion(major, minor) => {
+                write!(f, "unsupported .npy version {}.{}", major, minor)
+            }
+            NpyError::InvalidHeader => write!(f, "invalid .npy header"),
+            NpyError::DtypeMismatch { ref expected, ref found } => {
+                write!(f, "expected dtype {}, found {}", expected, found)
+            }
+            NpyError::ShapeMismatch(ref shape) => write!(f, "unexpected shape {:?}", shape),
+            NpyError::ShapeOverflow(ref shape) => write!(f, "shape {:?} is too large", shape),
+        }
+    }
+}
+
+
+impl Error for NpyError {
+    fn description(&self) -> &str {
+        match *self {
+            NpyError::Io(ref err) => err.description(),
+            _ => "invalid .npy file",
+        }
+    }
+}
+
+
+impl From<io::Error> for NpyError {
+    fn from(err: io::Error) -> NpyError {
+        NpyError::Io(err)
+    }
+}
+
+
+struct Header {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+
+fn descr<T: NpyScalar>(endian: Endian) -> String {
+    let order = match (T::size(), endian) {
+        (1, _) => '|',
+        (_, Endian::Little) => '<',
+        (_, Endian::Big) => '>',
+    };
+
+    format!("{}{}{}", order, T::kind(), T::size())
+}
+
+
+/// Finds the text following `'key':` in a header dictionary.
+fn dict_value<'a>(dict: &'a str, key: &str) -> Option<&'a str> {
+    let quoted = ["'", key, "'"].concat();
+    let start = match dict.find(&quoted[..]) {
+        Some(start) => start + quoted.len(),
+        None => return None,
+    };
+
+    let rest = dict[start..].trim_start();
+
+    if rest.starts_with(':') {
+        Some(rest[1..].trim_start())
+    } else {
+        None
+    }
+}
+
+
+fn parse_header(dict: &str) -> Option<Header> {
+    let descr = match dict_value(dict, "descr") {
+        Some(value) if value.len() > 1 => value[1..].split(|c| c == '\'' || c == '"').next(),
+        _ => None,
+    };
+
+    let fortran_order = match dict_value(dict, "fortran_order") {
+        Some(value) if value.starts_with("True") => Some(true),
+        Some(value) if value.starts_with("False") => Some(false),
+        _ => None,
+    };
+
+    let shape = match dict_value(dict, "shape") {
+        Some(value) if value.starts_with('(') => value.find(')').map(|end| &value[1..end]),
+        _ => None,
+    };
+
+    let (descr, fortran_order, shape) = match (descr, fortran_order, shape) {
+        (Some(descr), Some(fortran_order), Some(shape)) => (descr, fortran_order, shape),
+        _ => return None,
+    };
+
+    let mut dims = Vec::new();
+    for dim in shape.split(',').map(str::trim).filter(|dim| !dim.is_empty()) {
+        match dim.parse() {
+            Ok(dim) => dims.push(dim),
+            Err(_) => return None,
+        }
+    }
+
+    Some(Header {
+        descr: descr.to_string(),
+        fortran_order: fortran_order,
+        shape: dims,
+    })
+}
+
+
+fn read_header<R: Read>(reader: &mut R) -> Result<Header, NpyError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+
+    if &magic[..6] != MAGIC {
+        return Err(NpyError::BadMagic);
+    }
+
+    let len = match (magic[6], magic[7]) {
+        (1, 0) => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            len[0] as usize | (len[1] as usize) << 8
+        }
+        (2, 0) | (3, 0) => {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            len.iter().rev().fold(0, |acc, &b| acc << 8 | b as usize)
+        }
+        (major, minor) => return Err(NpyError::UnsupportedVersion(major, minor)),
+    };
+
+    let mut dict = vec![0; len];
+    reader.read_exact(&mut dict)?;
+
+    String::from_utf8(dict)
+        .ok()
+        .and_then(|dict| parse_header(&dict))
+        .ok_or(NpyError::InvalidHeader)
+}
+
+
+fn write_header<W: Write>(writer: &mut W,
+                          descr: &str,
+                          fortran_order: bool,
+                          shape: &[usize])
+                          -> io::Result<()> {
+    let dims: Vec<String> = shape.iter().map(|d| d.to_string()).collect();
+    let shape = match dims.len() {
+        1 => format!("({},)", dims[0]),
+        _ => format!("({})", dims.join(", ")),
+    };
+
+    let mut dict = format!("{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+                           descr,
+                           if fortran_order { "True" } else { "False" },
+                           shape);
+
+    // The header is padded with spaces and terminated by a newline so that the data which
+    // follows it is aligned to 64 bytes. Version 2.0 is only needed for enormous headers.
+    let (version, preamble) = if dict.len() + 1 + 10 <= 65535 {
+        (1, 10)
+    } else {
+        (2, 12)
+    };
+
+    while (preamble + dict.len() + 1) % 64 != 0 {
+        dict.push(' ');
+    }
+    dict.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[version, 0])?;
+
+    let len = dict.len();
+    if version == 1 {
+        writer.write_all(&[len as u8, (len >> 8) as u8])?;
+    } else {
+        writer.write_all(&[len as u8, (len >> 8) as u8, (len >> 16) as u8, (len >> 24) as u8])?;
+    }
+
+    writer.write_all(dict.as_bytes())
+}
+
+
+/// Checks the stored dtype against `T` and returns the byte order of the stored elements.
+fn check_descr<T: NpyScalar>(found: &str) -> Result<Endian, NpyError> {
+    let endian = match found.chars().next() {
+        Some('<') => Endian::Little,
+        Some('>') => Endian::Big,
+        Some('|') | Some('=') => Endian::native(),
+        _ => return Err(NpyError::InvalidHeader),
+    };
+
+    let expected = descr::<T>(endian);
+
+    if found[1..] == expected[1..] {
+        Ok(endian)
+    } else {
+        Err(NpyError::DtypeMismatch {
+            expected: expected,
+            found: found.to_string(),
+        })
+    }
+}
+
+
+/// Reads the elements of an array with the given shape. The shape comes from an untrusted header,
+/// so its byte count is computed with overflow checks, and the bytes are read incrementally
+/// rather than allocated up front.
+fn read_elems<T: NpyScalar, R: Read>(reader: &mut R,
+                                     shape: &[usize],
+                                     endian: Endian)
+                                     -> Result<Vec<T>, NpyError> {
+    let size = T::size();
+
+    // Bound the product of the nonzero extents, so that no partial product of the shape taken
+    // from either end overflows, even when a zero extent makes the whole array empty.
+    let bound = shape.iter()
+        .fold(Some(size), |len, &extent| len.and_then(|len| len.checked_mul(extent.max(1))));
+    let len = match bound {
+        Some(_) if shape.contains(&0) => 0,
+        Some(len) => len,
+        None => return Err(NpyError::ShapeOverflow(shape.to_vec())),
+    };
+
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != len {
+        return Err(NpyError::Io(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                               "failed to fill whole buffer")));
+    }
+
+    Ok(bytes.chunks(size)
+        .map(|chunk| {
+            let bits = match endian {
+                Endian::Little => chunk.iter().rev().fold(0, |acc, &b| acc << 8 | b as u64),
+                Endian::Big => chunk.iter().fold(0, |acc, &b| acc << 8 | b as u64),
+            };
+
+            T::from_raw_bits(bits)
+        })
+        .collect())
+}
+
+
+fn write_elems<'a, T: NpyScalar + 'a, W: Write, I: Iterator<Item = &'a T>>(writer: &mut W,
+                                                                         elems: I,
+                                                                         endian: Endian)
+                                                                         -> io::Result<()> {
+    let size = T::size();
+    let mut bytes = Vec::with_capacity(size);
+
+    for elem in elems {
+        let bits = elem.to_raw_bits();
+
+        bytes.clear();
+        for k in 0..size {
+            let shift = match endian {
+                Endian::Little => 8 * k,
+                Endian::Big => 8 * (size - 1 - k),
+            };
+
+            bytes.push((bits >> shift) as u8);
+        }
+
+        writer.write_all(&bytes)?;
+    }
+
+    Ok(())
+}
+
+
+/// Reads a two-dimensional array into a matrix. A one-dimensional array of length `n` is read as
+/// an `n x 1` column.
+pub fn read_mat<T: NpyScalar, L: Layout, R: Read>
+    (mut reader: R)
+     -> Result<DenseMat<T, Dyn, Dyn, L>, NpyError> {
+    let header = read_header(&mut reader)?;
+    let endian = check_descr::<T>(&header.descr)?;
+
+    let (m, n) = match header.shape.len() {
+        1 => (header.shape[0], 1),
+        2 => (header.shape[0], header.shape[1]),
+        _ => return Err(NpyError::ShapeMismatch(header.shape)),
+    };
+
+    let elems = read_elems::<T, _>(&mut reader, &header.shape, endian)?;
+    let mut mat = DenseMat::from_elem(Dyn(m), Dyn(n), T::zero());
+
+    for i in 0..m {
+        for j in 0..n {
+            let k = if header.fortran_order { i + j * m } else { i * n + j };
+            mat[[i, j]] = elems[k].clone();
+        }
+    }
+
+    Ok(mat)
+}
+
+
+/// Writes a matrix as a two-dimensional array, in the matrix's own memory order.
+pub fn write_mat<T: NpyScalar, M: Dim, N: Dim, L: Layout, W: Write>(mut writer: W,
+                                                                    mat: &DenseMat<T, M, N, L>,
+                                                                    endian: Endian)
+                                                                    -> io::Result<()> {
+    let (m, n) = (mat.rows().reify(), mat.cols().reify());
+    let fortran_order = L::strides(m, n)[0] == 1;
+
+    write_header(&mut writer, &descr::<T>(endian), fortran_order, &[m, n])?;
+    write_elems(&mut writer, mat.as_slice().iter(), endian)
+}
+
+
+/// Reads an N-dimensional array. The number of axes must match `D`, as must the extent of every
+/// axis which `D` fixes statically.
+pub fn read_array<T: NpyScalar, D: Dims<T>, R: Read>(mut reader: R) -> Result<Array<T, D>, NpyError> {
+    let header = read_header(&mut reader)?;
+    let endian = check_descr::<T>(&header.descr)?;
+
+    let dims = match D::from_shape(&header.shape) {
+        Some(dims) => dims,
+        None => return Err(NpyError::ShapeMismatch(header.shape)),
+    };
+
+    let elems = read_elems::<T, _>(&mut reader, &header.shape, endian)?;
+    let total = elems.len();
+
+    // An empty array reads the same in either order. Returning early also keeps the strides below
+    // from overflowing, since every partial product of a nonempty shape is at most `total`.
+    if !header.fortran_order || total == 0 {
+        return Ok(Array::from_data(dims, elems.into_iter().collect_exact()));
+    }
+
+    // Fortran order varies the first index fastest; gather the elements into C order.
+    let shape = &header.shape;
+    let mut strides = Vec::with_capacity(shape.len());
+    let mut stride = 1;
+    for &extent in shape {
+        strides.push(stride);
+        stride *= extent;
+    }
+
+    let data = (0..total)
+        .map(|c| {
+            let mut rem = c;
+            let mut f = 0;
+
+            for (&s, &extent) in strides.iter().zip(shape).rev() {
+                f += (rem % extent) * s;
+                rem /= extent;
+            }
+
+            elems[f].clone()
+        })
+        .collect_exact();
+
+    Ok(Array::from_data(dims, data))
+}
+
+
+/// Writes an N-dimensional array in C order.
+pub fn write_array<T: NpyScalar, D: Dims<T>, W: Write>(mut writer: W,
+                                                       array: &Array<T, D>,
+                                                       endian: Endian)
+                                                       -> io::Result<()> {
+    write_header(&mut writer, &descr::<T>(endian), false, &array.dims().shape())?;
+    write_elems(&mut writer, array.as_slice().iter(), endian)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linalg::{Column, Row};
+    use typehack::binary::*;
+
+    /// The file `np.save` writes for `np.array([[1, 2, 3], [4, 5, 6]], dtype='>f4', order='F')`.
+    fn big_endian_fortran_f32() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, ">f4", true, &[2, 3]).unwrap();
+
+        for &x in &[1f32, 4., 2., 5., 3., 6.] {
+            let bits = x.to_bits();
+            bytes.extend_from_slice(&[(bits >> 24) as u8, (bits >> 16) as u8, (bits >> 8) as u8,
+                                      bits as u8]);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn npy_header_layout() {
+        let mut bytes = Vec::new();
+        write_mat(&mut bytes, &Mat![#row [1i16, 2], [3, 4]], Endian::Little).unwrap();
+
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+
+        let len = bytes[8] as usize | (bytes[9] as usize) << 8;
+        assert_eq!((10 + len) % 64, 0);
+        assert_eq!(bytes[10 + len - 1], b'\n');
+
+        let header = String::from_utf8(bytes[10..10 + len].to_vec()).unwrap();
+        assert!(header.starts_with("{'descr': '<i2', 'fortran_order': False, 'shape': (2, 2), }"));
+        assert_eq!(&bytes[10 + len..], &[1, 0, 2, 0, 3, 0, 4, 0]);
+    }
+
+    #[test]
+    fn npy_read_big_endian_fortran() {
+        let bytes = big_endian_fortran_f32();
+
+        let a: DenseMat<f32, Dyn, Dyn, Row> = read_mat(&bytes[..]).unwrap();
+        assert_eq!(a.as_slice(), &[1., 2., 3., 4., 5., 6.]);
+
+        let b: DenseMat<f32, Dyn, Dyn, Column> = read_mat(&bytes[..]).unwrap();
+        assert_eq!(b.as_slice(), &[1., 4., 2., 5., 3., 6.]);
+
+        match read_mat::<f64, Row, _>(&bytes[..]) {
+            Err(NpyError::DtypeMismatch { .. }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn npy_mat_round_trip() {
+        let a = Mat![[1i64, -2, 3], [-4, 5, i64::min_value()]];
+
+        for &endian in &[Endian::Little, Endian::Big] {
+            let mut bytes = Vec::new();
+            write_mat(&mut bytes, &a, endian).unwrap();
+
+            let b: DenseMat<i64, Dyn, Dyn, Column> = read_mat(&bytes[..]).unwrap();
+            assert_eq!(b.as_slice(), a.as_slice());
+        }
+
+        let c = Mat![#row [0.5f64, -1.25e300], [3., 4.]];
+        let mut bytes = Vec::new();
+        write_mat(&mut bytes, &c, Endian::Big).unwrap();
+
+        let d: DenseMat<f64, Dyn, Dyn, Row> = read_mat(&bytes[..]).unwrap();
+        assert_eq!(d.as_slice(), c.as_slice());
+    }
+
+    #[test]
+    fn npy_array_round_trip() {
+        let a = Array::from_fn(dims![B2, (3), B4],
+                               |idx| (idx.elem * 100 + idx.next.elem * 10 + idx.next.next.elem) as i8);
+
+        let mut bytes = Vec::new();
+        write_array(&mut bytes, &a, Endian::Little).unwrap();
+
+        let b: Array<i8, Dims![B2, Dyn, B4]> = read_array(&bytes[..]).unwrap();
+        assert_eq!(Dims::<i8>::shape(&b.dims()), vec![2, 3, 4]);
+        assert_eq!(b.as_slice(), a.as_slice());
+
+        match read_array::<i8, Dims![B2, B3], _>(&bytes[..]) {
+            Err(NpyError::ShapeMismatch(ref shape)) if shape == &[2, 3, 4] => {}
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("read a three-dimensional array as two-dimensional"),
+        }
+    }
+
+    #[test]
+    fn npy_array_fortran_order() {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, "|i1", true, &[2, 3]).unwrap();
+        bytes.extend_from_slice(&[1, 4, 2, 5, 3, 6]);
+
+        let a: Array<i8, Dims![B2, B3]> = read_array(&bytes[..]).unwrap();
+        assert_eq!(a.as_slice(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn npy_shape_overflow() {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, "<i2", false, &[usize::max_value(), 2]).unwrap();
+
+        match read_mat::<i16, Row, _>(&bytes[..]) {
+            Err(NpyError::ShapeOverflow(ref shape)) if shape == &[usize::max_value(), 2] => {}
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("read an array too large to address"),
+        }
+
+        match read_array::<i16, Dims![Dyn, Dyn], _>(&bytes[..]) {
+            Err(NpyError::ShapeOverflow(_)) => {}
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("read an array too large to address"),
+        }
+
+        // Empty, but the extents before the zero cannot all be addressed at once.
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, "|i1", true, &[0, 1 << 40, 1 << 40]).unwrap();
+        match read_array::<i8, Dims![Dyn, Dyn, Dyn], _>(&bytes[..]) {
+            Err(NpyError::ShapeOverflow(_)) => {}
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("read an array too large to address"),
+        }
+
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, "|i1", true, &[1 << 40, 1 << 20, 0]).unwrap();
+        let empty = read_array::<i8, Dims![Dyn, Dyn, Dyn], _>(&bytes[..]).unwrap();
+        assert_eq!(Dims::<i8>::shape(&empty.dims()), vec![1 << 40, 1 << 20, 0]);
+        assert!(empty.as_slice().is_empty());
+    }
+}
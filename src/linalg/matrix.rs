@@ -1,10 +1,17 @@
-use std::fmt::{self, Debug};
+use std::fmt::{self, Debug, Display};
+use std::io::{self, Read, Write};
+use std::iter;
 use std::marker::PhantomData;
 use std::ops::{Mul, Index, IndexMut};
 use std::ptr;
+use std::slice;
 
+use iter_exact::CollectExactExt;
+
+use codec::{self, Decode, DecodeError, Encode};
 use linalg::traits::*;
 use linalg::vector::*;
+use pretty;
 use typehack::data::*;
 use typehack::dim::*;
 use typehack::binary::*;
@@ -145,6 +152,55 @@ impl<T: Scalar, M: Dim, N: Dim, L: Layout> Debug for DenseMat<T, M, N, L> {
 }
 
 
+/// Displays the matrix one row per line with aligned columns, e.g. `{:.2}` for two decimal places.
+/// Matrices with many rows or columns are truncated with ellipses unless `{:#}` is used.
+impl<T: Scalar + Display, M: Dim, N: Dim, L: Layout> Display for DenseMat<T, M, N, L> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision();
+
+        pretty::write_grid(f,
+                           self.rows.reify(),
+                           self.cols.reify(),
+                           |i, j| pretty::elem(&self[[i, j]], precision))
+    }
+}
+
+
+/// Encodes the matrix as its row and column counts followed by its elements in row-major order,
+/// so that a matrix may be decoded into a different layout than it was encoded from.
+impl<T: Scalar + Encode, M: Dim, N: Dim, L: Layout> Encode for DenseMat<T, M, N, L> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        codec::encode_dim(self.rows, writer)?;
+        codec::encode_dim(self.cols, writer)?;
+
+        for i in 0..self.rows.reify() {
+            for j in 0..self.cols.reify() {
+                self[[i, j]].encode(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+impl<T: Scalar + Decode, M: Dim, N: Dim, L: Layout> Decode for DenseMat<T, M, N, L> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let rows: M = codec::decode_dim(reader)?;
+        let cols: N = codec::decode_dim(reader)?;
+
+        if rows.reify().checked_mul(cols.reify()).is_none() {
+            let len = (rows.reify() as u64).saturating_mul(cols.reify() as u64);
+            return Err(DecodeError::LengthOverflow(len));
+        }
+
+        let elems = codec::decode_elems(rows.mul(cols), reader)?;
+
+        Ok(DenseMat::<T, M, N, Row>::from_data(rows, cols, elems).to_layout())
+    }
+}
+
+
 #[derive(Clone, PartialEq, Eq)]
 #[repr(C)]
 pub struct DenseMat<T: Scalar, M: Dim, N: Dim, L: Layout> {
@@ -220,10 +276,23 @@ impl<T: Scalar, M: Dim, N: Dim, L: Layout> DenseMat<T, M, N, L> {
     }
 
 
+    /// The elements of this matrix, in the order given by its layout.
+    pub fn as_slice(&self) -> &[T] {
+        &self.elems
+    }
+
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.elems
+    }
+
+
     pub fn augment_cols<X: Layout, Y: Layout, P: Dim>
         (self,
          rhs: DenseMat<T, M, P, X>)
          -> DenseMat<T, M, <P as DimAdd<N>>::Result, Y> {
+        assert_eq!(self.rows(), rhs.rows());
+
         let mut out: DenseMat<T, M, <P as DimAdd<N>>::Result, Y>;
 
         unsafe {
@@ -252,6 +321,8 @@ impl<T: Scalar, M: Dim, N: Dim, L: Layout> DenseMat<T, M, N, L> {
         (self,
          rhs: DenseMat<T, P, N, X>)
          -> DenseMat<T, <P as DimAdd<M>>::Result, N, Y> {
+        assert_eq!(self.cols(), rhs.cols());
+
         let mut out: DenseMat<T, <P as DimAdd<M>>::Result, N, Y>;
 
         unsafe {
@@ -333,6 +404,134 @@ impl<T: Scalar, P: Dim, Q: Dim, L: Layout> DenseMat<T, P, Q, L> {
 }
 
 
+/// An iterator over the `[row, column]` indices of a matrix, in the order its elements are laid
+/// out in memory.
+#[derive(Clone, Debug)]
+pub struct Indices {
+    rows: usize,
+    cols: usize,
+    column_major: bool,
+    next: usize,
+}
+
+
+impl Iterator for Indices {
+    type Item = [usize; 2];
+
+    fn next(&mut self) -> Option<[usize; 2]> {
+        if self.next >= self.rows * self.cols {
+            return None;
+        }
+
+        let k = self.next;
+        self.next += 1;
+
+        if self.column_major {
+            Some([k % self.rows, k / self.rows])
+        } else {
+            Some([k / self.cols, k % self.cols])
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.rows * self.cols - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+
+impl ExactSizeIterator for Indices {}
+
+
+impl<T: Scalar, M: Dim, N: Dim, L: Layout> DenseMat<T, M, N, L> {
+    /// Iterates over the indices of this matrix in memory order.
+    pub fn indices(&self) -> Indices {
+        let (m, n) = (self.rows.reify(), self.cols.reify());
+
+        Indices {
+            rows: m,
+            cols: n,
+            column_major: L::strides(m, n)[0] == 1,
+            next: 0,
+        }
+    }
+
+
+    /// Iterates over the elements of this matrix in memory order; that is, row by row for `Row`
+    /// matrices and column by column for `Column` matrices.
+    pub fn iter(&self) -> slice::Iter<T> {
+        self.elems.iter()
+    }
+
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<T> {
+        self.elems.iter_mut()
+    }
+
+
+    /// Iterates over the elements of this matrix in memory order, paired with their indices.
+    pub fn indexed_iter(&self) -> iter::Zip<Indices, slice::Iter<T>> {
+        self.indices().zip(self.elems.iter())
+    }
+
+
+    pub fn map<U: Scalar, F: FnMut(T) -> U>(self, f: F) -> DenseMat<U, M, N, L> {
+        DenseMat {
+            rows: self.rows,
+            cols: self.cols,
+            elems: self.elems.into_iter().map(f).collect_exact(),
+            phantom: PhantomData,
+        }
+    }
+
+
+    /// Combines this matrix element-wise with another of the same dimensions. The result has
+    /// the layout of `self`, and is built in `self`'s memory order; when the layouts of `self`
+    /// and `rhs` agree, `rhs` is read in its memory order as well.
+    pub fn zip_map<U: Scalar, V: Scalar, L2: Layout, F: FnMut(T, U) -> V>
+        (self,
+         rhs: DenseMat<U, M, N, L2>,
+         mut f: F)
+         -> DenseMat<V, M, N, L> {
+        assert_eq!(self.rows, rhs.rows);
+        assert_eq!(self.cols, rhs.cols);
+
+        let (rows, cols) = (self.rows, self.cols);
+        let indices = self.indices();
+
+        let elems;
+
+        unsafe {
+            elems = self.elems
+                .into_iter()
+                .zip(indices)
+                .map(|(a, idx)| f(a, ptr::read(&rhs[idx])))
+                .collect_exact();
+
+            rhs.elems.forget();
+        }
+
+        DenseMat {
+            rows: rows,
+            cols: cols,
+            elems: elems,
+            phantom: PhantomData,
+        }
+    }
+
+
+    pub fn fold<A, F: FnMut(A, &T) -> A>(&self, init: A, f: F) -> A {
+        self.elems.iter().fold(init, f)
+    }
+
+
+    /// The element-wise (Hadamard) product of two matrices.
+    pub fn component_mul<L2: Layout>(self, rhs: DenseMat<T, M, N, L2>) -> DenseMat<T, M, N, L> {
+        self.zip_map(rhs, |a, b| a * b)
+    }
+}
+
+
 impl<T: Scalar, P: Dim, Q: Dim, L: Layout> From<DenseMat<T, P, Q, L>> for DenseVec<T, <P as DimMul<Q>>::Result> {
     fn from(mat: DenseMat<T, P, Q, L>) -> DenseVec<T, <P as DimMul<Q>>::Result> {
         DenseVec::from_data(mat.elems)
@@ -516,6 +715,113 @@ impl<T: Scalar, M: Dim, N: Dim> MatrixTranspose<DenseMat<T, N, M, Column>>
 }
 
 
+/// Transposes a densely packed, column-major `a x b` buffer into a column-major `b x a` one
+/// without allocating a second buffer. The element at `k = i + j * a` belongs at `j + i * b`,
+/// which is `k * b mod (a * b - 1)` for every element but the last; we rotate each cycle of that
+/// permutation through its first position, keeping one flag per element to mark visited cycles.
+fn transpose_buffer<T>(buf: &mut [T], a: usize, b: usize) {
+    let n = a * b;
+    assert_eq!(buf.len(), n);
+
+    if n < 3 || a == 1 || b == 1 {
+        return;
+    }
+
+    let mut visited = vec![false; n];
+
+    for start in 1..n - 1 {
+        if visited[start] {
+            continue;
+        }
+
+        let mut k = start;
+        loop {
+            visited[k] = true;
+
+            let next = (k * b) % (n - 1);
+            if next == start {
+                break;
+            }
+
+            buf.swap(start, next);
+            k = next;
+        }
+    }
+}
+
+
+impl<T: Scalar, M: Dim, N: Dim, L: Layout> DenseMat<T, M, N, L> {
+    /// Reinterprets this matrix's memory under the alternate layout, which yields its transpose
+    /// without moving any data.
+    pub fn reinterpret_transposed(self) -> DenseMat<T, N, M, L::Alternate>
+        where N: DimMul<M, Result = <M as DimMul<N>>::Result>
+    {
+        DenseMat {
+            rows: self.cols,
+            cols: self.rows,
+            elems: self.elems,
+            phantom: PhantomData,
+        }
+    }
+
+
+    /// Converts this matrix to the layout `L2`, preserving its elements. When `L2` orders memory
+    /// the same way as `L`, this is free; otherwise the elements are permuted in place.
+    pub fn to_layout<L2: Layout>(mut self) -> DenseMat<T, M, N, L2> {
+        let (m, n) = (self.rows.reify(), self.cols.reify());
+        let strides = L::strides(m, n);
+
+        if strides != L2::strides(m, n) {
+            if strides[0] == 1 {
+                transpose_buffer(&mut self.elems, m, n);
+            } else {
+                transpose_buffer(&mut self.elems, n, m);
+            }
+        }
+
+        DenseMat {
+            rows: self.rows,
+            cols: self.cols,
+            elems: self.elems,
+            phantom: PhantomData,
+        }
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> MatrixTransposeInPlace for DenseMat<T, N, N, L> {
+    default fn transpose_in_place(&mut self) {
+        let n = self.side().reify();
+
+        for i in 0..n {
+            for j in i + 1..n {
+                self.swap([i, j], [j, i]);
+            }
+        }
+    }
+}
+
+
+impl<T: Scalar, L: Layout> MatrixTransposeInPlace for DenseMat<T, Dyn, Dyn, L> {
+    /// Transposes a dynamically sized matrix in place, which need not be square: the shape of the
+    /// matrix is swapped, and its elements are permuted by cycle-following.
+    fn transpose_in_place(&mut self) {
+        let (m, n) = (self.rows.reify(), self.cols.reify());
+
+        // The memory of an `m x n` matrix in one layout is that of its `n x m` transpose in the
+        // other, so we rearrange memory into the alternate layout and then swap dimensions.
+        if L::strides(m, n)[0] == 1 {
+            transpose_buffer(&mut self.elems, m, n);
+        } else {
+            transpose_buffer(&mut self.elems, n, m);
+        }
+
+        self.rows = Dyn(n);
+        self.cols = Dyn(m);
+    }
+}
+
+
 macro_rules! mtra_impl {
     ($mat:ident) => {
         unsafe {
@@ -613,6 +919,28 @@ impl<T: Clone + Scalar, M: Dim, N: Dim, P: Dim, L1: Layout, L2: Layout> Mul<Dens
 }
 
 
+impl<T: Scalar, M: Dim, N: Dim, L: Layout> Mul<DenseVec<T, N>> for DenseMat<T, M, N, L> {
+    type Output = DenseVec<T, M>;
+
+    fn mul(self, rhs: DenseVec<T, N>) -> DenseVec<T, M> {
+        &self * &rhs
+    }
+}
+
+
+impl<'a, T: Scalar, M: Dim, N: Dim, L: Layout> Mul<&'a DenseVec<T, N>> for &'a DenseMat<T, M, N, L> {
+    type Output = DenseVec<T, M>;
+
+    fn mul(self, rhs: &'a DenseVec<T, N>) -> DenseVec<T, M> {
+        assert_eq!(self.cols, rhs.size());
+
+        DenseVec::from_fn(self.rows, |i| {
+            (0..self.cols.reify()).map(|j| self[[i, j]].clone() * rhs[j].clone()).sum()
+        })
+    }
+}
+
+
 impl<T: Clone + Scalar, N: Dim, L: Layout> MatrixIdentity for DenseMat<T, N, N, L> {
     fn eye(n: N) -> Self {
         let mut res = DenseMat {
@@ -651,6 +979,66 @@ impl<T: Scalar, M: Dim, N: Dim, L: Layout> IndexMut<[usize; 2]> for DenseMat<T,
 
 #[cfg(test)]
 mod tests {
+    use codec::{Decode, DecodeError, Encode};
+    use linalg::{Column, DenseMat, Matrix, MatrixTransposeInPlace, Row};
+    use typehack::binary::{B2, B3};
+    use typehack::data::Data;
+    use typehack::dim::Dyn;
+
+    #[test]
+    fn mat_codec_round_trip_across_layouts() {
+        let a = Mat![#column [1i32, 2, 3], [4, 5, 6]];
+
+        let mut bytes = Vec::new();
+        a.encode(&mut bytes).unwrap();
+        assert_eq!(&bytes[..16], &[2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&bytes[16..24], &[1, 0, 0, 0, 2, 0, 0, 0]);
+
+        let b: DenseMat<i32, B2, B3, Row> = Decode::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(b.as_slice(), &[1, 2, 3, 4, 5, 6]);
+
+        let c: DenseMat<i32, Dyn, Dyn, Column> = Decode::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(c.as_slice(), a.as_slice());
+
+        match DenseMat::<i32, B3, B2, Row>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(2)) => {}
+            other => panic!("unexpected result {:?}", other.map(|m| m.as_slice().to_vec())),
+        }
+
+        let mut huge = Vec::new();
+        u64::max_value().encode(&mut huge).unwrap();
+        2u64.encode(&mut huge).unwrap();
+
+        match DenseMat::<i32, Dyn, Dyn, Row>::decode(&mut &huge[..]) {
+            Err(DecodeError::LengthOverflow(_)) => {}
+            other => panic!("unexpected result {:?}", other.map(|m| m.as_slice().to_vec())),
+        }
+    }
+
+
+    #[test]
+    fn mat_codec_checks_dims_and_length() {
+        let a = Mat![#row [1i32, 2], [3, 4]];
+
+        let mut bytes = Vec::new();
+        a.encode(&mut bytes).unwrap();
+
+        let b: DenseMat<i32, B2, B2, Column> = Decode::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(b.as_slice(), &[1, 3, 2, 4]);
+        assert_eq!(b[[0, 1]], 2);
+
+        match DenseMat::<i32, B2, B3, Row>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(2)) => {}
+            other => panic!("unexpected result {:?}", other.map(|m| m.as_slice().to_vec())),
+        }
+
+        match DenseMat::<i32, Dyn, B2, Row>::decode(&mut &bytes[..bytes.len() - 1]) {
+            Err(DecodeError::Io(_)) => {}
+            other => panic!("unexpected result {:?}", other.map(|m| m.as_slice().to_vec())),
+        }
+    }
+
+
     #[test]
     fn mat_test_index_column_major() {
         let a = Mat![#column [0, 1], [2, 3], [4, 5]];
@@ -675,4 +1063,124 @@ mod tests {
         assert_eq!(a[[2, 0]], 4);
         assert_eq!(a[[2, 1]], 5);
     }
+
+
+    #[test]
+    fn mat_test_iter_memory_order() {
+        let a = Mat![#column [0, 1], [2, 3]];
+        let b = Mat![#row [0, 1], [2, 3]];
+
+        assert_eq!(a.iter().cloned().collect::<Vec<_>>(), vec![0, 2, 1, 3]);
+        assert_eq!(b.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(a.indices().collect::<Vec<_>>(),
+                   vec![[0, 0], [1, 0], [0, 1], [1, 1]]);
+    }
+
+
+    #[test]
+    fn mat_test_map_and_fold() {
+        let a = Mat![[1, 2], [3, 4]].map(|x| x * 10);
+
+        assert_eq!(a, Mat![[10, 20], [30, 40]]);
+        assert_eq!(a.fold(0, |acc, x| acc + x), 100);
+    }
+
+
+    #[test]
+    fn mat_test_zip_map_mixed_layout() {
+        let a = Mat![#column [1, 2], [3, 4]];
+        let b = Mat![#row [1, 2], [3, 4]];
+
+        assert_eq!(a.zip_map(b, |x, y| x - y), Mat![#column [0, 0], [0, 0]]);
+    }
+
+
+    #[test]
+    fn mat_test_component_mul() {
+        let a = Mat![#row [1, 2], [3, 4]];
+        let b = Mat![#column [5, 6], [7, 8]];
+
+        assert_eq!(a.component_mul(b), Mat![#row [5, 12], [21, 32]]);
+    }
+
+
+    #[test]
+    fn mat_test_transpose_in_place_square() {
+        let mut a = Mat![#row [1, 2, 3], [4, 5, 6], [7, 8, 9]];
+        let mut b = Mat![#column [1, 2, 3], [4, 5, 6], [7, 8, 9]];
+
+        a.transpose_in_place();
+        b.transpose_in_place();
+
+        assert_eq!(a, Mat![#row [1, 4, 7], [2, 5, 8], [3, 6, 9]]);
+        assert_eq!(b, Mat![#column [1, 4, 7], [2, 5, 8], [3, 6, 9]]);
+    }
+
+
+    #[test]
+    fn mat_test_transpose_in_place_rectangular_dyn() {
+        let elems = Data::from_slice(Dyn(6), &[1, 2, 3, 4, 5, 6]);
+        let mut a: DenseMat<i32, Dyn, Dyn, Row> = DenseMat::from_data(Dyn(2), Dyn(3), elems);
+
+        a.transpose_in_place();
+
+        assert_eq!(a.rows(), Dyn(3));
+        assert_eq!(a.cols(), Dyn(2));
+        assert_eq!(a.as_slice(), &[1, 4, 2, 5, 3, 6]);
+
+        let elems = Data::from_slice(Dyn(12), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        let mut b: DenseMat<i32, Dyn, Dyn, Column> = DenseMat::from_data(Dyn(3), Dyn(4), elems);
+        let original = b.clone();
+
+        b.transpose_in_place();
+
+        for i in 0..3 {
+            for j in 0..4 {
+                assert_eq!(b[[j, i]], original[[i, j]]);
+            }
+        }
+    }
+
+
+    #[test]
+    fn mat_test_to_layout() {
+        let a = Mat![#column [1, 2, 3], [4, 5, 6]];
+        let b = a.clone().to_layout::<Row>();
+
+        assert_eq!(b.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(b, Mat![#row [1, 2, 3], [4, 5, 6]]);
+        assert_eq!(b.to_layout::<Column>(), a);
+    }
+
+
+    #[test]
+    fn mat_test_reinterpret_transposed() {
+        let a = Mat![#row [1, 2, 3], [4, 5, 6]];
+        let t = a.reinterpret_transposed();
+
+        assert_eq!(t, Mat![#column [1, 4], [2, 5], [3, 6]]);
+        assert_eq!(t.as_slice(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+
+    #[test]
+    fn mat_test_display() {
+        let a = Mat![#row [1, -2], [30, 4]];
+
+        assert_eq!(format!("{}", a), "[ 1  -2]\n[30   4]");
+        assert_eq!(format!("{:.1}", Mat![[1., 2.5]]), "[1.0  2.5]");
+        assert_eq!(format!("{:3}", Mat![[1, 2]]), "[  1    2]");
+    }
+
+
+    #[test]
+    fn mat_test_display_truncated() {
+        let a: DenseMat<i32, Dyn, Dyn, Column> = DenseMat::from_elem(Dyn(100), Dyn(3), 0);
+
+        let s = format!("{}", a);
+        assert_eq!(s.lines().count(), 9);
+        assert_eq!(s.lines().nth(4), Some("[...  ...  ...]"));
+
+        assert_eq!(format!("{:#}", a).lines().count(), 100);
+    }
 }
@@ -1,5 +1,6 @@
 use typehack::dim::*;
 use typehack::binary::*;
+use geometry::frame::Unframed;
 
 
 #[macro_export]
@@ -22,6 +23,33 @@ macro_rules! Mat {
 }
 
 
+/// Assembles a matrix from a grid of blocks, given row by row. Blocks in the same block-row must
+/// have the same number of rows, and blocks in the same block-column the same number of columns;
+/// when these are `Nat`s, mismatches are caught at compile time. As with `Mat!`, a leading `#row`
+/// or `#column` selects the layout of the result.
+///
+/// ```ignore
+/// let m = block![[a, b],
+///                [c, d]];
+/// ```
+#[macro_export]
+macro_rules! block {
+    (@layout row) => ($crate::linalg::Row);
+    (@layout column) => ($crate::linalg::Column);
+    (@cols $layout:ident [$x:expr]) => ($x);
+    (@cols $layout:ident [$x:expr, $($xs:expr),+]) => (
+        $x.augment_cols::<_, block!(@layout $layout), _>(block!(@cols $layout [$($xs),+]))
+    );
+    (@rows $layout:ident $row:tt) => (block!(@cols $layout $row));
+    (@rows $layout:ident $row:tt, $($rows:tt),+) => (
+        block!(@cols $layout $row)
+            .augment_rows::<_, block!(@layout $layout), _>(block!(@rows $layout $($rows),+))
+    );
+    (#$layout:ident $($rows:tt),+) => (block!(@rows $layout $($rows),+));
+    ($($rows:tt),+) => (block![#column $($rows),+]);
+}
+
+
 #[macro_export]
 macro_rules! Vect {
     ($($xs:expr),*) => ($crate::linalg::Vect::from_data(data![$($xs),*]));
@@ -71,17 +99,49 @@ macro_rules! Vect6 {
 pub mod matrix;
 pub use self::matrix::*;
 
+pub mod norm;
+
+pub mod parse;
+pub use self::parse::*;
+
+pub mod product;
+pub use self::product::*;
+
+pub mod quaternion;
+pub use self::quaternion::*;
+
+pub mod rotation;
+pub use self::rotation::*;
+
+pub mod dual_quaternion;
+pub use self::dual_quaternion::*;
+
+pub mod diagonal;
+pub use self::diagonal::*;
+
+pub mod symmetric;
+pub use self::symmetric::*;
+
+pub mod triangular;
+pub use self::triangular::*;
+
+mod swizzle;
+
 pub mod traits;
 pub use self::traits::*;
 
 pub mod vector;
 pub use self::vector::*;
 
+pub mod view;
+pub use self::view::*;
+
 pub mod algorithm;
+pub mod io;
 
 
 pub type Mat<T, M, N, L = Column> = DenseMat<T, M, N, L>;
-pub type Vect<T, N> = DenseVec<T, N>;
+pub type Vect<T, N, F = Unframed> = DenseVec<T, N, F>;
 
 
 // THEY SAID I WAS CRAZY
@@ -0,0 +1,214 @@
+use linalg::algorithm::solve::lu::LuDecompositionExt;
+use linalg::matrix::*;
+use linalg::traits::*;
+use num::traits::Float;
+use typehack::dim::*;
+
+
+/// The maximum number of power iterations used when computing the spectral norm.
+const SPECTRAL_MAX_ITERATIONS: usize = 1000;
+
+
+impl<T: Scalar + Float, M: Dim, N: Dim, L: Layout> MatrixNorm for DenseMat<T, M, N, L> {
+    fn frobenius_norm(&self) -> T {
+        self.fold(T::zero(), |acc, x| acc + x.clone() * x.clone()).sqrt()
+    }
+
+
+    fn one_norm(&self) -> T {
+        let (m, n) = (self.rows().reify(), self.cols().reify());
+        let mut max = T::zero();
+
+        for j in 0..n {
+            let sum: T = (0..m).map(|i| self[[i, j]].abs()).sum();
+            if sum > max {
+                max = sum;
+            }
+        }
+
+        max
+    }
+
+
+    fn inf_norm(&self) -> T {
+        let (m, n) = (self.rows().reify(), self.cols().reify());
+        let mut max = T::zero();
+
+        for i in 0..m {
+            let sum: T = (0..n).map(|j| self[[i, j]].abs()).sum();
+            if sum > max {
+                max = sum;
+            }
+        }
+
+        max
+    }
+
+
+    fn spectral_norm(&self) -> T {
+        let (m, n) = (self.rows().reify(), self.cols().reify());
+
+        // We use power iteration on `A^T * A`, starting from the row of `A` with the largest
+        // norm. That row can never be orthogonal to the dominant right singular vector unless the
+        // matrix is zero.
+
+        let mut start = (0, T::zero());
+        for i in 0..m {
+            let sum: T = (0..n).map(|j| self[[i, j]].clone() * self[[i, j]].clone()).sum();
+            if sum > start.1 {
+                start = (i, sum);
+            }
+        }
+
+        if start.1 == T::zero() {
+            return T::zero();
+        }
+
+        let tolerance = T::epsilon() * T::from_usize(8);
+
+        let mut x: Vec<T> = (0..n).map(|j| self[[start.0, j]].clone()).collect();
+        let mut sigma = T::zero();
+
+        for _ in 0..SPECTRAL_MAX_ITERATIONS {
+            let len = x.iter().map(|v| v.clone() * v.clone()).sum::<T>().sqrt();
+            for v in x.iter_mut() {
+                *v /= len.clone();
+            }
+
+            let y: Vec<T> = (0..m)
+                .map(|i| (0..n).map(|j| self[[i, j]].clone() * x[j].clone()).sum())
+                .collect();
+
+            // Since `x` is a unit vector, `||A * x||` is a lower bound on the largest singular
+            // value, which increases monotonically towards it.
+            let next = y.iter().map(|v| v.clone() * v.clone()).sum::<T>().sqrt();
+
+            x = (0..n)
+                .map(|j| (0..m).map(|i| self[[i, j]].clone() * y[i].clone()).sum())
+                .collect();
+
+            let converged = (next.clone() - sigma.clone()).abs() <= tolerance.clone() * next.clone();
+            sigma = next;
+
+            if converged {
+                break;
+            }
+        }
+
+        sigma
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout + Clone> DenseMat<T, N, N, L> {
+    /// The sum of the elements on the main diagonal.
+    pub fn trace(&self) -> T {
+        (0..self.side().reify()).map(|i| self[[i, i]].clone()).sum()
+    }
+
+
+    /// Estimates the 1-norm condition number of this matrix through an LU factorization of a copy
+    /// of it. If a factorization is already at hand, prefer `Lu::cond1_estimate`. Returns `None`
+    /// if the matrix is singular.
+    pub fn cond1_estimate(&self) -> Option<T> {
+        self.clone().lu().cond1_estimate()
+    }
+}
+
+
+impl<T: Scalar, M: Dim, N: Dim, L: Layout + Clone> DenseMat<T, M, N, L> {
+    /// The numerical rank of this matrix: the number of pivots with absolute value greater than
+    /// `tolerance` found by Gaussian elimination with complete pivoting. A reasonable choice of
+    /// tolerance is `max(rows, cols) * epsilon * norm` for some matrix norm.
+    pub fn rank(&self, tolerance: T) -> usize {
+        let (m, n) = (self.rows().reify(), self.cols().reify());
+        let mut a = self.clone();
+        let mut rank = 0;
+
+        while rank < m && rank < n {
+            let mut max = ([rank, rank], a[[rank, rank]].abs());
+            for i in rank..m {
+                for j in rank..n {
+                    let abs = a[[i, j]].abs();
+                    if abs > max.1 {
+                        max = ([i, j], abs);
+                    }
+                }
+            }
+
+            if max.1 <= tolerance {
+                break;
+            }
+
+            a.row_switch_mut(rank, max.0[0]);
+            a.col_switch_mut(rank, max.0[1]);
+
+            for i in rank + 1..m {
+                let k = a[[i, rank]].clone() / a[[rank, rank]].clone();
+                for j in rank + 1..n {
+                    let u = a[[rank, j]].clone();
+                    a[[i, j]] -= k.clone() * u;
+                }
+                a[[i, rank]] = T::zero();
+            }
+
+            rank += 1;
+        }
+
+        rank
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn mat_norms_2x3() {
+        let a = Mat![[1f64, -2.,  3.],
+                     [4.,    5., -6.]];
+
+        assert!((a.frobenius_norm() - 91f64.sqrt()).abs() < 0.000001);
+        assert!((a.one_norm() - 9.).abs() < 0.000001);
+        assert!((a.inf_norm() - 15.).abs() < 0.000001);
+    }
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn mat_spectral_norm() {
+        let d = Mat![#row [3f64,  0.],
+                          [0.,   -5.]];
+        assert!((d.spectral_norm() - 5.).abs() < 0.000001);
+
+        // The singular values of [[1, 1], [0, 1]] are the golden ratio and its reciprocal.
+        let j = Mat![[1f64, 1.],
+                     [0.,   1.]];
+        assert!((j.spectral_norm() - (1. + 5f64.sqrt()) / 2.).abs() < 0.000001);
+
+        let z = Mat![[0., 0.]];
+        assert_eq!(z.spectral_norm(), 0.);
+    }
+
+    #[test]
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    fn mat_trace_and_rank() {
+        let a = Mat![[1., 2., 3.],
+                     [4., 5., 6.],
+                     [7., 8., 9.]];
+
+        assert_eq!(a.trace(), 15.);
+        assert_eq!(a.rank(1e-9), 2);
+        assert_eq!(Mat![[0., 0.], [0., 0.]].rank(1e-9), 0);
+        assert_eq!(Mat![[1., 2., 3., 4.]].rank(1e-9), 1);
+    }
+
+    #[test]
+    fn mat_cond1_estimate() {
+        let a = Mat![[4., 0.], [0., 0.5]];
+
+        assert!((a.cond1_estimate().unwrap() - 8.).abs() < 0.000001);
+        assert!(Mat![[1., 1.], [1., 1.]].cond1_estimate().is_none());
+    }
+}
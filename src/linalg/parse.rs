@@ -0,0 +1,477 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use linalg::vector::*;
+use typehack::dim::*;
+
+
+/// An error encountered while parsing a matrix or vector, along with the one-based line and
+/// column at which it occurred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMatError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseMatErrorKind,
+}
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMatErrorKind {
+    UnexpectedChar(char),
+    UnexpectedEnd,
+    /// An element which could not be parsed as the scalar type.
+    InvalidElement(String),
+    /// A row whose length differs from that of the first row.
+    RaggedRow { expected: usize, found: usize },
+    /// A shape which does not match the static dimensions of the target type.
+    WrongRows { expected: usize, found: usize },
+    WrongCols { expected: usize, found: usize },
+    WrongLength { expected: usize, found: usize },
+}
+
+
+impl fmt::Display for ParseMatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+
+        match self.kind {
+            ParseMatErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ParseMatErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseMatErrorKind::InvalidElement(ref s) => write!(f, "invalid element {:?}", s),
+            ParseMatErrorKind::RaggedRow { expected, found } => {
+                write!(f, "expected a row of {} elements, found {}", expected, found)
+            }
+            ParseMatErrorKind::WrongRows { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            ParseMatErrorKind::WrongCols { expected, found } => {
+                write!(f, "expected {} columns, found {}", expected, found)
+            }
+            ParseMatErrorKind::WrongLength { expected, found } => {
+                write!(f, "expected {} elements, found {}", expected, found)
+            }
+        }
+    }
+}
+
+
+impl Error for ParseMatError {
+    fn description(&self) -> &str {
+        "failed to parse matrix"
+    }
+}
+
+
+/// The rows of a parsed matrix, along with the positions needed to report shape errors.
+struct Parsed<T> {
+    rows: Vec<Vec<T>>,
+    row_starts: Vec<(usize, usize)>,
+    start: (usize, usize),
+}
+
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+
+impl Parser {
+    fn new(s: &str) -> Parser {
+        Parser {
+            chars: s.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+
+    fn here(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+
+    fn error_at(&self, at: (usize, usize), kind: ParseMatErrorKind) -> ParseMatError {
+        ParseMatError {
+            line: at.0,
+            column: at.1,
+            kind: kind,
+        }
+    }
+
+
+    fn unexpected(&self) -> ParseMatError {
+        match self.peek() {
+            Some(c) => self.error_at(self.here(), ParseMatErrorKind::UnexpectedChar(c)),
+            None => self.error_at(self.here(), ParseMatErrorKind::UnexpectedEnd),
+        }
+    }
+
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+
+        if let Some(c) = c {
+            self.pos += 1;
+
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+
+        c
+    }
+
+
+    /// Skips whitespace, stopping at newlines unless `newlines` is set.
+    fn skip_ws(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() || (c == '\n' && !newlines) {
+                break;
+            }
+
+            self.bump();
+        }
+    }
+
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseMatError> {
+        if self.peek() == Some(expected) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+
+    fn element<T: FromStr>(&mut self) -> Result<T, ParseMatError> {
+        let start = self.here();
+        let mut token = String::new();
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == ',' || c == ';' || c == '[' || c == ']' {
+                break;
+            }
+
+            token.push(c);
+            self.bump();
+        }
+
+        if token.is_empty() {
+            return Err(self.unexpected());
+        }
+
+        token.parse().map_err(|_| self.error_at(start, ParseMatErrorKind::InvalidElement(token)))
+    }
+
+
+    /// Parses `[[a, b], [c, d]]`; the opening bracket has already been consumed.
+    fn nested<T: FromStr>(&mut self, parsed: &mut Parsed<T>) -> Result<(), ParseMatError> {
+        loop {
+            self.skip_ws(true);
+            parsed.row_starts.push(self.here());
+            self.expect('[')?;
+
+            let mut row = Vec::new();
+
+            self.skip_ws(true);
+            if self.peek() == Some(']') {
+                self.bump();
+            } else {
+                loop {
+                    self.skip_ws(true);
+                    row.push(self.element()?);
+                    self.skip_ws(true);
+
+                    match self.peek() {
+                        Some(',') => {
+                            self.bump();
+                        }
+                        Some(']') => {
+                            self.bump();
+                            break;
+                        }
+                        _ => return Err(self.unexpected()),
+                    }
+                }
+            }
+
+            parsed.rows.push(row);
+
+            self.skip_ws(true);
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {
+                    self.bump();
+                    return Ok(());
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+
+    /// Parses `[a b; c d]`, in which elements are separated by whitespace or commas and rows by
+    /// semicolons or newlines; the opening bracket has already been consumed.
+    fn flat<T: FromStr>(&mut self, parsed: &mut Parsed<T>) -> Result<(), ParseMatError> {
+        let mut row = Vec::new();
+
+        loop {
+            self.skip_ws(false);
+
+            match self.peek() {
+                Some(']') | Some(';') | Some('\n') => {
+                    let c = self.bump();
+
+                    if !row.is_empty() {
+                        parsed.rows.push(row);
+                        row = Vec::new();
+                    }
+
+                    if c == Some(']') {
+                        return Ok(());
+                    }
+                }
+                Some(',') => {
+                    self.bump();
+                }
+                Some('[') | None => return Err(self.unexpected()),
+                Some(_) => {
+                    if row.is_empty() {
+                        parsed.row_starts.push(self.here());
+                    }
+
+                    row.push(self.element()?);
+                }
+            }
+        }
+    }
+
+
+    fn parse<T: FromStr>(mut self) -> Result<Parsed<T>, ParseMatError> {
+        self.skip_ws(true);
+
+        let mut parsed = Parsed {
+            rows: Vec::new(),
+            row_starts: Vec::new(),
+            start: self.here(),
+        };
+
+        self.expect('[')?;
+        self.skip_ws(true);
+
+        if self.peek() == Some('[') {
+            self.nested(&mut parsed)?;
+        } else {
+            self.flat(&mut parsed)?;
+        }
+
+        self.skip_ws(true);
+        if self.peek().is_some() {
+            return Err(self.unexpected());
+        }
+
+        if let Some(first) = parsed.rows.first().map(|row| row.len()) {
+            for (row, &at) in parsed.rows.iter().zip(&parsed.row_starts) {
+                if row.len() != first {
+                    return Err(self.error_at(at,
+                                             ParseMatErrorKind::RaggedRow {
+                                                 expected: first,
+                                                 found: row.len(),
+                                             }));
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+
+/// Parses a matrix written either as `[1 2; 3 4]` (MATLAB style, where newlines may also separate
+/// rows) or as `[[1, 2], [3, 4]]` (NumPy style). Static dimensions must match the parsed shape;
+/// dynamic ones are taken from it.
+impl<T: Scalar + FromStr, M: Dim, N: Dim, L: Layout> FromStr for DenseMat<T, M, N, L> {
+    type Err = ParseMatError;
+
+    fn from_str(s: &str) -> Result<Self, ParseMatError> {
+        let parser = Parser::new(s);
+        let parsed = parser.parse::<T>()?;
+
+        let m = parsed.rows.len();
+        let n = parsed.rows.first().map_or(0, |row| row.len());
+
+        let start = parsed.start;
+        let error = |kind| {
+            Err(ParseMatError {
+                line: start.0,
+                column: start.1,
+                kind: kind,
+            })
+        };
+
+        if !M::compatible(m) {
+            return error(ParseMatErrorKind::WrongRows {
+                expected: M::from_usize(m).reify(),
+                found: m,
+            });
+        }
+
+        if !N::compatible(n) {
+            return error(ParseMatErrorKind::WrongCols {
+                expected: N::from_usize(n).reify(),
+                found: n,
+            });
+        }
+
+        let mut mat = DenseMat::from_elem(M::from_usize(m), N::from_usize(n), T::zero());
+
+        for (i, row) in parsed.rows.into_iter().enumerate() {
+            for (j, x) in row.into_iter().enumerate() {
+                mat[[i, j]] = x;
+            }
+        }
+
+        Ok(mat)
+    }
+}
+
+
+/// Parses a vector written as a single row or a single column in either of the syntaxes accepted
+/// for matrices, e.g. `[1 2 3]`, `[1; 2; 3]` or `[[1], [2], [3]]`.
+impl<T: Scalar + FromStr, N: Dim> FromStr for DenseVec<T, N> {
+    type Err = ParseMatError;
+
+    fn from_str(s: &str) -> Result<Self, ParseMatError> {
+        let parser = Parser::new(s);
+        let parsed = parser.parse::<T>()?;
+
+        let elems: Vec<T> = if parsed.rows.len() == 1 {
+            parsed.rows.into_iter().next().unwrap()
+        } else if parsed.rows.iter().all(|row| row.len() == 1) {
+            parsed.rows.into_iter().flat_map(|row| row).collect()
+        } else {
+            return Err(ParseMatError {
+                line: parsed.start.0,
+                column: parsed.start.1,
+                kind: ParseMatErrorKind::WrongCols {
+                    expected: 1,
+                    found: parsed.rows[0].len(),
+                },
+            });
+        };
+
+        if !N::compatible(elems.len()) {
+            return Err(ParseMatError {
+                line: parsed.start.0,
+                column: parsed.start.1,
+                kind: ParseMatErrorKind::WrongLength {
+                    expected: N::from_usize(elems.len()).reify(),
+                    found: elems.len(),
+                },
+            });
+        }
+
+        let mut vect = DenseVec::from_elem(N::from_usize(elems.len()), &T::zero());
+
+        for (i, x) in elems.into_iter().enumerate() {
+            vect[i] = x;
+        }
+
+        Ok(vect)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linalg::{Mat2x2, Mat2x3, MatDxD, Row, Vect3, VectD};
+
+    #[test]
+    fn parse_matlab_style() {
+        let a: Mat2x2<i32> = "[1 2; 3 4]".parse().unwrap();
+        assert_eq!(a, Mat![[1, 2], [3, 4]]);
+
+        let b: Mat2x3<f64, Row> = "[1, 2.5, -3\n 4e1 5 6]".parse().unwrap();
+        assert_eq!(b, Mat![#row [1., 2.5, -3.], [40., 5., 6.]]);
+    }
+
+    #[test]
+    fn parse_numpy_style() {
+        let a: Mat2x2<i32> = " [[1, 2],\n  [3, 4]] ".parse().unwrap();
+        assert_eq!(a, Mat![[1, 2], [3, 4]]);
+    }
+
+    #[test]
+    fn parse_dyn_inferred() {
+        let a: MatDxD<i32> = "[[1, 2, 3], [4, 5, 6]]".parse().unwrap();
+
+        assert_eq!(a.rows(), Dyn(2));
+        assert_eq!(a.cols(), Dyn(3));
+        assert_eq!(a[[1, 2]], 6);
+
+        let v: VectD<i32> = "[1; 2; 3; 4]".parse().unwrap();
+        assert_eq!(v.len(), 4);
+        assert_eq!(v[3], 4);
+    }
+
+    #[test]
+    fn parse_vect() {
+        let v: Vect3<i32> = "[1 2 3]".parse().unwrap();
+        assert_eq!(v, Vect![1, 2, 3]);
+
+        let w: Vect3<i32> = "[[1], [2], [3]]".parse().unwrap();
+        assert_eq!(w, Vect![1, 2, 3]);
+
+        let err = "[1 2]".parse::<Vect3<i32>>().unwrap_err();
+        assert_eq!(err.kind,
+                   ParseMatErrorKind::WrongLength {
+                       expected: 3,
+                       found: 2,
+                   });
+    }
+
+    #[test]
+    fn parse_errors_report_position() {
+        let err = "[1 2;\n 3 x]".parse::<Mat2x2<i32>>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.kind, ParseMatErrorKind::InvalidElement(String::from("x")));
+
+        let err = "[[1, 2],\n [3]]".parse::<MatDxD<i32>>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.kind,
+                   ParseMatErrorKind::RaggedRow {
+                       expected: 2,
+                       found: 1,
+                   });
+
+        let err = "[1 2; 3 4".parse::<Mat2x2<i32>>().unwrap_err();
+        assert_eq!(err.kind, ParseMatErrorKind::UnexpectedEnd);
+
+        let err = "[1 2 3; 4 5 6]".parse::<Mat2x2<i32>>().unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(err.kind,
+                   ParseMatErrorKind::WrongCols {
+                       expected: 2,
+                       found: 3,
+                   });
+    }
+}
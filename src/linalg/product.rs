@@ -0,0 +1,104 @@
+use std::ptr;
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use typehack::dim::*;
+
+
+/// The Kronecker product of two matrices. If `a` is `M x N` and `b` is `P x Q`, the result is the
+/// `MP x NQ` block matrix whose `[[i, j]]`th block is `a[[i, j]] * b`. The dimensions of the result
+/// are computed at the type level where possible.
+pub fn kron<T: Scalar, M: Dim, N: Dim, P: Dim, Q: Dim, L1: Layout, L2: Layout>
+    (a: &DenseMat<T, M, N, L1>,
+     b: &DenseMat<T, P, Q, L2>)
+     -> DenseMat<T, <M as DimMul<P>>::Result, <N as DimMul<Q>>::Result, L1> {
+    let (m, n) = (a.rows().reify(), a.cols().reify());
+    let (p, q) = (b.rows().reify(), b.cols().reify());
+
+    let mut out;
+
+    unsafe {
+        out = DenseMat::uninitialized(a.rows().mul(b.rows()), a.cols().mul(b.cols()));
+
+        for i in 0..m {
+            for j in 0..n {
+                for k in 0..p {
+                    for l in 0..q {
+                        ptr::write(&mut out[[i * p + k, j * q + l]],
+                                   a[[i, j]].clone() * b[[k, l]].clone());
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linalg::{Column, Row, Vect};
+    use typehack::binary::*;
+
+    #[test]
+    fn kron_2x2_2x2() {
+        let a = Mat![[1, 2], [3, 4]];
+        let b = Mat![#row [0, 5], [6, 7]];
+
+        let k = kron(&a, &b);
+
+        assert_eq!(k.rows(), B4::as_data());
+        assert_eq!(k.cols(), B4::as_data());
+        assert_eq!(k,
+                   Mat![[ 0,  5,  0, 10],
+                        [ 6,  7, 12, 14],
+                        [ 0, 15,  0, 20],
+                        [18, 21, 24, 28]]);
+    }
+
+
+    #[test]
+    fn kron_dyn() {
+        let a: DenseMat<i32, Dyn, Dyn, Column> = DenseMat::from_elem(Dyn(2), Dyn(1), 1);
+        let b = Mat![[1, 2, 3]];
+
+        let k = kron(&a, &b);
+
+        assert_eq!(k.rows(), Dyn(2));
+        assert_eq!(k.cols(), Dyn(3));
+        assert_eq!(k[[1, 2]], 3);
+    }
+
+
+    #[test]
+    fn outer_product() {
+        let u = Vect![1, 2];
+        let v = Vect![3, 4, 5];
+
+        assert_eq!(u.outer(&v), Mat![[3, 4, 5], [6, 8, 10]]);
+    }
+
+
+    #[test]
+    fn block_macro_2x2() {
+        let a = Mat![[1, 2], [3, 4]];
+        let b = Mat![[5], [6]];
+        let c = Mat![[7, 8]];
+        let d = Mat![[9]];
+
+        let m = block![#row [a, b], [c, d]];
+
+        assert_eq!(m, Mat![#row [1, 2, 5], [3, 4, 6], [7, 8, 9]]);
+    }
+
+
+    #[test]
+    fn block_macro_single_row() {
+        let m = block![[Mat![[1], [2]], Mat![[3], [4]]]];
+
+        assert_eq!(m, Mat![[1, 3], [2, 4]]);
+        let _: &DenseMat<_, _, _, Row> = &block![#row [Mat![[1]], Mat![[2]]]];
+    }
+}
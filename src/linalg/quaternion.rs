@@ -0,0 +1,626 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use geometry::primitive::Point;
+use linalg::{DenseMat, Layout, Mat3x3, Mat4x4, Scalar, Vect3};
+use num::traits::Float;
+use typehack::binary::{Nat, B3, B4};
+
+
+/// A quaternion `w + xi + yj + zk`. Unit quaternions represent rotations in three dimensions;
+/// the rotation by `angle` about the unit vector `n` is `cos(angle / 2) + sin(angle / 2) n`.
+///
+/// Rotations compose like matrices: `(p * q).rotate(v)` rotates `v` by `q`, then by `p`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Quat<T: Scalar> {
+    pub w: T,
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+
+/// The axes of a sequence of three elementary rotations. Angles are *intrinsic*: `XYZ` rotates
+/// about the `x` axis, then about the rotated `y` axis, then about the twice-rotated `z` axis, so
+/// that the rotation is `Rx(a) * Ry(b) * Rz(c)`. The same angles read in reverse order are the
+/// extrinsic, fixed-axis rotations `ZYX`.
+///
+/// The first six orders are Tait–Bryan angles, in which each axis appears once; the last six are
+/// proper Euler angles, in which the first and last axes are the same.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
+}
+
+
+impl EulerOrder {
+    pub fn all() -> [EulerOrder; 12] {
+        use self::EulerOrder::*;
+
+        [XYZ, XZY, YXZ, YZX, ZXY, ZYX, XYX, XZX, YXY, YZY, ZXZ, ZYZ]
+    }
+
+
+    /// The indices of the axes rotated about, in order, with `x`, `y` and `z` as `0`, `1` and `2`.
+    pub fn axes(&self) -> [usize; 3] {
+        use self::EulerOrder::*;
+
+        match *self {
+            XYZ => [0, 1, 2],
+            XZY => [0, 2, 1],
+            YXZ => [1, 0, 2],
+            YZX => [1, 2, 0],
+            ZXY => [2, 0, 1],
+            ZYX => [2, 1, 0],
+            XYX => [0, 1, 0],
+            XZX => [0, 2, 0],
+            YXY => [1, 0, 1],
+            YZY => [1, 2, 1],
+            ZXZ => [2, 0, 2],
+            ZYZ => [2, 1, 2],
+        }
+    }
+}
+
+
+/// Wraps an angle in `(-2pi, 2pi)` into `(-pi, pi]`.
+fn wrap_angle<T: Scalar + Float>(angle: T) -> T {
+    let two_pi = T::pi() + T::pi();
+
+    if angle > T::pi() {
+        angle - two_pi
+    } else if angle <= -T::pi() {
+        angle + two_pi
+    } else {
+        angle
+    }
+}
+
+
+impl<T: Scalar> Quat<T> {
+    pub fn new(w: T, x: T, y: T, z: T) -> Self {
+        Quat { w: w, x: x, y: y, z: z }
+    }
+
+
+    pub fn identity() -> Self {
+        Quat::new(T::one(), T::zero(), T::zero(), T::zero())
+    }
+
+
+    pub fn from_parts(w: T, v: Vect3<T>) -> Self {
+        Quat::new(w, v[0].clone(), v[1].clone(), v[2].clone())
+    }
+
+
+    /// The imaginary part, `(x, y, z)`.
+    pub fn vector_part(&self) -> Vect3<T> {
+        Vect3!(self.x.clone(), self.y.clone(), self.z.clone())
+    }
+
+
+    pub fn conjugate(&self) -> Self {
+        Quat::new(self.w.clone(), -self.x.clone(), -self.y.clone(), -self.z.clone())
+    }
+
+
+    pub fn dot(&self, rhs: &Self) -> T {
+        self.w.clone() * rhs.w.clone() + self.x.clone() * rhs.x.clone() +
+        self.y.clone() * rhs.y.clone() + self.z.clone() * rhs.z.clone()
+    }
+
+
+    pub fn squared_norm(&self) -> T {
+        self.dot(self)
+    }
+
+
+    /// Multiplies every component by `c`.
+    pub fn scale(&self, c: T) -> Self {
+        Quat::new(self.w.clone() * c.clone(),
+                  self.x.clone() * c.clone(),
+                  self.y.clone() * c.clone(),
+                  self.z.clone() * c)
+    }
+
+
+    /// The multiplicative inverse, or `None` for the zero quaternion.
+    pub fn inverse(&self) -> Option<Self> {
+        let n2 = self.squared_norm();
+
+        if n2.eq_zero() {
+            None
+        } else {
+            Some(self.conjugate().scale(T::one() / n2))
+        }
+    }
+}
+
+
+impl<T: Scalar + Float> Quat<T> {
+    pub fn norm(&self) -> T {
+        self.squared_norm().sqrt()
+    }
+
+
+    /// Scales the quaternion to unit length. The zero quaternion has no direction, and normalizes
+    /// to NaNs for floating-point scalars.
+    pub fn normalize(&self) -> Self {
+        self.scale(self.norm().recip())
+    }
+
+
+    /// The rotation by `angle` radians about `axis`, counterclockwise when looking down the axis
+    /// toward the origin. The axis need not be normalized.
+    pub fn from_axis_angle(axis: &Vect3<T>, angle: T) -> Self {
+        let half = angle / (T::one() + T::one());
+        let len = (axis[0].clone() * axis[0].clone() + axis[1].clone() * axis[1].clone() +
+                   axis[2].clone() * axis[2].clone())
+            .sqrt();
+
+        Quat::from_parts(half.clone().cos(), axis.clone() / len * half.sin())
+    }
+
+
+    /// The axis and angle of the rotation, with the angle in `[0, pi]`. The identity has no
+    /// well-defined axis; `(1, 0, 0)` is returned for it.
+    pub fn to_axis_angle(&self) -> (Vect3<T>, T) {
+        let q = self.normalize();
+        let q = if q.w.lt_zero() { -q } else { q };
+
+        let s = (q.x.clone() * q.x.clone() + q.y.clone() * q.y.clone() +
+                 q.z.clone() * q.z.clone())
+            .sqrt();
+        let angle = (s.clone().atan2(q.w.clone())) * (T::one() + T::one());
+
+        if s.eq_zero() {
+            (Vect3!(T::one(), T::zero(), T::zero()), T::zero())
+        } else {
+            (q.vector_part() / s, angle)
+        }
+    }
+
+
+    /// The rotation about the given axis of the standard basis.
+    fn elementary(axis: usize, angle: T) -> Self {
+        let half = angle / (T::one() + T::one());
+        let mut q = Quat::new(half.clone().cos(), T::zero(), T::zero(), T::zero());
+
+        match axis {
+            0 => q.x = half.sin(),
+            1 => q.y = half.sin(),
+            _ => q.z = half.sin(),
+        }
+
+        q
+    }
+
+
+    /// The rotation by the intrinsic Euler angles `[a, b, c]` in the given order.
+    pub fn from_euler(order: EulerOrder, angles: [T; 3]) -> Self {
+        let axes = order.axes();
+
+        Quat::elementary(axes[0], angles[0].clone()) *
+        Quat::elementary(axes[1], angles[1].clone()) *
+        Quat::elementary(axes[2], angles[2].clone())
+    }
+
+
+    /// Intrinsic Euler angles `[a, b, c]` in the given order which reproduce this rotation.
+    ///
+    /// The first and last angles are in `(-pi, pi]`. The middle angle is in `[0, pi]` for proper
+    /// Euler angles and in `[-pi/2, pi/2]` for Tait–Bryan angles. At gimbal lock, where the middle
+    /// angle makes the first and last axes coincide, only their sum or difference is determined;
+    /// the last angle is then taken to be zero.
+    pub fn to_euler(&self, order: EulerOrder) -> [T; 3] {
+        // This is the method of Bernardes and Viollet, "Quaternion to Euler angles conversion: A
+        // direct, general and computationally efficient method" (2022). It works in terms of
+        // extrinsic angles, which are our intrinsic angles in reverse.
+        let axes = order.axes();
+        let (i, j, mut k) = (axes[2], axes[1], axes[0]);
+        let symmetric = i == k;
+
+        if symmetric {
+            k = 3 - i - j;
+        }
+
+        // The sign of the permutation `(i, j, k)`.
+        let even = (i + 1) % 3 == j;
+        let v = [self.x.clone(), self.y.clone(), self.z.clone()];
+        let vk = if even { v[k].clone() } else { -v[k].clone() };
+
+        let (a, b, c, d) = if symmetric {
+            (self.w.clone(), v[i].clone(), v[j].clone(), vk)
+        } else {
+            (self.w.clone() - v[j].clone(),
+             v[i].clone() + vk.clone(),
+             v[j].clone() + self.w.clone(),
+             vk - v[i].clone())
+        };
+
+        let two = T::one() + T::one();
+        let hypot = |p: T, q: T| (p.clone() * p + q.clone() * q).sqrt();
+
+        let mut middle = hypot(c.clone(), d.clone()).atan2(hypot(a.clone(), b.clone())) *
+                         two.clone();
+        let half_sum = b.atan2(a);
+        let half_diff = d.atan2(c);
+
+        let tolerance = T::epsilon().sqrt();
+        let (mut first, last) = if middle.abs() <= tolerance {
+            (half_sum * two.clone(), T::zero())
+        } else if (middle.clone() - T::pi()).abs() <= tolerance {
+            (half_diff * two.clone(), T::zero())
+        } else {
+            (half_sum.clone() + half_diff.clone(), half_sum - half_diff)
+        };
+
+        if !symmetric {
+            if !even {
+                first = -first;
+            }
+
+            middle = middle - T::pi() / two;
+        }
+
+        [wrap_angle(first), middle, wrap_angle(last)]
+    }
+
+
+    /// Rotates a vector. The quaternion is assumed to be of unit length.
+    pub fn rotate(&self, v: &Vect3<T>) -> Vect3<T> {
+        (self.clone() * Quat::from_parts(T::zero(), v.clone()) * self.conjugate()).vector_part()
+    }
+
+
+    /// Normalized linear interpolation, along the shorter arc. Cheaper than `slerp`, but does not
+    /// move at a constant angular velocity.
+    pub fn nlerp(&self, rhs: &Self, t: T) -> Self {
+        let rhs = if self.dot(rhs).lt_zero() { -rhs.clone() } else { rhs.clone() };
+
+        (self.scale(T::one() - t.clone()) + rhs.scale(t)).normalize()
+    }
+
+
+    /// Spherical linear interpolation, along the shorter arc, at a constant angular velocity. Both
+    /// quaternions are assumed to be of unit length.
+    pub fn slerp(&self, rhs: &Self, t: T) -> Self {
+        let mut cos = self.dot(rhs);
+        let rhs = if cos.lt_zero() {
+            cos = -cos;
+            -rhs.clone()
+        } else {
+            rhs.clone()
+        };
+
+        // Nearly parallel quaternions would divide by a vanishing sine; the chord is then a fine
+        // approximation of the arc.
+        if cos > T::one() - T::epsilon().sqrt() {
+            return self.nlerp(&rhs, t);
+        }
+
+        let theta = cos.acos();
+        let sin = theta.clone().sin();
+
+        self.scale(((T::one() - t.clone()) * theta.clone()).sin() / sin.clone()) +
+        rhs.scale((t * theta).sin() / sin)
+    }
+
+
+    /// The rotation given by a rotation matrix, by Shepperd's method. The matrix is assumed to be
+    /// orthogonal with a determinant of one.
+    fn from_rotation<F: Fn(usize, usize) -> T>(m: F) -> Self {
+        let two = T::one() + T::one();
+        let quarter = T::one() / (two.clone() * two.clone());
+        let trace = m(0, 0) + m(1, 1) + m(2, 2);
+
+        let q = if trace.gt_zero() {
+            let s = (trace + T::one()).sqrt() * two;
+            Quat::new(s.clone() * quarter,
+                      (m(2, 1) - m(1, 2)) / s.clone(),
+                      (m(0, 2) - m(2, 0)) / s.clone(),
+                      (m(1, 0) - m(0, 1)) / s)
+        } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+            let s = (T::one() + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * two;
+            Quat::new((m(2, 1) - m(1, 2)) / s.clone(),
+                      s.clone() * quarter,
+                      (m(0, 1) + m(1, 0)) / s.clone(),
+                      (m(0, 2) + m(2, 0)) / s)
+        } else if m(1, 1) > m(2, 2) {
+            let s = (T::one() + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * two;
+            Quat::new((m(0, 2) - m(2, 0)) / s.clone(),
+                      (m(0, 1) + m(1, 0)) / s.clone(),
+                      s.clone() * quarter,
+                      (m(1, 2) + m(2, 1)) / s)
+        } else {
+            let s = (T::one() + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * two;
+            Quat::new((m(1, 0) - m(0, 1)) / s.clone(),
+                      (m(0, 2) + m(2, 0)) / s.clone(),
+                      (m(1, 2) + m(2, 1)) / s.clone(),
+                      s * quarter)
+        };
+
+        q.normalize()
+    }
+
+
+    /// Writes the rotation matrix of this quaternion into the upper-left `3 x 3` block of `m`.
+    fn write_rotation<F: FnMut(usize, usize, T)>(&self, mut m: F) {
+        let q = self.normalize();
+        let two = T::one() + T::one();
+        let (w, x, y, z) = (q.w, q.x, q.y, q.z);
+
+        let (xx, yy, zz) = (x.clone() * x.clone(), y.clone() * y.clone(), z.clone() * z.clone());
+        let (xy, xz, yz) = (x.clone() * y.clone(), x.clone() * z.clone(), y.clone() * z.clone());
+        let (wx, wy, wz) = (w.clone() * x, w.clone() * y, w * z);
+
+        m(0, 0, T::one() - two.clone() * (yy.clone() + zz.clone()));
+        m(0, 1, two.clone() * (xy.clone() - wz.clone()));
+        m(0, 2, two.clone() * (xz.clone() + wy.clone()));
+        m(1, 0, two.clone() * (xy + wz));
+        m(1, 1, T::one() - two.clone() * (xx.clone() + zz));
+        m(1, 2, two.clone() * (yz.clone() - wx.clone()));
+        m(2, 0, two.clone() * (xz - wy));
+        m(2, 1, two.clone() * (yz + wx));
+        m(2, 2, T::one() - two * (xx + yy));
+    }
+}
+
+
+/// The Hamilton product.
+impl<T: Scalar> Mul for Quat<T> {
+    type Output = Quat<T>;
+
+    fn mul(self, rhs: Quat<T>) -> Quat<T> {
+        let Quat { w: a1, x: b1, y: c1, z: d1 } = self;
+        let Quat { w: a2, x: b2, y: c2, z: d2 } = rhs;
+
+        Quat::new(a1.clone() * a2.clone() - b1.clone() * b2.clone() - c1.clone() * c2.clone() -
+                  d1.clone() * d2.clone(),
+                  a1.clone() * b2.clone() + b1.clone() * a2.clone() + c1.clone() * d2.clone() -
+                  d1.clone() * c2.clone(),
+                  a1.clone() * c2.clone() - b1.clone() * d2.clone() + c1.clone() * a2.clone() +
+                  d1.clone() * b2.clone(),
+                  a1 * d2 + b1 * c2 - c1 * b2 + d1 * a2)
+    }
+}
+
+
+impl<T: Scalar + Float> Mul<Vect3<T>> for Quat<T> {
+    type Output = Vect3<T>;
+
+    fn mul(self, rhs: Vect3<T>) -> Vect3<T> {
+        self.rotate(&rhs)
+    }
+}
+
+
+impl<T: Scalar + Float> Mul<Point<T, B3>> for Quat<T> {
+    type Output = Point<T, B3>;
+
+    fn mul(self, rhs: Point<T, B3>) -> Point<T, B3> {
+        Point::from(self.rotate(&Vect3::from(rhs)))
+    }
+}
+
+
+impl<T: Scalar> Add for Quat<T> {
+    type Output = Quat<T>;
+
+    fn add(self, rhs: Quat<T>) -> Quat<T> {
+        Quat::new(self.w + rhs.w, self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+
+impl<T: Scalar> Sub for Quat<T> {
+    type Output = Quat<T>;
+
+    fn sub(self, rhs: Quat<T>) -> Quat<T> {
+        Quat::new(self.w - rhs.w, self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+
+impl<T: Scalar> Neg for Quat<T> {
+    type Output = Quat<T>;
+
+    fn neg(self) -> Quat<T> {
+        Quat::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+
+impl<T: Scalar + Float, L: Layout> From<Quat<T>> for Mat3x3<T, L> {
+    fn from(q: Quat<T>) -> Mat3x3<T, L> {
+        let mut m = DenseMat::from_elem(B3::as_data(), B3::as_data(), T::zero());
+        q.write_rotation(|i, j, x| m[[i, j]] = x);
+        m
+    }
+}
+
+
+/// The homogeneous rotation matrix, with no translation.
+impl<T: Scalar + Float, L: Layout> From<Quat<T>> for Mat4x4<T, L> {
+    fn from(q: Quat<T>) -> Mat4x4<T, L> {
+        let mut m = DenseMat::from_elem(B4::as_data(), B4::as_data(), T::zero());
+        q.write_rotation(|i, j, x| m[[i, j]] = x);
+        m[[3, 3]] = T::one();
+        m
+    }
+}
+
+
+impl<'a, T: Scalar + Float, L: Layout> From<&'a Mat3x3<T, L>> for Quat<T> {
+    fn from(m: &Mat3x3<T, L>) -> Quat<T> {
+        Quat::from_rotation(|i, j| m[[i, j]].clone())
+    }
+}
+
+
+/// The rotation of a homogeneous transformation matrix, read from its upper-left `3 x 3` block.
+/// Any translation or projection is ignored.
+impl<'a, T: Scalar + Float, L: Layout> From<&'a Mat4x4<T, L>> for Quat<T> {
+    fn from(m: &Mat4x4<T, L>) -> Quat<T> {
+        Quat::from_rotation(|i, j| m[[i, j]].clone())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use linalg::Row;
+
+    fn assert_quat_eq(p: &Quat<f64>, q: &Quat<f64>) {
+        // `q` and `-q` are the same rotation.
+        let d = p.dot(q).abs();
+        assert!((d - 1.).abs() < 1e-10, "{:?} != {:?}", p, q);
+    }
+
+    fn assert_vect_eq(u: &Vect3<f64>, v: &Vect3<f64>) {
+        for i in 0..3 {
+            assert!((u[i] - v[i]).abs() < 1e-10, "{:?} != {:?}", u, v);
+        }
+    }
+
+    #[test]
+    fn quat_hamilton_product() {
+        let i = Quat::new(0., 1., 0., 0.);
+        let j = Quat::new(0., 0., 1., 0.);
+        let k = Quat::new(0., 0., 0., 1.);
+
+        assert_eq!(i * j, k);
+        assert_eq!(j * i, -k);
+        assert_eq!(i * j * k, Quat::new(-1., 0., 0., 0.));
+
+        let q = Quat::new(1., 2., -3., 0.5);
+        assert_quat_eq(&(q * q.inverse().unwrap()), &Quat::identity());
+        assert_eq!(Quat::<f64>::new(0., 0., 0., 0.).inverse(), None);
+    }
+
+    #[test]
+    fn quat_rotate() {
+        let q = Quat::from_axis_angle(&Vect3!(0., 0., 2.), PI / 2.);
+
+        assert_vect_eq(&(q * Vect3!(1., 0., 0.)), &Vect3!(0., 1., 0.));
+        let p = q * Point::from(Vect3!(0., 3., 1.));
+        assert_vect_eq(&p.into(), &Vect3!(-3., 0., 1.));
+
+        // Composition applies the right-hand rotation first.
+        let r = Quat::from_axis_angle(&Vect3!(1., 0., 0.), PI / 2.);
+        assert_vect_eq(&(r * q).rotate(&Vect3!(1., 0., 0.)), &Vect3!(0., 0., 1.));
+    }
+
+    #[test]
+    fn quat_axis_angle_round_trip() {
+        let axis = Vect3!(1., -2., 0.5);
+        let q = Quat::from_axis_angle(&axis, 2.);
+        let (n, angle) = q.to_axis_angle();
+
+        assert!((angle - 2.).abs() < 1e-12);
+        assert_vect_eq(&n, &(axis.clone() / 5.25f64.sqrt()));
+
+        // Angles past pi come back about the opposite axis.
+        let (n, angle) = Quat::from_axis_angle(&axis, 4.).to_axis_angle();
+        assert!((angle - (2. * PI - 4.)).abs() < 1e-12);
+        assert_vect_eq(&n, &(-axis / 5.25f64.sqrt()));
+
+        assert_eq!(Quat::<f64>::identity().to_axis_angle().1, 0.);
+    }
+
+    #[test]
+    fn quat_matrix_round_trip() {
+        // Exercise every branch of Shepperd's method, including half-turns.
+        let qs = [Quat::from_axis_angle(&Vect3!(1., 2., 3.), 0.7),
+                  Quat::from_axis_angle(&Vect3!(1., 0., 0.), PI),
+                  Quat::from_axis_angle(&Vect3!(0., 1., 0.1), 3.),
+                  Quat::from_axis_angle(&Vect3!(0.1, 0., 1.), -3.)];
+
+        for q in &qs {
+            let m: Mat3x3<f64> = (*q).into();
+            assert_quat_eq(&Quat::from(&m), q);
+
+            let v = Vect3!(0.3, -1., 2.);
+            let mv = Vect3::from_fn(B3::as_data(), |i| (0..3).map(|j| m[[i, j]] * v[j]).sum());
+            assert_vect_eq(&mv, &q.rotate(&v));
+
+            let h: Mat4x4<f64, Row> = (*q).into();
+            assert_eq!(h[[3, 3]], 1.);
+            assert_eq!(h[[0, 3]], 0.);
+            assert_quat_eq(&Quat::from(&h), q);
+        }
+    }
+
+    #[test]
+    fn quat_euler_round_trip() {
+        let angles = [[0.1, 0.2, 0.3], [-2.5, 1.1, 3.], [1., -1.4, -0.2]];
+
+        for &order in &EulerOrder::all() {
+            let symmetric = order.axes()[0] == order.axes()[2];
+
+            for abc in &angles {
+                // Proper Euler angles take their middle angle in `[0, pi]`.
+                let (a, b, c) = (abc[0], if symmetric { abc[1].abs() } else { abc[1] }, abc[2]);
+                let q = Quat::from_euler(order, [a, b, c]);
+                let e = q.to_euler(order);
+
+                assert!((e[0] - a).abs() < 1e-10 && (e[1] - b).abs() < 1e-10 &&
+                        (e[2] - c).abs() < 1e-10,
+                        "{:?}: {:?} != {:?}",
+                        order,
+                        e,
+                        [a, b, c]);
+            }
+        }
+    }
+
+    #[test]
+    fn quat_euler_gimbal_lock() {
+        for &order in &EulerOrder::all() {
+            let symmetric = order.axes()[0] == order.axes()[2];
+            let locks = if symmetric { [0., PI] } else { [PI / 2., -PI / 2.] };
+
+            for &b in &locks {
+                let q = Quat::from_euler(order, [0.4, b, -1.2]);
+                let e = q.to_euler(order);
+
+                // Only the sum or difference of the outer angles survives, so we get the same
+                // rotation back with the last angle zeroed.
+                assert_eq!(e[2], 0.);
+                assert!((e[1] - b).abs() < 1e-6, "{:?}: {:?}", order, e);
+                assert_quat_eq(&Quat::from_euler(order, e), &q);
+            }
+        }
+    }
+
+    #[test]
+    fn quat_slerp() {
+        let p = Quat::identity();
+        let q = Quat::from_axis_angle(&Vect3!(0., 1., 0.), 2.);
+
+        assert_quat_eq(&p.slerp(&q, 0.), &p);
+        assert_quat_eq(&p.slerp(&q, 1.), &q);
+        assert_quat_eq(&p.slerp(&q, 0.25), &Quat::from_axis_angle(&Vect3!(0., 1., 0.), 0.5));
+
+        // Both interpolations take the shorter arc when given the antipodal quaternion.
+        assert_quat_eq(&p.slerp(&-q, 0.5), &Quat::from_axis_angle(&Vect3!(0., 1., 0.), 1.));
+        assert_quat_eq(&p.nlerp(&-q, 0.5), &Quat::from_axis_angle(&Vect3!(0., 1., 0.), 1.));
+
+        // Nearly identical rotations fall back to `nlerp`.
+        let r = Quat::from_axis_angle(&Vect3!(0., 1., 0.), 1e-9);
+        assert_quat_eq(&p.slerp(&r, 0.5), &r);
+    }
+}
@@ -0,0 +1,461 @@
+use std::ops::Mul;
+
+use geometry::primitive::Point;
+use linalg::{Mat, MatrixIdentity, MatrixNorm, MatrixTranspose, Quat, Scalar, Square, Vect, Vect2,
+             Vect3};
+use linalg::algorithm::solve::lu::LuDecompositionExt;
+use num::complex::Complex;
+use num::traits::Float;
+use typehack::binary::{Nat, B2, B3};
+use typehack::dim::*;
+
+
+/// The maximum number of Newton iterations `Rotation::nearest` takes toward the orthogonal
+/// polar factor.
+const POLAR_MAX_ITERATIONS: usize = 100;
+
+
+/// A rotation in `D` dimensions: an orthogonal matrix with a determinant of one. Unlike a bare
+/// `Mat`, a `Rotation` can only be composed with other rotations, and so stays a rotation; its
+/// inverse is simply its transpose.
+///
+/// Floating-point error accumulates when many rotations are composed. `renormalize` restores the
+/// invariant.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rotation<T: Scalar, D: Dim> {
+    mat: Mat<T, D, D>,
+}
+
+
+pub type Rotation2<T> = Rotation<T, B2>;
+pub type Rotation3<T> = Rotation<T, B3>;
+
+
+/// The largest absolute difference between corresponding elements.
+fn max_diff<T: Scalar, D: Dim>(a: &Mat<T, D, D>, b: &Mat<T, D, D>) -> T {
+    a.clone().zip_map(b.clone(), |x, y| (x - y).abs()).fold(T::zero(), |m, e| {
+        if *e > m { e.clone() } else { m }
+    })
+}
+
+
+impl<T: Scalar, D: Dim> Rotation<T, D> {
+    pub fn identity(dim: D) -> Self {
+        Rotation { mat: MatrixIdentity::eye(dim) }
+    }
+
+
+    /// Wraps a matrix without checking that it is a rotation. This is unsafe because the caller
+    /// must ensure that `mat` is orthogonal with a determinant of one; if it is not, `inverse` is no
+    /// longer its inverse. Use `from_mat` or `nearest` to check or enforce the invariant instead.
+    pub unsafe fn from_mat_unchecked(mat: Mat<T, D, D>) -> Self {
+        Rotation { mat: mat }
+    }
+
+
+    pub fn dim(&self) -> D {
+        self.mat.side()
+    }
+
+
+    pub fn as_mat(&self) -> &Mat<T, D, D> {
+        &self.mat
+    }
+
+
+    pub fn into_mat(self) -> Mat<T, D, D> {
+        self.mat
+    }
+
+
+    /// The inverse rotation, which is the transpose of the matrix.
+    pub fn inverse(&self) -> Self {
+        Rotation { mat: self.mat.clone().transpose() }
+    }
+}
+
+
+impl<T: Scalar + Float, D: Dim> Rotation<T, D> {
+    /// Wraps a matrix if it is a rotation to within a tolerance of the square root of machine
+    /// epsilon: if `M^T M` is the identity and `M` has a positive determinant.
+    pub fn from_mat(mat: Mat<T, D, D>) -> Option<Self> {
+        let eye: Mat<T, D, D> = MatrixIdentity::eye(mat.side());
+        let transpose: Mat<T, D, D> = mat.clone().transpose();
+        let gram = transpose * mat.clone();
+
+        if max_diff(&gram, &eye) > T::epsilon().sqrt() ||
+           !mat.clone().lu().determinant().gt_zero() {
+            return None;
+        }
+
+        Some(Rotation { mat: mat })
+    }
+
+
+    /// The rotation nearest to `mat` in the Frobenius norm, found as the orthogonal factor of its
+    /// polar decomposition by Newton's iteration `X <- (X + X^-T) / 2`. Returns `None` if `mat` is
+    /// singular or if the nearest orthogonal matrix is a reflection rather than a rotation.
+    pub fn nearest(mat: &Mat<T, D, D>) -> Option<Self> {
+        let half = T::one() / (T::one() + T::one());
+        let tolerance = T::epsilon() * T::from_usize(100 * mat.side().reify().max(1));
+
+        let mut x = mat.clone();
+
+        for _ in 0..POLAR_MAX_ITERATIONS {
+            let inv_t: Mat<T, D, D> = match x.clone().lu().inverse() {
+                Some(inv) => inv.transpose(),
+                None => return None,
+            };
+
+            let next = x.clone().zip_map(inv_t, |a, b| (a + b) * half.clone());
+            let converged = max_diff(&next, &x) <= tolerance.clone() * next.one_norm();
+            x = next;
+
+            if converged {
+                break;
+            }
+        }
+
+        if x.clone().lu().determinant().gt_zero() {
+            Some(Rotation { mat: x })
+        } else {
+            None
+        }
+    }
+
+
+    /// Restores orthonormality lost to rounding error by Gram–Schmidt orthonormalization of the
+    /// columns, in order. The first column keeps its direction.
+    pub fn renormalize(&self) -> Self {
+        let n = self.dim().reify();
+        let mut mat = self.mat.clone();
+
+        for j in 0..n {
+            for k in 0..j {
+                let proj = (0..n).map(|i| mat[[i, j]].clone() * mat[[i, k]].clone()).sum::<T>();
+
+                for i in 0..n {
+                    let delta = proj.clone() * mat[[i, k]].clone();
+                    mat[[i, j]] -= delta;
+                }
+            }
+
+            let len = (0..n).map(|i| mat[[i, j]].clone() * mat[[i, j]].clone()).sum::<T>().sqrt();
+
+            for i in 0..n {
+                mat[[i, j]] /= len.clone();
+            }
+        }
+
+        Rotation { mat: mat }
+    }
+}
+
+
+impl<T: Scalar + Float> Rotation<T, B2> {
+    /// The counterclockwise rotation by `angle` radians.
+    pub fn from_angle(angle: T) -> Self {
+        let (c, s) = (angle.clone().cos(), angle.sin());
+
+        Rotation { mat: Mat![[c.clone(), -s.clone()], [s.clone(), c]] }
+    }
+
+
+    /// The angle of the rotation, in `(-pi, pi]`.
+    pub fn angle(&self) -> T {
+        self.mat[[1, 0]].clone().atan2(self.mat[[0, 0]].clone())
+    }
+}
+
+
+impl<T: Scalar + Float> Rotation<T, B3> {
+    /// The rotation by `angle` radians about `axis`. The axis need not be normalized.
+    pub fn from_axis_angle(axis: &Vect3<T>, angle: T) -> Self {
+        Quat::from_axis_angle(axis, angle).into()
+    }
+
+
+    /// The axis and angle of the rotation, with the angle in `[0, pi]`.
+    pub fn to_axis_angle(&self) -> (Vect3<T>, T) {
+        Quat::from(self.clone()).to_axis_angle()
+    }
+}
+
+
+impl<T: Scalar + Float> From<Quat<T>> for Rotation<T, B3> {
+    fn from(q: Quat<T>) -> Self {
+        Rotation { mat: q.into() }
+    }
+}
+
+
+impl<T: Scalar + Float> From<Rotation<T, B3>> for Quat<T> {
+    fn from(r: Rotation<T, B3>) -> Self {
+        Quat::from(&r.mat)
+    }
+}
+
+
+impl<T: Scalar, D: Dim> From<Rotation<T, D>> for Mat<T, D, D> {
+    fn from(r: Rotation<T, D>) -> Self {
+        r.mat
+    }
+}
+
+
+/// Composition: `a * b` rotates by `b`, then by `a`.
+impl<T: Scalar, D: Dim> Mul for Rotation<T, D> {
+    type Output = Rotation<T, D>;
+
+    fn mul(self, rhs: Rotation<T, D>) -> Rotation<T, D> {
+        Rotation { mat: self.mat * rhs.mat }
+    }
+}
+
+
+impl<T: Scalar, D: Dim> Mul<Vect<T, D>> for Rotation<T, D> {
+    type Output = Vect<T, D>;
+
+    fn mul(self, rhs: Vect<T, D>) -> Vect<T, D> {
+        &self.mat * &rhs
+    }
+}
+
+
+impl<'a, T: Scalar, D: Dim> Mul<&'a Vect<T, D>> for &'a Rotation<T, D> {
+    type Output = Vect<T, D>;
+
+    fn mul(self, rhs: &'a Vect<T, D>) -> Vect<T, D> {
+        &self.mat * rhs
+    }
+}
+
+
+/// Rotates a point about the origin.
+impl<T: Scalar, D: Dim> Mul<Point<T, D>> for Rotation<T, D> {
+    type Output = Point<T, D>;
+
+    fn mul(self, rhs: Point<T, D>) -> Point<T, D> {
+        Point::from(&self.mat * &Vect::from(rhs))
+    }
+}
+
+
+/// A two-dimensional rotation stored as a unit complex number `cos(angle) + sin(angle) i`. It is
+/// cheaper to compose and renormalize than a `Rotation2`, and converts to and from one.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct UnitComplex<T: Scalar> {
+    complex: Complex<T>,
+}
+
+
+impl<T: Scalar> UnitComplex<T> {
+    pub fn identity() -> Self {
+        UnitComplex { complex: Complex::real(T::one()) }
+    }
+
+
+    /// Wraps a complex number without checking that it has unit magnitude. This is unsafe because
+    /// the caller must ensure that `|complex| = 1`; see `Rotation::from_mat_unchecked`.
+    pub unsafe fn from_complex_unchecked(complex: Complex<T>) -> Self {
+        UnitComplex { complex: complex }
+    }
+
+
+    pub fn as_complex(&self) -> &Complex<T> {
+        &self.complex
+    }
+
+
+    pub fn cos(&self) -> T {
+        self.complex.re.clone()
+    }
+
+
+    pub fn sin(&self) -> T {
+        self.complex.im.clone()
+    }
+
+
+    /// The inverse rotation, which is the complex conjugate.
+    pub fn inverse(&self) -> Self {
+        UnitComplex { complex: self.complex.conj() }
+    }
+}
+
+
+impl<T: Scalar + Float> UnitComplex<T> {
+    /// The counterclockwise rotation by `angle` radians.
+    pub fn from_angle(angle: T) -> Self {
+        UnitComplex { complex: Complex::new(angle.clone().cos(), angle.sin()) }
+    }
+
+
+    /// Normalizes a nonzero complex number, or returns `None` if it is zero.
+    pub fn new(complex: Complex<T>) -> Option<Self> {
+        let norm = complex.norm();
+
+        if norm.eq_zero() {
+            None
+        } else {
+            Some(UnitComplex { complex: complex.scale(norm.recip()) })
+        }
+    }
+
+
+    /// The angle of the rotation, in `(-pi, pi]`.
+    pub fn angle(&self) -> T {
+        self.complex.arg()
+    }
+
+
+    /// Restores unit magnitude lost to rounding error.
+    pub fn renormalize(&self) -> Self {
+        UnitComplex { complex: self.complex.scale(self.complex.norm().recip()) }
+    }
+}
+
+
+impl<T: Scalar> From<UnitComplex<T>> for Rotation<T, B2> {
+    fn from(r: UnitComplex<T>) -> Self {
+        let (c, s) = (r.cos(), r.sin());
+
+        Rotation { mat: Mat![[c.clone(), -s.clone()], [s.clone(), c]] }
+    }
+}
+
+
+impl<T: Scalar> From<Rotation<T, B2>> for UnitComplex<T> {
+    fn from(r: Rotation<T, B2>) -> Self {
+        UnitComplex { complex: Complex::new(r.mat[[0, 0]].clone(), r.mat[[1, 0]].clone()) }
+    }
+}
+
+
+/// Composition: `a * b` rotates by `b`, then by `a`.
+impl<T: Scalar> Mul for UnitComplex<T> {
+    type Output = UnitComplex<T>;
+
+    fn mul(self, rhs: UnitComplex<T>) -> UnitComplex<T> {
+        UnitComplex { complex: self.complex * rhs.complex }
+    }
+}
+
+
+impl<T: Scalar> Mul<Vect2<T>> for UnitComplex<T> {
+    type Output = Vect2<T>;
+
+    fn mul(self, rhs: Vect2<T>) -> Vect2<T> {
+        let (c, s) = (self.cos(), self.sin());
+
+        Vect2!(c.clone() * rhs[0].clone() - s.clone() * rhs[1].clone(),
+               s * rhs[0].clone() + c * rhs[1].clone())
+    }
+}
+
+
+/// Rotates a point about the origin.
+impl<T: Scalar> Mul<Point<T, B2>> for UnitComplex<T> {
+    type Output = Point<T, B2>;
+
+    fn mul(self, rhs: Point<T, B2>) -> Point<T, B2> {
+        Point::from(self * Vect::from(rhs))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn assert_mat_eq<D: Dim>(a: &Mat<f64, D, D>, b: &Mat<f64, D, D>) {
+        assert!(max_diff(a, b) < 1e-10, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn rotation_2d() {
+        let a = Rotation::from_angle(0.5);
+        let b = Rotation::from_angle(1.);
+
+        assert!(((a.clone() * b.clone()).angle() - 1.5).abs() < 1e-12);
+        assert!((a.inverse().angle() + 0.5).abs() < 1e-12);
+        assert_mat_eq((a.clone() * a.inverse()).as_mat(), Rotation::identity(B2::as_data()).as_mat());
+
+        let v = Rotation::from_angle(PI / 2.) * Vect![1., 0.];
+        assert!(v[0].abs() < 1e-12 && (v[1] - 1.).abs() < 1e-12);
+
+        let p = Rotation::from_angle(PI) * Point::from(Vect![2., 1.]);
+        assert!((p[0] + 2.).abs() < 1e-12 && (p[1] + 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rotation_3d_matches_quat() {
+        let q = Quat::from_axis_angle(&Vect3!(1., 1., 0.), 1.2);
+        let r = Rotation::from(q);
+        let v = Vect3!(0.5, -2., 3.);
+
+        let u = r.clone() * v.clone();
+        let w = q.rotate(&v);
+        for i in 0..3 {
+            assert!((u[i] - w[i]).abs() < 1e-12);
+        }
+
+        let (axis, angle) = r.to_axis_angle();
+        assert!((angle - 1.2).abs() < 1e-12);
+        assert!((axis[0] - 0.5f64.sqrt()).abs() < 1e-12 && axis[2].abs() < 1e-12);
+    }
+
+    #[test]
+    fn rotation_from_mat_checks_invariant() {
+        assert!(Rotation::from_mat(Mat![[0., -1.], [1., 0.]]).is_some());
+
+        // Orthogonal, but a reflection.
+        assert!(Rotation::from_mat(Mat![[1., 0.], [0., -1.]]).is_none());
+        // Not orthogonal.
+        assert!(Rotation::from_mat(Mat![[1., 0.1], [0., 1.]]).is_none());
+    }
+
+    #[test]
+    fn rotation_renormalize_and_nearest() {
+        let exact = Rotation::from_axis_angle(&Vect3!(0., 0., 1.), 0.3);
+        let mut drifted = exact.clone().into_mat();
+        drifted[[0, 0]] *= 1.001;
+        drifted[[1, 0]] += 0.0005;
+        drifted[[2, 2]] *= 0.999;
+
+        let polar = Rotation::nearest(&drifted).unwrap();
+        assert!(Rotation::from_mat(polar.clone().into_mat()).is_some());
+        assert!(max_diff(polar.as_mat(), exact.as_mat()) < 1e-3);
+
+        let gs = unsafe { Rotation::from_mat_unchecked(drifted) }.renormalize();
+        assert!(Rotation::from_mat(gs.clone().into_mat()).is_some());
+        assert!(max_diff(gs.as_mat(), exact.as_mat()) < 1e-3);
+
+        assert!(Rotation::nearest(&Mat![[-1., 0.], [0., 1.]]).is_none());
+        assert!(Rotation::nearest(&Mat![[1., 2.], [2., 4.]]).is_none());
+    }
+
+    #[test]
+    fn unit_complex_matches_rotation() {
+        let a = UnitComplex::from_angle(0.5);
+        let b = UnitComplex::from_angle(1.);
+
+        assert!(((a * b).angle() - 1.5).abs() < 1e-12);
+        assert!(((a * a.inverse()).angle()).abs() < 1e-12);
+
+        let r = Rotation2::from(a);
+        assert!((r.angle() - 0.5).abs() < 1e-12);
+        assert!((UnitComplex::from(r.clone()).angle() - 0.5).abs() < 1e-12);
+
+        let v = Vect![2., -1.];
+        let (u, w) = (a * v.clone(), r * v);
+        assert!((u[0] - w[0]).abs() < 1e-12 && (u[1] - w[1]).abs() < 1e-12);
+
+        let drifted = unsafe { UnitComplex::from_complex_unchecked(Complex::new(0.6, 0.81)) };
+        let drifted = drifted.renormalize();
+        assert!((drifted.as_complex().norm() - 1.).abs() < 1e-12);
+        assert!(UnitComplex::new(Complex::new(0., 0.)).is_none());
+    }
+}
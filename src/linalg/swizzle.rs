@@ -0,0 +1,175 @@
+//! Swizzles: `v.xy()`, `v.zyx()`, `v.xxyy()` and so on, for every combination of two, three or
+//! four components of a `Vect2`, `Vect3` or `Vect4`. Each invocation of `impl_swizzles!` lists
+//! the combinations for one vector size, in lexicographic order of component indices.
+
+use geometry::frame::Frame;
+use linalg::{DenseVec, Scalar};
+use typehack::binary::{B2, B3, B4};
+
+
+macro_rules! impl_swizzles {
+    ($n:ident { $($name:ident => $size:ident [$($i:expr),*]),* $(,)* }) => {
+        impl<T: Clone + Scalar, F: Frame> DenseVec<T, $n, F> {
+            $(#[inline]
+            pub fn $name(&self) -> DenseVec<T, $size, F> {
+                DenseVec::from_data(data![$(self[$i].clone()),*]).in_frame()
+            })*
+        }
+    }
+}
+
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+impl_swizzles!(B2 {
+    xx => B2[0, 0], xy => B2[0, 1], yx => B2[1, 0], yy => B2[1, 1],
+
+    xxx => B3[0, 0, 0], xxy => B3[0, 0, 1], xyx => B3[0, 1, 0], xyy => B3[0, 1, 1],
+    yxx => B3[1, 0, 0], yxy => B3[1, 0, 1], yyx => B3[1, 1, 0], yyy => B3[1, 1, 1],
+
+    xxxx => B4[0, 0, 0, 0], xxxy => B4[0, 0, 0, 1], xxyx => B4[0, 0, 1, 0], xxyy => B4[0, 0, 1, 1],
+    xyxx => B4[0, 1, 0, 0], xyxy => B4[0, 1, 0, 1], xyyx => B4[0, 1, 1, 0], xyyy => B4[0, 1, 1, 1],
+    yxxx => B4[1, 0, 0, 0], yxxy => B4[1, 0, 0, 1], yxyx => B4[1, 0, 1, 0], yxyy => B4[1, 0, 1, 1],
+    yyxx => B4[1, 1, 0, 0], yyxy => B4[1, 1, 0, 1], yyyx => B4[1, 1, 1, 0], yyyy => B4[1, 1, 1, 1],
+});
+
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+impl_swizzles!(B3 {
+    xx => B2[0, 0], xy => B2[0, 1], xz => B2[0, 2], yx => B2[1, 0], yy => B2[1, 1], yz => B2[1, 2],
+    zx => B2[2, 0], zy => B2[2, 1], zz => B2[2, 2],
+
+    xxx => B3[0, 0, 0], xxy => B3[0, 0, 1], xxz => B3[0, 0, 2], xyx => B3[0, 1, 0],
+    xyy => B3[0, 1, 1], xyz => B3[0, 1, 2], xzx => B3[0, 2, 0], xzy => B3[0, 2, 1],
+    xzz => B3[0, 2, 2], yxx => B3[1, 0, 0], yxy => B3[1, 0, 1], yxz => B3[1, 0, 2],
+    yyx => B3[1, 1, 0], yyy => B3[1, 1, 1], yyz => B3[1, 1, 2], yzx => B3[1, 2, 0],
+    yzy => B3[1, 2, 1], yzz => B3[1, 2, 2], zxx => B3[2, 0, 0], zxy => B3[2, 0, 1],
+    zxz => B3[2, 0, 2], zyx => B3[2, 1, 0], zyy => B3[2, 1, 1], zyz => B3[2, 1, 2],
+    zzx => B3[2, 2, 0], zzy => B3[2, 2, 1], zzz => B3[2, 2, 2],
+
+    xxxx => B4[0, 0, 0, 0], xxxy => B4[0, 0, 0, 1], xxxz => B4[0, 0, 0, 2], xxyx => B4[0, 0, 1, 0],
+    xxyy => B4[0, 0, 1, 1], xxyz => B4[0, 0, 1, 2], xxzx => B4[0, 0, 2, 0], xxzy => B4[0, 0, 2, 1],
+    xxzz => B4[0, 0, 2, 2], xyxx => B4[0, 1, 0, 0], xyxy => B4[0, 1, 0, 1], xyxz => B4[0, 1, 0, 2],
+    xyyx => B4[0, 1, 1, 0], xyyy => B4[0, 1, 1, 1], xyyz => B4[0, 1, 1, 2], xyzx => B4[0, 1, 2, 0],
+    xyzy => B4[0, 1, 2, 1], xyzz => B4[0, 1, 2, 2], xzxx => B4[0, 2, 0, 0], xzxy => B4[0, 2, 0, 1],
+    xzxz => B4[0, 2, 0, 2], xzyx => B4[0, 2, 1, 0], xzyy => B4[0, 2, 1, 1], xzyz => B4[0, 2, 1, 2],
+    xzzx => B4[0, 2, 2, 0], xzzy => B4[0, 2, 2, 1], xzzz => B4[0, 2, 2, 2], yxxx => B4[1, 0, 0, 0],
+    yxxy => B4[1, 0, 0, 1], yxxz => B4[1, 0, 0, 2], yxyx => B4[1, 0, 1, 0], yxyy => B4[1, 0, 1, 1],
+    yxyz => B4[1, 0, 1, 2], yxzx => B4[1, 0, 2, 0], yxzy => B4[1, 0, 2, 1], yxzz => B4[1, 0, 2, 2],
+    yyxx => B4[1, 1, 0, 0], yyxy => B4[1, 1, 0, 1], yyxz => B4[1, 1, 0, 2], yyyx => B4[1, 1, 1, 0],
+    yyyy => B4[1, 1, 1, 1], yyyz => B4[1, 1, 1, 2], yyzx => B4[1, 1, 2, 0], yyzy => B4[1, 1, 2, 1],
+    yyzz => B4[1, 1, 2, 2], yzxx => B4[1, 2, 0, 0], yzxy => B4[1, 2, 0, 1], yzxz => B4[1, 2, 0, 2],
+    yzyx => B4[1, 2, 1, 0], yzyy => B4[1, 2, 1, 1], yzyz => B4[1, 2, 1, 2], yzzx => B4[1, 2, 2, 0],
+    yzzy => B4[1, 2, 2, 1], yzzz => B4[1, 2, 2, 2], zxxx => B4[2, 0, 0, 0], zxxy => B4[2, 0, 0, 1],
+    zxxz => B4[2, 0, 0, 2], zxyx => B4[2, 0, 1, 0], zxyy => B4[2, 0, 1, 1], zxyz => B4[2, 0, 1, 2],
+    zxzx => B4[2, 0, 2, 0], zxzy => B4[2, 0, 2, 1], zxzz => B4[2, 0, 2, 2], zyxx => B4[2, 1, 0, 0],
+    zyxy => B4[2, 1, 0, 1], zyxz => B4[2, 1, 0, 2], zyyx => B4[2, 1, 1, 0], zyyy => B4[2, 1, 1, 1],
+    zyyz => B4[2, 1, 1, 2], zyzx => B4[2, 1, 2, 0], zyzy => B4[2, 1, 2, 1], zyzz => B4[2, 1, 2, 2],
+    zzxx => B4[2, 2, 0, 0], zzxy => B4[2, 2, 0, 1], zzxz => B4[2, 2, 0, 2], zzyx => B4[2, 2, 1, 0],
+    zzyy => B4[2, 2, 1, 1], zzyz => B4[2, 2, 1, 2], zzzx => B4[2, 2, 2, 0], zzzy => B4[2, 2, 2, 1],
+    zzzz => B4[2, 2, 2, 2],
+});
+
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+impl_swizzles!(B4 {
+    xx => B2[0, 0], xy => B2[0, 1], xz => B2[0, 2], xw => B2[0, 3], yx => B2[1, 0], yy => B2[1, 1],
+    yz => B2[1, 2], yw => B2[1, 3], zx => B2[2, 0], zy => B2[2, 1], zz => B2[2, 2], zw => B2[2, 3],
+    wx => B2[3, 0], wy => B2[3, 1], wz => B2[3, 2], ww => B2[3, 3],
+
+    xxx => B3[0, 0, 0], xxy => B3[0, 0, 1], xxz => B3[0, 0, 2], xxw => B3[0, 0, 3],
+    xyx => B3[0, 1, 0], xyy => B3[0, 1, 1], xyz => B3[0, 1, 2], xyw => B3[0, 1, 3],
+    xzx => B3[0, 2, 0], xzy => B3[0, 2, 1], xzz => B3[0, 2, 2], xzw => B3[0, 2, 3],
+    xwx => B3[0, 3, 0], xwy => B3[0, 3, 1], xwz => B3[0, 3, 2], xww => B3[0, 3, 3],
+    yxx => B3[1, 0, 0], yxy => B3[1, 0, 1], yxz => B3[1, 0, 2], yxw => B3[1, 0, 3],
+    yyx => B3[1, 1, 0], yyy => B3[1, 1, 1], yyz => B3[1, 1, 2], yyw => B3[1, 1, 3],
+    yzx => B3[1, 2, 0], yzy => B3[1, 2, 1], yzz => B3[1, 2, 2], yzw => B3[1, 2, 3],
+    ywx => B3[1, 3, 0], ywy => B3[1, 3, 1], ywz => B3[1, 3, 2], yww => B3[1, 3, 3],
+    zxx => B3[2, 0, 0], zxy => B3[2, 0, 1], zxz => B3[2, 0, 2], zxw => B3[2, 0, 3],
+    zyx => B3[2, 1, 0], zyy => B3[2, 1, 1], zyz => B3[2, 1, 2], zyw => B3[2, 1, 3],
+    zzx => B3[2, 2, 0], zzy => B3[2, 2, 1], zzz => B3[2, 2, 2], zzw => B3[2, 2, 3],
+    zwx => B3[2, 3, 0], zwy => B3[2, 3, 1], zwz => B3[2, 3, 2], zww => B3[2, 3, 3],
+    wxx => B3[3, 0, 0], wxy => B3[3, 0, 1], wxz => B3[3, 0, 2], wxw => B3[3, 0, 3],
+    wyx => B3[3, 1, 0], wyy => B3[3, 1, 1], wyz => B3[3, 1, 2], wyw => B3[3, 1, 3],
+    wzx => B3[3, 2, 0], wzy => B3[3, 2, 1], wzz => B3[3, 2, 2], wzw => B3[3, 2, 3],
+    wwx => B3[3, 3, 0], wwy => B3[3, 3, 1], wwz => B3[3, 3, 2], www => B3[3, 3, 3],
+
+    xxxx => B4[0, 0, 0, 0], xxxy => B4[0, 0, 0, 1], xxxz => B4[0, 0, 0, 2], xxxw => B4[0, 0, 0, 3],
+    xxyx => B4[0, 0, 1, 0], xxyy => B4[0, 0, 1, 1], xxyz => B4[0, 0, 1, 2], xxyw => B4[0, 0, 1, 3],
+    xxzx => B4[0, 0, 2, 0], xxzy => B4[0, 0, 2, 1], xxzz => B4[0, 0, 2, 2], xxzw => B4[0, 0, 2, 3],
+    xxwx => B4[0, 0, 3, 0], xxwy => B4[0, 0, 3, 1], xxwz => B4[0, 0, 3, 2], xxww => B4[0, 0, 3, 3],
+    xyxx => B4[0, 1, 0, 0], xyxy => B4[0, 1, 0, 1], xyxz => B4[0, 1, 0, 2], xyxw => B4[0, 1, 0, 3],
+    xyyx => B4[0, 1, 1, 0], xyyy => B4[0, 1, 1, 1], xyyz => B4[0, 1, 1, 2], xyyw => B4[0, 1, 1, 3],
+    xyzx => B4[0, 1, 2, 0], xyzy => B4[0, 1, 2, 1], xyzz => B4[0, 1, 2, 2], xyzw => B4[0, 1, 2, 3],
+    xywx => B4[0, 1, 3, 0], xywy => B4[0, 1, 3, 1], xywz => B4[0, 1, 3, 2], xyww => B4[0, 1, 3, 3],
+    xzxx => B4[0, 2, 0, 0], xzxy => B4[0, 2, 0, 1], xzxz => B4[0, 2, 0, 2], xzxw => B4[0, 2, 0, 3],
+    xzyx => B4[0, 2, 1, 0], xzyy => B4[0, 2, 1, 1], xzyz => B4[0, 2, 1, 2], xzyw => B4[0, 2, 1, 3],
+    xzzx => B4[0, 2, 2, 0], xzzy => B4[0, 2, 2, 1], xzzz => B4[0, 2, 2, 2], xzzw => B4[0, 2, 2, 3],
+    xzwx => B4[0, 2, 3, 0], xzwy => B4[0, 2, 3, 1], xzwz => B4[0, 2, 3, 2], xzww => B4[0, 2, 3, 3],
+    xwxx => B4[0, 3, 0, 0], xwxy => B4[0, 3, 0, 1], xwxz => B4[0, 3, 0, 2], xwxw => B4[0, 3, 0, 3],
+    xwyx => B4[0, 3, 1, 0], xwyy => B4[0, 3, 1, 1], xwyz => B4[0, 3, 1, 2], xwyw => B4[0, 3, 1, 3],
+    xwzx => B4[0, 3, 2, 0], xwzy => B4[0, 3, 2, 1], xwzz => B4[0, 3, 2, 2], xwzw => B4[0, 3, 2, 3],
+    xwwx => B4[0, 3, 3, 0], xwwy => B4[0, 3, 3, 1], xwwz => B4[0, 3, 3, 2], xwww => B4[0, 3, 3, 3],
+    yxxx => B4[1, 0, 0, 0], yxxy => B4[1, 0, 0, 1], yxxz => B4[1, 0, 0, 2], yxxw => B4[1, 0, 0, 3],
+    yxyx => B4[1, 0, 1, 0], yxyy => B4[1, 0, 1, 1], yxyz => B4[1, 0, 1, 2], yxyw => B4[1, 0, 1, 3],
+    yxzx => B4[1, 0, 2, 0], yxzy => B4[1, 0, 2, 1], yxzz => B4[1, 0, 2, 2], yxzw => B4[1, 0, 2, 3],
+    yxwx => B4[1, 0, 3, 0], yxwy => B4[1, 0, 3, 1], yxwz => B4[1, 0, 3, 2], yxww => B4[1, 0, 3, 3],
+    yyxx => B4[1, 1, 0, 0], yyxy => B4[1, 1, 0, 1], yyxz => B4[1, 1, 0, 2], yyxw => B4[1, 1, 0, 3],
+    yyyx => B4[1, 1, 1, 0], yyyy => B4[1, 1, 1, 1], yyyz => B4[1, 1, 1, 2], yyyw => B4[1, 1, 1, 3],
+    yyzx => B4[1, 1, 2, 0], yyzy => B4[1, 1, 2, 1], yyzz => B4[1, 1, 2, 2], yyzw => B4[1, 1, 2, 3],
+    yywx => B4[1, 1, 3, 0], yywy => B4[1, 1, 3, 1], yywz => B4[1, 1, 3, 2], yyww => B4[1, 1, 3, 3],
+    yzxx => B4[1, 2, 0, 0], yzxy => B4[1, 2, 0, 1], yzxz => B4[1, 2, 0, 2], yzxw => B4[1, 2, 0, 3],
+    yzyx => B4[1, 2, 1, 0], yzyy => B4[1, 2, 1, 1], yzyz => B4[1, 2, 1, 2], yzyw => B4[1, 2, 1, 3],
+    yzzx => B4[1, 2, 2, 0], yzzy => B4[1, 2, 2, 1], yzzz => B4[1, 2, 2, 2], yzzw => B4[1, 2, 2, 3],
+    yzwx => B4[1, 2, 3, 0], yzwy => B4[1, 2, 3, 1], yzwz => B4[1, 2, 3, 2], yzww => B4[1, 2, 3, 3],
+    ywxx => B4[1, 3, 0, 0], ywxy => B4[1, 3, 0, 1], ywxz => B4[1, 3, 0, 2], ywxw => B4[1, 3, 0, 3],
+    ywyx => B4[1, 3, 1, 0], ywyy => B4[1, 3, 1, 1], ywyz => B4[1, 3, 1, 2], ywyw => B4[1, 3, 1, 3],
+    ywzx => B4[1, 3, 2, 0], ywzy => B4[1, 3, 2, 1], ywzz => B4[1, 3, 2, 2], ywzw => B4[1, 3, 2, 3],
+    ywwx => B4[1, 3, 3, 0], ywwy => B4[1, 3, 3, 1], ywwz => B4[1, 3, 3, 2], ywww => B4[1, 3, 3, 3],
+    zxxx => B4[2, 0, 0, 0], zxxy => B4[2, 0, 0, 1], zxxz => B4[2, 0, 0, 2], zxxw => B4[2, 0, 0, 3],
+    zxyx => B4[2, 0, 1, 0], zxyy => B4[2, 0, 1, 1], zxyz => B4[2, 0, 1, 2], zxyw => B4[2, 0, 1, 3],
+    zxzx => B4[2, 0, 2, 0], zxzy => B4[2, 0, 2, 1], zxzz => B4[2, 0, 2, 2], zxzw => B4[2, 0, 2, 3],
+    zxwx => B4[2, 0, 3, 0], zxwy => B4[2, 0, 3, 1], zxwz => B4[2, 0, 3, 2], zxww => B4[2, 0, 3, 3],
+    zyxx => B4[2, 1, 0, 0], zyxy => B4[2, 1, 0, 1], zyxz => B4[2, 1, 0, 2], zyxw => B4[2, 1, 0, 3],
+    zyyx => B4[2, 1, 1, 0], zyyy => B4[2, 1, 1, 1], zyyz => B4[2, 1, 1, 2], zyyw => B4[2, 1, 1, 3],
+    zyzx => B4[2, 1, 2, 0], zyzy => B4[2, 1, 2, 1], zyzz => B4[2, 1, 2, 2], zyzw => B4[2, 1, 2, 3],
+    zywx => B4[2, 1, 3, 0], zywy => B4[2, 1, 3, 1], zywz => B4[2, 1, 3, 2], zyww => B4[2, 1, 3, 3],
+    zzxx => B4[2, 2, 0, 0], zzxy => B4[2, 2, 0, 1], zzxz => B4[2, 2, 0, 2], zzxw => B4[2, 2, 0, 3],
+    zzyx => B4[2, 2, 1, 0], zzyy => B4[2, 2, 1, 1], zzyz => B4[2, 2, 1, 2], zzyw => B4[2, 2, 1, 3],
+    zzzx => B4[2, 2, 2, 0], zzzy => B4[2, 2, 2, 1], zzzz => B4[2, 2, 2, 2], zzzw => B4[2, 2, 2, 3],
+    zzwx => B4[2, 2, 3, 0], zzwy => B4[2, 2, 3, 1], zzwz => B4[2, 2, 3, 2], zzww => B4[2, 2, 3, 3],
+    zwxx => B4[2, 3, 0, 0], zwxy => B4[2, 3, 0, 1], zwxz => B4[2, 3, 0, 2], zwxw => B4[2, 3, 0, 3],
+    zwyx => B4[2, 3, 1, 0], zwyy => B4[2, 3, 1, 1], zwyz => B4[2, 3, 1, 2], zwyw => B4[2, 3, 1, 3],
+    zwzx => B4[2, 3, 2, 0], zwzy => B4[2, 3, 2, 1], zwzz => B4[2, 3, 2, 2], zwzw => B4[2, 3, 2, 3],
+    zwwx => B4[2, 3, 3, 0], zwwy => B4[2, 3, 3, 1], zwwz => B4[2, 3, 3, 2], zwww => B4[2, 3, 3, 3],
+    wxxx => B4[3, 0, 0, 0], wxxy => B4[3, 0, 0, 1], wxxz => B4[3, 0, 0, 2], wxxw => B4[3, 0, 0, 3],
+    wxyx => B4[3, 0, 1, 0], wxyy => B4[3, 0, 1, 1], wxyz => B4[3, 0, 1, 2], wxyw => B4[3, 0, 1, 3],
+    wxzx => B4[3, 0, 2, 0], wxzy => B4[3, 0, 2, 1], wxzz => B4[3, 0, 2, 2], wxzw => B4[3, 0, 2, 3],
+    wxwx => B4[3, 0, 3, 0], wxwy => B4[3, 0, 3, 1], wxwz => B4[3, 0, 3, 2], wxww => B4[3, 0, 3, 3],
+    wyxx => B4[3, 1, 0, 0], wyxy => B4[3, 1, 0, 1], wyxz => B4[3, 1, 0, 2], wyxw => B4[3, 1, 0, 3],
+    wyyx => B4[3, 1, 1, 0], wyyy => B4[3, 1, 1, 1], wyyz => B4[3, 1, 1, 2], wyyw => B4[3, 1, 1, 3],
+    wyzx => B4[3, 1, 2, 0], wyzy => B4[3, 1, 2, 1], wyzz => B4[3, 1, 2, 2], wyzw => B4[3, 1, 2, 3],
+    wywx => B4[3, 1, 3, 0], wywy => B4[3, 1, 3, 1], wywz => B4[3, 1, 3, 2], wyww => B4[3, 1, 3, 3],
+    wzxx => B4[3, 2, 0, 0], wzxy => B4[3, 2, 0, 1], wzxz => B4[3, 2, 0, 2], wzxw => B4[3, 2, 0, 3],
+    wzyx => B4[3, 2, 1, 0], wzyy => B4[3, 2, 1, 1], wzyz => B4[3, 2, 1, 2], wzyw => B4[3, 2, 1, 3],
+    wzzx => B4[3, 2, 2, 0], wzzy => B4[3, 2, 2, 1], wzzz => B4[3, 2, 2, 2], wzzw => B4[3, 2, 2, 3],
+    wzwx => B4[3, 2, 3, 0], wzwy => B4[3, 2, 3, 1], wzwz => B4[3, 2, 3, 2], wzww => B4[3, 2, 3, 3],
+    wwxx => B4[3, 3, 0, 0], wwxy => B4[3, 3, 0, 1], wwxz => B4[3, 3, 0, 2], wwxw => B4[3, 3, 0, 3],
+    wwyx => B4[3, 3, 1, 0], wwyy => B4[3, 3, 1, 1], wwyz => B4[3, 3, 1, 2], wwyw => B4[3, 3, 1, 3],
+    wwzx => B4[3, 3, 2, 0], wwzy => B4[3, 3, 2, 1], wwzz => B4[3, 3, 2, 2], wwzw => B4[3, 3, 2, 3],
+    wwwx => B4[3, 3, 3, 0], wwwy => B4[3, 3, 3, 1], wwwz => B4[3, 3, 3, 2], wwww => B4[3, 3, 3, 3],
+});
+
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn swizzles() {
+        let v = Vect4!(1, 2, 3, 4);
+
+        assert_eq!(v.xy(), Vect2!(1, 2));
+        assert_eq!(v.wzyx(), Vect4!(4, 3, 2, 1));
+        assert_eq!(v.zzw(), Vect3!(3, 3, 4));
+        assert_eq!(Vect3!(1, 2, 3).zyx(), Vect3!(3, 2, 1));
+        assert_eq!(Vect2!(1, 2).xxyy(), Vect4!(1, 1, 2, 2));
+    }
+}
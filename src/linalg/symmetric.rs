@@ -0,0 +1,341 @@
+use std::marker::PhantomData;
+use std::ops::{Mul, Index, IndexMut};
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use linalg::vector::*;
+use typehack::data::*;
+use typehack::dim::*;
+
+
+/// A symmetric square matrix in packed storage: only the upper triangle (equivalently, the lower
+/// triangle transposed) is stored, taking `n * (n + 1) / 2` elements. Both `[[i, j]]` and
+/// `[[j, i]]` refer to the same stored element, so mutation can never break symmetry.
+///
+/// The `Layout` parameter selects the packing order. `Column` packs the upper triangle column by
+/// column, and `Row` packs it row by row, matching the conventions of LAPACK's `U` and `L` packed
+/// formats respectively.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SymmetricMat<T: Scalar, N: Dim, L: Layout> {
+    side: N,
+    elems: Data<T, Dyn>,
+    phantom: PhantomData<L>,
+}
+
+
+#[inline]
+fn packed_len(n: usize) -> usize {
+    n * (n + 1) / 2
+}
+
+
+trait PackedOffset {
+    fn offset(&self, usize, usize) -> usize;
+}
+
+
+/// The packing order follows the layout's strides: a layout which steps through a column
+/// contiguously packs the upper triangle column by column, and any other packs it row by row.
+impl<T: Scalar, N: Dim, L: Layout> PackedOffset for SymmetricMat<T, N, L> {
+    fn offset(&self, row: usize, col: usize) -> usize {
+        let (i, j) = if row <= col { (row, col) } else { (col, row) };
+        let n = self.side.reify();
+        let strides = L::strides(n, n);
+
+        if strides[0] < strides[1] {
+            packed_len(j) + i
+        } else {
+            i * n - packed_len(i) + i + (j - i)
+        }
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> SymmetricMat<T, N, L> {
+    /// Builds a symmetric matrix by evaluating `f(i, j)` for every `i <= j`.
+    pub fn from_fn<F: Fn(usize, usize) -> T>(side: N, f: F) -> Self {
+        let n = side.reify();
+
+        let mut out = SymmetricMat {
+            side: side,
+            elems: Data::from_elem(Dyn(packed_len(n)), &T::zero()),
+            phantom: PhantomData,
+        };
+
+        for j in 0..n {
+            for i in 0..j + 1 {
+                let k = out.offset(i, j);
+                out.elems[k] = f(i, j);
+            }
+        }
+
+        out
+    }
+
+
+    pub fn from_elem(side: N, elem: &T) -> Self {
+        SymmetricMat {
+            side: side,
+            elems: Data::from_elem(Dyn(packed_len(side.reify())), elem),
+            phantom: PhantomData,
+        }
+    }
+
+
+    /// Builds a symmetric matrix from the upper triangle of `mat`, ignoring its lower triangle.
+    pub fn from_upper<L2: Layout>(mat: &DenseMat<T, N, N, L2>) -> Self {
+        SymmetricMat::from_fn(mat.side(), |i, j| mat[[i, j]].clone())
+    }
+
+
+    /// Builds a symmetric matrix from the lower triangle of `mat`, ignoring its upper triangle.
+    pub fn from_lower<L2: Layout>(mat: &DenseMat<T, N, N, L2>) -> Self {
+        SymmetricMat::from_fn(mat.side(), |i, j| mat[[j, i]].clone())
+    }
+
+
+    /// Packs `mat`, returning `None` if it is not symmetric.
+    pub fn try_from_mat<L2: Layout>(mat: &DenseMat<T, N, N, L2>) -> Option<Self> {
+        let n = mat.side().reify();
+
+        for i in 0..n {
+            for j in i + 1..n {
+                if !(mat[[i, j]].clone() - mat[[j, i]].clone()).eq_zero() {
+                    return None;
+                }
+            }
+        }
+
+        Some(SymmetricMat::from_upper(mat))
+    }
+
+
+    pub fn to_dense<L2: Layout>(&self) -> DenseMat<T, N, N, L2> {
+        let mut out = DenseMat::from_elem(self.side, self.side, T::zero());
+
+        for i in 0..self.side.reify() {
+            for j in 0..self.side.reify() {
+                out[[i, j]] = self[[i, j]].clone();
+            }
+        }
+
+        out
+    }
+
+
+    /// The packed elements, in the order given by the layout parameter.
+    pub fn as_packed_slice(&self) -> &[T] {
+        &self.elems
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> Matrix for SymmetricMat<T, N, L> {
+    type Rows = N;
+    type Cols = N;
+
+    type Scalar = T;
+
+
+    fn rows(&self) -> N {
+        self.side
+    }
+
+    fn cols(&self) -> N {
+        self.side
+    }
+
+
+    fn get(&self, row: usize, col: usize) -> &T {
+        assert!(row < self.side.reify() && col < self.side.reify(),
+                "Matrix index out of bounds: [[{}, {}]] is out of bounds of [[{}, {}]]!",
+                row,
+                col,
+                self.side.reify(),
+                self.side.reify());
+
+        &self.elems[self.offset(row, col)]
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        assert!(row < self.side.reify() && col < self.side.reify(),
+                "Matrix index out of bounds: [[{}, {}]] is out of bounds of [[{}, {}]]!",
+                row,
+                col,
+                self.side.reify(),
+                self.side.reify());
+
+        let k = self.offset(row, col);
+        &mut self.elems[k]
+    }
+
+    // Swapping two packed elements swaps both of their mirrored pairs, so symmetry is preserved.
+    fn swap(&mut self, a: [usize; 2], b: [usize; 2]) {
+        let (a, b) = (self.offset(a[0], a[1]), self.offset(b[0], b[1]));
+        self.elems.swap(a, b);
+    }
+
+
+    fn row_switch_mut(&mut self, i: usize, j: usize) {
+        assert!(i == j,
+                "Switching rows {} and {} would break the symmetric invariant!",
+                i,
+                j);
+    }
+
+    fn row_mul_mut(&mut self, i: usize, c: &T) {
+        assert!(c.eq_one(),
+                "Scaling row {} would break the symmetric invariant!",
+                i);
+    }
+
+    fn row_add_mut(&mut self, i: usize, j: usize, c: &T) {
+        assert!(c.eq_zero(),
+                "Adding a multiple of row {} to row {} would break the symmetric invariant!",
+                j,
+                i);
+    }
+
+
+    fn col_switch_mut(&mut self, i: usize, j: usize) {
+        assert!(i == j,
+                "Switching columns {} and {} would break the symmetric invariant!",
+                i,
+                j);
+    }
+
+    fn col_mul_mut(&mut self, i: usize, c: &T) {
+        assert!(c.eq_one(),
+                "Scaling column {} would break the symmetric invariant!",
+                i);
+    }
+
+    fn col_add_mut(&mut self, i: usize, j: usize, c: &T) {
+        assert!(c.eq_zero(),
+                "Adding a multiple of column {} to column {} would break the symmetric \
+                 invariant!",
+                j,
+                i);
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> Square for SymmetricMat<T, N, L> {
+    type Side = N;
+
+    fn side(&self) -> N {
+        self.side
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> MatrixIdentity for SymmetricMat<T, N, L> {
+    fn eye(n: N) -> Self {
+        SymmetricMat::from_fn(n, |i, j| if i == j { T::one() } else { T::zero() })
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> MatrixTranspose<SymmetricMat<T, N, L>> for SymmetricMat<T, N, L> {
+    fn transpose(self) -> Self {
+        self
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> Mul<DenseVec<T, N>> for SymmetricMat<T, N, L> {
+    type Output = DenseVec<T, N>;
+
+    fn mul(self, rhs: DenseVec<T, N>) -> DenseVec<T, N> {
+        &self * &rhs
+    }
+}
+
+
+impl<'a, T: Scalar, N: Dim, L: Layout> Mul<&'a DenseVec<T, N>> for &'a SymmetricMat<T, N, L> {
+    type Output = DenseVec<T, N>;
+
+    // Each stored off-diagonal element contributes to two entries of the output, so we walk the
+    // packed triangle once rather than reading every element twice.
+    fn mul(self, rhs: &'a DenseVec<T, N>) -> DenseVec<T, N> {
+        let n = self.side.reify();
+
+        assert_eq!(self.side, rhs.size());
+
+        let mut out = DenseVec::from_elem(self.side, &T::zero());
+
+        for j in 0..n {
+            out[j] += self[[j, j]].clone() * rhs[j].clone();
+
+            for i in 0..j {
+                let a = self[[i, j]].clone();
+                out[i] += a.clone() * rhs[j].clone();
+                out[j] += a * rhs[i].clone();
+            }
+        }
+
+        out
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> Index<[usize; 2]> for SymmetricMat<T, N, L> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, idx: [usize; 2]) -> &T {
+        self.get(idx[0], idx[1])
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> IndexMut<[usize; 2]> for SymmetricMat<T, N, L> {
+    #[inline]
+    fn index_mut(&mut self, idx: [usize; 2]) -> &mut T {
+        self.get_mut(idx[0], idx[1])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typehack::binary::*;
+
+    #[test]
+    fn sym_packed_storage_row_and_column() {
+        let a = Mat![[1, 2, 3], [2, 4, 5], [3, 5, 6]];
+
+        let c = SymmetricMat::<_, _, Column>::try_from_mat(&a).unwrap();
+        let r = SymmetricMat::<_, _, Row>::try_from_mat(&a).unwrap();
+
+        assert_eq!(c.as_packed_slice(), &[1, 2, 4, 3, 5, 6]);
+        assert_eq!(r.as_packed_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(c.to_dense::<Column>(), a);
+        assert_eq!(r.to_dense::<Column>(), a);
+    }
+
+
+    #[test]
+    fn sym_index_mut_writes_both_halves() {
+        let mut s = SymmetricMat::<_, _, Column>::from_elem(B3::as_data(), &0);
+        s[[0, 2]] = 7;
+
+        assert_eq!(s[[2, 0]], 7);
+    }
+
+
+    #[test]
+    fn sym_try_from_mat_rejects_asymmetric() {
+        assert!(SymmetricMat::<_, _, Row>::try_from_mat(&Mat![[1, 2], [3, 4]]).is_none());
+    }
+
+
+    #[test]
+    fn sym_mul_vect() {
+        let a = Mat![[1, 2, 3], [2, 4, 5], [3, 5, 6]];
+        let s = SymmetricMat::<_, _, Row>::from_upper(&a);
+
+        assert_eq!(s * Vect![1, 1, 1], Vect![6, 11, 14]);
+    }
+}
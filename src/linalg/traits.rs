@@ -179,6 +179,10 @@ pub trait VectorNorm: Vector {
 
 pub trait Layout {
     type Alternate: Layout;
+
+    /// Returns the distances in memory between `[[i, j]]` and `[[i + 1, j]]` and between
+    /// `[[i, j]]` and `[[i, j + 1]]`, respectively, for a densely packed matrix of this layout.
+    fn strides(rows: usize, cols: usize) -> [usize; 2];
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -189,10 +193,18 @@ pub struct Column;
 
 impl Layout for Row {
     type Alternate = Column;
+
+    fn strides(_rows: usize, cols: usize) -> [usize; 2] {
+        [cols, 1]
+    }
 }
 
 impl Layout for Column {
     type Alternate = Row;
+
+    fn strides(rows: usize, _cols: usize) -> [usize; 2] {
+        [1, rows]
+    }
 }
 
 
@@ -235,6 +247,11 @@ pub trait MatrixTranspose<T: Matrix>: Matrix<Rows = T::Cols, Cols = T::Rows> {
 }
 
 
+pub trait MatrixTransposeInPlace: Matrix {
+    fn transpose_in_place(&mut self);
+}
+
+
 pub trait MatrixNeg: Matrix {
     type Output: Matrix<Rows = Self::Rows, Cols = Self::Cols>;
 
@@ -268,3 +285,18 @@ pub trait MatrixMul<RHS: Matrix<Rows = <Self as Matrix>::Cols>,
 pub trait MatrixIdentity: Square {
     fn eye(Self::Side) -> Self;
 }
+
+
+pub trait MatrixNorm: Matrix {
+    /// The square root of the sum of the squares of every element.
+    fn frobenius_norm(&self) -> Self::Scalar;
+
+    /// The operator 1-norm: the maximum absolute column sum.
+    fn one_norm(&self) -> Self::Scalar;
+
+    /// The operator ∞-norm: the maximum absolute row sum.
+    fn inf_norm(&self) -> Self::Scalar;
+
+    /// The operator 2-norm, or spectral norm: the largest singular value.
+    fn spectral_norm(&self) -> Self::Scalar;
+}
@@ -0,0 +1,375 @@
+use std::ops::{Mul, Index, IndexMut};
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use linalg::vector::*;
+use typehack::dim::*;
+
+
+macro_rules! triangular_impl {
+    ($name:ident, $alternate:ident, $kind:tt, $in_triangle:ident) => {
+        impl<T: Scalar, N: Dim, L: Layout> $name<T, N, L> {
+            /// Builds a triangular matrix from the corresponding triangle of `mat`, zeroing every
+            /// element outside of it.
+            pub fn from_triangle(mut mat: DenseMat<T, N, N, L>) -> Self {
+                let n = mat.side().reify();
+
+                for i in 0..n {
+                    for j in 0..n {
+                        if !$in_triangle(i, j) {
+                            mat[[i, j]] = T::zero();
+                        }
+                    }
+                }
+
+                $name { mat: mat }
+            }
+
+
+            /// Wraps `mat`, returning `None` if it has a nonzero element outside of the triangle.
+            pub fn try_from_mat(mat: DenseMat<T, N, N, L>) -> Option<Self> {
+                let n = mat.side().reify();
+
+                for i in 0..n {
+                    for j in 0..n {
+                        if !$in_triangle(i, j) && !mat[[i, j]].eq_zero() {
+                            return None;
+                        }
+                    }
+                }
+
+                Some($name { mat: mat })
+            }
+
+
+            pub fn as_mat(&self) -> &DenseMat<T, N, N, L> {
+                &self.mat
+            }
+
+
+            pub fn into_inner(self) -> DenseMat<T, N, N, L> {
+                self.mat
+            }
+
+
+            /// The determinant of a triangular matrix is the product of its diagonal.
+            pub fn determinant(&self) -> T {
+                (0..self.mat.side().reify()).map(|i| self.mat[[i, i]].clone()).product()
+            }
+
+
+            pub fn transpose(self) -> $alternate<T, N, L::Alternate>
+                where DenseMat<T, N, N, L>: MatrixTranspose<DenseMat<T, N, N, L::Alternate>>
+            {
+                $alternate { mat: self.mat.transpose() }
+            }
+        }
+
+
+        impl<T: Scalar, N: Dim, L: Layout> Matrix for $name<T, N, L> {
+            type Rows = N;
+            type Cols = N;
+
+            type Scalar = T;
+
+
+            fn rows(&self) -> N {
+                self.mat.rows()
+            }
+
+            fn cols(&self) -> N {
+                self.mat.cols()
+            }
+
+
+            fn get(&self, row: usize, col: usize) -> &T {
+                self.mat.get(row, col)
+            }
+
+            fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+                assert!($in_triangle(row, col),
+                        concat!("Cannot mutably index element [[{}, {}]] outside of the ", $kind,
+                                " triangle!"),
+                        row,
+                        col);
+
+                self.mat.get_mut(row, col)
+            }
+
+            fn swap(&mut self, a: [usize; 2], b: [usize; 2]) {
+                assert!($in_triangle(a[0], a[1]) == $in_triangle(b[0], b[1]),
+                        concat!("Swapping [[{}, {}]] and [[{}, {}]] would break the ", $kind,
+                                " triangular invariant!"),
+                        a[0],
+                        a[1],
+                        b[0],
+                        b[1]);
+
+                self.mat.swap(a, b);
+            }
+
+
+            fn row_switch_mut(&mut self, i: usize, j: usize) {
+                assert!(i == j,
+                        concat!("Switching rows {} and {} would break the ", $kind,
+                                " triangular invariant!"),
+                        i,
+                        j);
+            }
+
+            fn row_mul_mut(&mut self, i: usize, c: &T) {
+                self.mat.row_mul_mut(i, c);
+            }
+
+            fn row_add_mut(&mut self, i: usize, j: usize, c: &T) {
+                // Row `j`'s nonzero elements must all lie in columns where row `i` may be nonzero.
+                assert!(c.eq_zero() || $in_triangle(i, j),
+                        concat!("Adding a multiple of row {} to row {} would break the ", $kind,
+                                " triangular invariant!"),
+                        j,
+                        i);
+
+                self.mat.row_add_mut(i, j, c);
+            }
+
+
+            fn col_switch_mut(&mut self, i: usize, j: usize) {
+                assert!(i == j,
+                        concat!("Switching columns {} and {} would break the ", $kind,
+                                " triangular invariant!"),
+                        i,
+                        j);
+            }
+
+            fn col_mul_mut(&mut self, i: usize, c: &T) {
+                self.mat.col_mul_mut(i, c);
+            }
+
+            fn col_add_mut(&mut self, i: usize, j: usize, c: &T) {
+                // Column `j`'s nonzero elements must all lie in rows where column `i` may be
+                // nonzero.
+                assert!(c.eq_zero() || $in_triangle(j, i),
+                        concat!("Adding a multiple of column {} to column {} would break the ",
+                                $kind,
+                                " triangular invariant!"),
+                        j,
+                        i);
+
+                self.mat.col_add_mut(i, j, c);
+            }
+        }
+
+
+        impl<T: Scalar, N: Dim, L: Layout> Square for $name<T, N, L> {
+            type Side = N;
+
+            fn side(&self) -> N {
+                self.mat.side()
+            }
+        }
+
+
+        impl<T: Scalar, N: Dim, L: Layout> MatrixIdentity for $name<T, N, L> {
+            fn eye(n: N) -> Self {
+                $name { mat: DenseMat::eye(n) }
+            }
+        }
+
+
+        impl<T: Scalar, N: Dim, L: Layout> Mul<DenseVec<T, N>> for $name<T, N, L> {
+            type Output = DenseVec<T, N>;
+
+            fn mul(self, rhs: DenseVec<T, N>) -> DenseVec<T, N> {
+                &self * &rhs
+            }
+        }
+
+
+        impl<'a, T: Scalar, N: Dim, L: Layout> Mul<&'a DenseVec<T, N>> for &'a $name<T, N, L> {
+            type Output = DenseVec<T, N>;
+
+            fn mul(self, rhs: &'a DenseVec<T, N>) -> DenseVec<T, N> {
+                let n = self.mat.side();
+
+                assert_eq!(n, rhs.size());
+
+                DenseVec::from_fn(n, |i| {
+                    (0..n.reify())
+                        .filter(|&k| $in_triangle(i, k))
+                        .map(|k| self.mat[[i, k]].clone() * rhs[k].clone())
+                        .sum()
+                })
+            }
+        }
+
+
+        /// The product of two triangular matrices of the same kind is again triangular; elements
+        /// known to be zero are skipped entirely.
+        impl<T: Scalar, N: Dim, L: Layout> Mul for $name<T, N, L> {
+            type Output = $name<T, N, L>;
+
+            fn mul(self, rhs: $name<T, N, L>) -> $name<T, N, L> {
+                let n = self.mat.side();
+
+                assert_eq!(n, rhs.mat.side());
+
+                let mut out = DenseMat::from_elem(n, n, T::zero());
+
+                for i in 0..n.reify() {
+                    for j in (0..n.reify()).filter(|&j| $in_triangle(i, j)) {
+                        out[[i, j]] = (0..n.reify())
+                            .filter(|&k| $in_triangle(i, k) && $in_triangle(k, j))
+                            .map(|k| self.mat[[i, k]].clone() * rhs.mat[[k, j]].clone())
+                            .sum();
+                    }
+                }
+
+                $name { mat: out }
+            }
+        }
+
+
+        impl<T: Scalar, N: Dim, L: Layout> Index<[usize; 2]> for $name<T, N, L> {
+            type Output = T;
+
+            #[inline]
+            fn index(&self, idx: [usize; 2]) -> &T {
+                self.get(idx[0], idx[1])
+            }
+        }
+
+
+        impl<T: Scalar, N: Dim, L: Layout> IndexMut<[usize; 2]> for $name<T, N, L> {
+            #[inline]
+            fn index_mut(&mut self, idx: [usize; 2]) -> &mut T {
+                self.get_mut(idx[0], idx[1])
+            }
+        }
+    }
+}
+
+
+/// A square matrix whose elements below the main diagonal are all zero. The invariant is
+/// established by the constructors and protected by refusing mutable access to the zeroed
+/// triangle, as well as by panicking on any row or column operation which would break it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct UpperTriangular<T: Scalar, N: Dim, L: Layout> {
+    mat: DenseMat<T, N, N, L>,
+}
+
+
+/// A square matrix whose elements above the main diagonal are all zero. See `UpperTriangular`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LowerTriangular<T: Scalar, N: Dim, L: Layout> {
+    mat: DenseMat<T, N, N, L>,
+}
+
+
+#[inline]
+fn in_upper(i: usize, j: usize) -> bool {
+    i <= j
+}
+
+
+#[inline]
+fn in_lower(i: usize, j: usize) -> bool {
+    i >= j
+}
+
+
+triangular_impl!(UpperTriangular, LowerTriangular, "upper", in_upper);
+triangular_impl!(LowerTriangular, UpperTriangular, "lower", in_lower);
+
+
+impl<T: Scalar, N: Dim, L: Layout> UpperTriangular<T, N, L> {
+    /// Solves `self * x = b` for `x` via back substitution. Panics if the diagonal contains a
+    /// zero, in which case the system is singular.
+    pub fn back_substitute(&self, mut b: DenseVec<T, N>) -> DenseVec<T, N> {
+        let n = self.mat.side().reify();
+
+        assert_eq!(n, b.len());
+
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                b[i] -= self.mat[[i, j]].clone() * b[j].clone();
+            }
+
+            assert!(!self.mat[[i, i]].eq_zero(),
+                    "Cannot back substitute through a singular triangular matrix!");
+
+            b[i] /= self.mat[[i, i]].clone();
+        }
+
+        b
+    }
+}
+
+
+impl<T: Scalar, N: Dim, L: Layout> LowerTriangular<T, N, L> {
+    /// Solves `self * x = b` for `x` via forward substitution. Panics if the diagonal contains a
+    /// zero, in which case the system is singular.
+    pub fn forward_substitute(&self, mut b: DenseVec<T, N>) -> DenseVec<T, N> {
+        let n = self.mat.side().reify();
+
+        assert_eq!(n, b.len());
+
+        for i in 0..n {
+            for j in 0..i {
+                b[i] -= self.mat[[i, j]].clone() * b[j].clone();
+            }
+
+            assert!(!self.mat[[i, i]].eq_zero(),
+                    "Cannot forward substitute through a singular triangular matrix!");
+
+            b[i] /= self.mat[[i, i]].clone();
+        }
+
+        b
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upper_from_triangle_zeroes_lower() {
+        let u = UpperTriangular::from_triangle(Mat![[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+
+        assert_eq!(u.into_inner(), Mat![[1, 2, 3], [0, 5, 6], [0, 0, 9]]);
+    }
+
+
+    #[test]
+    fn lower_try_from_mat_rejects_upper_elements() {
+        assert!(LowerTriangular::try_from_mat(Mat![[1, 2], [3, 4]]).is_none());
+        assert!(LowerTriangular::try_from_mat(Mat![[1, 0], [3, 4]]).is_some());
+    }
+
+
+    #[test]
+    fn upper_back_substitute() {
+        let u = UpperTriangular::from_triangle(Mat![[2., 1., -1.], [0., 1., 2.], [0., 0., 4.]]);
+        let x = u.back_substitute(Vect![3., 7., 8.]);
+
+        assert!((x - Vect![1., 3., 2.]).norm() < 0.000001);
+    }
+
+
+    #[test]
+    fn lower_forward_substitute() {
+        let l = LowerTriangular::from_triangle(Mat![[2., 0., 0.], [1., 1., 0.], [-1., 2., 4.]]);
+        let x = l.forward_substitute(Vect![2., 4., 13.]);
+
+        assert!((x - Vect![1., 3., 2.]).norm() < 0.000001);
+    }
+
+
+    #[test]
+    #[should_panic]
+    fn upper_row_switch_panics() {
+        let mut u = UpperTriangular::from_triangle(Mat![[1, 2], [0, 3]]);
+        u.row_switch_mut(0, 1);
+    }
+}
@@ -1,5 +1,8 @@
 use std::cmp;
+use std::fmt::{self, Display};
+use std::io::{self, Read, Write};
 use std::iter::{FromIterator, Sum};
+use std::marker::PhantomData;
 use std::mem;
 use std::ops::{Add, Sub, Mul, MulAssign, Div, Neg, Deref, DerefMut, Index, IndexMut};
 use std::ptr;
@@ -7,9 +10,14 @@ use std::ptr;
 use void::Void;
 
 use iter_exact::{CollectExactExt, FromExactSizeIterator};
+
+use codec::{Decode, DecodeError, Encode};
+use geometry::frame::{Frame, Unframed};
+use linalg::Mat;
 use linalg::matrix::*;
 use linalg::traits::*;
 use num::traits::*;
+use pretty;
 use typehack::binary::*;
 use typehack::data::*;
 use typehack::dim::*;
@@ -17,43 +25,43 @@ use typehack::dim::*;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[repr(C)]
-pub struct DenseVec<T: Scalar, N: Size<T>> {
+pub struct DenseVec<T: Scalar, N: Size<T>, F: Frame = Unframed> {
     elems: Data<T, N>,
+    frame: PhantomData<F>,
 }
 
 
-impl<T: Copy + Scalar, N: Size<T>> Copy for DenseVec<T, N> where Data<T, N>: Copy {}
+impl<T: Copy + Scalar, N: Size<T>, F: Frame> Copy for DenseVec<T, N, F> where Data<T, N>: Copy {}
 
 
-impl<T: Scalar, N: Size<T>> DenseVec<T, N> {
-    pub fn as_column<L: Layout>(self) -> DenseMat<T, N, I, L>
-        where N: DimMul<I, Result = N>
-    {
-        DenseMat::from_data(self.elems.size(), I::as_data(), self.elems)
-    }
+/// Displays the vector as a bracketed, comma-separated list, e.g. `[1, 2, 3]`.
+impl<T: Scalar + Display, N: Size<T>, F: Frame> Display for DenseVec<T, N, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision();
 
-
-    pub fn as_row<L: Layout>(self) -> DenseMat<T, I, N, L>
-        where I: DimMul<N, Result = N>
-    {
-        DenseMat::from_data(I::as_data(), self.elems.size(), self.elems)
+        pretty::write_list(f, self.len(), "[", "]", |i| pretty::elem(&self[i], precision))
     }
+}
 
 
-    pub fn from_elem(size: N, elem: &T) -> Self
-        where T: Clone
-    {
-        DenseVec { elems: Data::from_elem(size, elem) }
+/// Encodes the vector as its `Data`: a length followed by the elements.
+impl<T: Scalar + Encode, N: Size<T>> Encode for DenseVec<T, N> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.elems.encode(writer)
     }
+}
 
 
-    pub fn from_fn<F: Fn(usize) -> T>(size: N, f: F) -> Self {
-        DenseVec { elems: Data::from_fn(size, f) }
+impl<T: Scalar + Decode, N: Size<T>> Decode for DenseVec<T, N> {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        Data::decode(reader).map(DenseVec::from_data)
     }
+}
 
 
-    pub fn from_data(data: Data<T, N>) -> Self {
-        DenseVec { elems: data }
+impl<T: Scalar, N: Size<T>, F: Frame> DenseVec<T, N, F> {
+    pub fn from_fn<G: Fn(usize) -> T>(size: N, f: G) -> Self {
+        DenseVec { elems: Data::from_fn(size, f), frame: PhantomData }
     }
 
 
@@ -77,13 +85,95 @@ impl<T: Scalar, N: Size<T>> DenseVec<T, N> {
     }
 
 
+    pub fn map<U: Scalar, G: FnMut(T) -> U>(self, f: G) -> DenseVec<U, N, F> {
+        DenseVec { elems: self.elems.into_iter().map(f).collect_exact(), frame: PhantomData }
+    }
+
+
+    pub fn zip_map<U: Scalar, V: Scalar, G: FnMut(T, U) -> V>(self,
+                                                             rhs: DenseVec<U, N, F>,
+                                                             mut f: G)
+                                                             -> DenseVec<V, N, F> {
+        assert_eq!(self.elems.size(), rhs.elems.size());
+
+        DenseVec {
+            elems: self.elems.into_iter().zip(rhs.elems).map(|(a, b)| f(a, b)).collect_exact(),
+            frame: PhantomData,
+        }
+    }
+
+
+    /// Appends a component, e.g. lifting `(x, y, z)` to `(x, y, z, w)`.
+    pub fn extend(self, w: T) -> DenseVec<T, N::Succ, F> {
+        DenseVec { elems: self.elems.extend(w), frame: PhantomData }
+    }
+
+
+    /// Lifts a direction to homogeneous coordinates `(v, 0)`, which translations leave unchanged.
+    /// Points are lifted with `w = 1` by `Point::to_homogeneous`.
+    pub fn to_homogeneous(self) -> DenseVec<T, N::Succ, F> {
+        self.extend(T::zero())
+    }
+
+
+    /// Drops the last component, e.g. taking `(x, y, z, w)` to `(x, y, z)`. Panics if the vector
+    /// is empty.
+    pub fn truncate(self) -> DenseVec<T, N::Pred, F> {
+        assert!(self.len() > 0, "Cannot truncate an empty vector!");
+
+        let last = self.len() - 1;
+        DenseVec { elems: self.elems.contract(last), frame: PhantomData }
+    }
+
+
+    /// Reinterprets the vector as belonging to another frame. This is an escape hatch around the
+    /// frame checks; frames are normally changed by applying a `Transform`.
+    pub fn in_frame<G: Frame>(self) -> DenseVec<T, N, G> {
+        DenseVec {
+            elems: self.elems,
+            frame: PhantomData,
+        }
+    }
+}
+
+
+impl<T: Scalar, N: Size<T>> DenseVec<T, N> {
+    pub fn as_column<L: Layout>(self) -> DenseMat<T, N, I, L>
+        where N: DimMul<I, Result = N>
+    {
+        DenseMat::from_data(self.elems.size(), I::as_data(), self.elems)
+    }
+
+
+    pub fn as_row<L: Layout>(self) -> DenseMat<T, I, N, L>
+        where I: DimMul<N, Result = N>
+    {
+        DenseMat::from_data(I::as_data(), self.elems.size(), self.elems)
+    }
+
+
+    pub fn from_elem(size: N, elem: &T) -> Self
+        where T: Clone
+    {
+        DenseVec { elems: Data::from_elem(size, elem), frame: PhantomData }
+    }
+
+
+    pub fn from_data(data: Data<T, N>) -> Self {
+        DenseVec { elems: data, frame: PhantomData }
+    }
+
+
     pub fn augment<M: Size<T>, P: Size<T>>(self, rhs: DenseVec<T, M>) -> DenseVec<T, P>
         where N: DimAdd<M, Result = P>
     {
         let mut result;
 
         unsafe {
-            result = DenseVec { elems: Data::uninitialized(self.size().add(rhs.size())) };
+            result = DenseVec {
+                elems: Data::uninitialized(self.size().add(rhs.size())),
+                frame: PhantomData,
+            };
             let slen = self.len();
 
             for (i, elem) in self.elems.into_iter().enumerate() {
@@ -99,11 +189,29 @@ impl<T: Scalar, N: Size<T>> DenseVec<T, N> {
     }
 
 
+    /// The outer product `self * rhs^T`.
+    pub fn outer<M: Dim>(&self, rhs: &DenseVec<T, M>) -> Mat<T, N, M> {
+        let mut out;
+
+        unsafe {
+            out = Mat::uninitialized(self.size(), rhs.size());
+
+            for j in 0..rhs.len() {
+                for i in 0..self.len() {
+                    ptr::write(&mut out[[i, j]], self[i].clone() * rhs[j].clone());
+                }
+            }
+        }
+
+        out
+    }
+
+
     pub fn zero_extend<M: Size<T>>(self, size: M) -> DenseVec<T, M> {
         let mut result;
 
         unsafe {
-            result = DenseVec { elems: Data::uninitialized(size) };
+            result = DenseVec { elems: Data::uninitialized(size), frame: PhantomData };
             let slen = self.len();
 
             assert!(slen <= size.reify());
@@ -119,24 +227,28 @@ impl<T: Scalar, N: Size<T>> DenseVec<T, N> {
 
         result
     }
+
 }
 
 
 impl<T: Scalar, N: Size<T>> From<Data<T, N>> for DenseVec<T, N> {
     fn from(data: Data<T, N>) -> Self {
-        DenseVec { elems: data }
+        DenseVec { elems: data, frame: PhantomData }
     }
 }
 
 
 impl<'a, T: Clone + Scalar, N: Size<T>> From<&'a [T]> for DenseVec<T, N> {
     fn from(slice: &[T]) -> Self {
-        DenseVec { elems: Data::from_slice(N::from_usize(slice.len()), slice) }
+        DenseVec {
+            elems: Data::from_slice(N::from_usize(slice.len()), slice),
+            frame: PhantomData,
+        }
     }
 }
 
 
-impl<T: Scalar> Deref for DenseVec<T, B1> {
+impl<T: Scalar, F: Frame> Deref for DenseVec<T, B1, F> {
     type Target = T;
 
     fn deref<'a>(&'a self) -> &'a T {
@@ -145,7 +257,7 @@ impl<T: Scalar> Deref for DenseVec<T, B1> {
 }
 
 
-impl<T: Scalar> DerefMut for DenseVec<T, B1> {
+impl<T: Scalar, F: Frame> DerefMut for DenseVec<T, B1, F> {
     fn deref_mut<'a>(&'a mut self) -> &'a mut T {
         &mut self.elems[0]
     }
@@ -160,18 +272,18 @@ pub struct Vec2View<T> {
 }
 
 
-impl<T: Scalar> Deref for DenseVec<T, B2> {
+impl<T: Scalar, F: Frame> Deref for DenseVec<T, B2, F> {
     type Target = Vec2View<T>;
 
     fn deref<'a>(&'a self) -> &'a Vec2View<T> {
-        unsafe { mem::transmute::<&'a DenseVec<T, B2>, &'a Vec2View<T>>(self) }
+        unsafe { mem::transmute::<&'a DenseVec<T, B2, F>, &'a Vec2View<T>>(self) }
     }
 }
 
 
-impl<T: Scalar> DerefMut for DenseVec<T, B2> {
+impl<T: Scalar, F: Frame> DerefMut for DenseVec<T, B2, F> {
     fn deref_mut<'a>(&'a mut self) -> &'a mut Vec2View<T> {
-        unsafe { mem::transmute::<&'a mut DenseVec<T, B2>, &'a mut Vec2View<T>>(self) }
+        unsafe { mem::transmute::<&'a mut DenseVec<T, B2, F>, &'a mut Vec2View<T>>(self) }
     }
 }
 
@@ -185,18 +297,18 @@ pub struct Vec3View<T> {
 }
 
 
-impl<T: Scalar> Deref for DenseVec<T, B3> {
+impl<T: Scalar, F: Frame> Deref for DenseVec<T, B3, F> {
     type Target = Vec3View<T>;
 
     fn deref<'a>(&'a self) -> &'a Vec3View<T> {
-        unsafe { mem::transmute::<&'a DenseVec<T, B3>, &'a Vec3View<T>>(self) }
+        unsafe { mem::transmute::<&'a DenseVec<T, B3, F>, &'a Vec3View<T>>(self) }
     }
 }
 
 
-impl<T: Scalar> DerefMut for DenseVec<T, B3> {
+impl<T: Scalar, F: Frame> DerefMut for DenseVec<T, B3, F> {
     fn deref_mut<'a>(&'a mut self) -> &'a mut Vec3View<T> {
-        unsafe { mem::transmute::<&'a mut DenseVec<T, B3>, &'a mut Vec3View<T>>(self) }
+        unsafe { mem::transmute::<&'a mut DenseVec<T, B3, F>, &'a mut Vec3View<T>>(self) }
     }
 }
 
@@ -211,23 +323,23 @@ pub struct Vec4View<T> {
 }
 
 
-impl<T: Scalar> Deref for DenseVec<T, B4> {
+impl<T: Scalar, F: Frame> Deref for DenseVec<T, B4, F> {
     type Target = Vec4View<T>;
 
     fn deref<'a>(&'a self) -> &'a Vec4View<T> {
-        unsafe { mem::transmute::<&'a DenseVec<T, B4>, &'a Vec4View<T>>(self) }
+        unsafe { mem::transmute::<&'a DenseVec<T, B4, F>, &'a Vec4View<T>>(self) }
     }
 }
 
 
-impl<T: Scalar> DerefMut for DenseVec<T, B4> {
+impl<T: Scalar, F: Frame> DerefMut for DenseVec<T, B4, F> {
     fn deref_mut<'a>(&'a mut self) -> &'a mut Vec4View<T> {
-        unsafe { mem::transmute::<&'a mut DenseVec<T, B4>, &'a mut Vec4View<T>>(self) }
+        unsafe { mem::transmute::<&'a mut DenseVec<T, B4, F>, &'a mut Vec4View<T>>(self) }
     }
 }
 
 
-impl<T: Scalar, N: Size<T>> Vector for DenseVec<T, N> {
+impl<T: Scalar, N: Size<T>, F: Frame> Vector for DenseVec<T, N, F> {
     type Dims = N;
 
     type Scalar = T;
@@ -238,7 +350,7 @@ impl<T: Scalar, N: Size<T>> Vector for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Scalar, N: Size<T>> Vector for &'a DenseVec<T, N> {
+impl<'a, T: Scalar, N: Size<T>, F: Frame> Vector for &'a DenseVec<T, N, F> {
     type Dims = N;
 
     type Scalar = T;
@@ -249,7 +361,7 @@ impl<'a, T: Scalar, N: Size<T>> Vector for &'a DenseVec<T, N> {
 }
 
 
-impl<T: Scalar, N: Size<T>> Add for DenseVec<T, N> {
+impl<T: Scalar, N: Size<T>, F: Frame> Add for DenseVec<T, N, F> {
     type Output = Self;
 
     fn add(mut self, rhs: Self) -> Self::Output {
@@ -270,7 +382,7 @@ impl<T: Scalar, N: Size<T>> Add for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Add<&'a DenseVec<T, N>> for DenseVec<T, N> {
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Add<&'a DenseVec<T, N, F>> for DenseVec<T, N, F> {
     type Output = Self;
 
     fn add(mut self, rhs: &'a Self) -> Self::Output {
@@ -287,10 +399,10 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Add<&'a DenseVec<T, N>> for DenseVec<T,
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Add<DenseVec<T, N>> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Add<DenseVec<T, N, F>> for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
-    fn add(self, mut rhs: DenseVec<T, N>) -> Self::Output {
+    fn add(self, mut rhs: DenseVec<T, N, F>) -> Self::Output {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         let n = cmp::min(self.elems.len(), rhs.elems.len());
@@ -304,10 +416,12 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Add<DenseVec<T, N>> for &'a DenseVec<T,
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Add<&'b DenseVec<T, N>> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, 'b, T: Clone + Scalar, N: Size<T>, F: Frame> Add<&'b DenseVec<T, N, F>>
+    for &'a DenseVec<T, N, F>
+{
+    type Output = DenseVec<T, N, F>;
 
-    fn add(self, rhs: &'b DenseVec<T, N>) -> Self::Output {
+    fn add(self, rhs: &'b DenseVec<T, N, F>) -> Self::Output {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         DenseVec::from_fn(self.elems.size(),
@@ -316,7 +430,7 @@ impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Add<&'b DenseVec<T, N>> for &'a Dens
 }
 
 
-impl<T: Scalar, N: Size<T>> Sub for DenseVec<T, N> {
+impl<T: Scalar, N: Size<T>, F: Frame> Sub for DenseVec<T, N, F> {
     type Output = Self;
 
     fn sub(mut self, rhs: Self) -> Self {
@@ -337,7 +451,7 @@ impl<T: Scalar, N: Size<T>> Sub for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Sub<&'a DenseVec<T, N>> for DenseVec<T, N> {
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Sub<&'a DenseVec<T, N, F>> for DenseVec<T, N, F> {
     type Output = Self;
 
     fn sub(mut self, rhs: &'a Self) -> Self::Output {
@@ -354,10 +468,10 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Sub<&'a DenseVec<T, N>> for DenseVec<T,
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Sub<DenseVec<T, N>> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Sub<DenseVec<T, N, F>> for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
-    fn sub(self, mut rhs: DenseVec<T, N>) -> Self::Output {
+    fn sub(self, mut rhs: DenseVec<T, N, F>) -> Self::Output {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         let n = cmp::min(self.elems.len(), rhs.elems.len());
@@ -371,10 +485,12 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Sub<DenseVec<T, N>> for &'a DenseVec<T,
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Sub<&'b DenseVec<T, N>> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, 'b, T: Clone + Scalar, N: Size<T>, F: Frame> Sub<&'b DenseVec<T, N, F>>
+    for &'a DenseVec<T, N, F>
+{
+    type Output = DenseVec<T, N, F>;
 
-    fn sub(self, rhs: &'b DenseVec<T, N>) -> Self::Output {
+    fn sub(self, rhs: &'b DenseVec<T, N, F>) -> Self::Output {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         DenseVec::from_fn(self.elems.size(),
@@ -383,7 +499,7 @@ impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Sub<&'b DenseVec<T, N>> for &'a Dens
 }
 
 
-impl<T: Scalar, N: Size<T>> Mul for DenseVec<T, N> {
+impl<T: Scalar, N: Size<T>, F: Frame> Mul for DenseVec<T, N, F> {
     type Output = Self;
 
     fn mul(mut self, rhs: Self) -> Self {
@@ -404,10 +520,12 @@ impl<T: Scalar, N: Size<T>> Mul for DenseVec<T, N> {
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Mul<&'b DenseVec<T, N>> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, 'b, T: Clone + Scalar, N: Size<T>, F: Frame> Mul<&'b DenseVec<T, N, F>>
+    for &'a DenseVec<T, N, F>
+{
+    type Output = DenseVec<T, N, F>;
 
-    fn mul(self, rhs: &'b DenseVec<T, N>) -> Self::Output {
+    fn mul(self, rhs: &'b DenseVec<T, N, F>) -> Self::Output {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         DenseVec::from_fn(self.elems.size(),
@@ -416,7 +534,7 @@ impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Mul<&'b DenseVec<T, N>> for &'a Dens
 }
 
 
-impl<T: Clone + Scalar, N: Size<T>> MulAssign<T> for DenseVec<T, N> {
+impl<T: Clone + Scalar, N: Size<T>, F: Frame> MulAssign<T> for DenseVec<T, N, F> {
     fn mul_assign(&mut self, rhs: T) {
         for i in 0..self.elems.len() {
             self.elems[i] *= rhs.clone();
@@ -425,7 +543,7 @@ impl<T: Clone + Scalar, N: Size<T>> MulAssign<T> for DenseVec<T, N> {
 }
 
 
-impl<T: Clone + Scalar, N: Size<T>> Mul<T> for DenseVec<T, N> {
+impl<T: Clone + Scalar, N: Size<T>, F: Frame> Mul<T> for DenseVec<T, N, F> {
     type Output = Self;
 
     fn mul(mut self, rhs: T) -> Self {
@@ -435,7 +553,7 @@ impl<T: Clone + Scalar, N: Size<T>> Mul<T> for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Mul<&'a T> for DenseVec<T, N> {
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Mul<&'a T> for DenseVec<T, N, F> {
     type Output = Self;
 
     fn mul(mut self, rhs: &'a T) -> Self::Output {
@@ -448,8 +566,8 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Mul<&'a T> for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Mul<T> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Mul<T> for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
     fn mul(self, rhs: T) -> Self::Output {
         DenseVec::from_fn(self.elems.size(), |i| self.elems[i].clone() * rhs.clone())
@@ -457,8 +575,8 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Mul<T> for &'a DenseVec<T, N> {
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Mul<&'b T> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, 'b, T: Clone + Scalar, N: Size<T>, F: Frame> Mul<&'b T> for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
     fn mul(self, rhs: &'b T) -> Self::Output {
         DenseVec::from_fn(self.elems.size(), |i| self.elems[i].clone() * rhs.clone())
@@ -466,7 +584,7 @@ impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Mul<&'b T> for &'a DenseVec<T, N> {
 }
 
 
-impl<T: Clone + Scalar, N: Size<T>> Div<T> for DenseVec<T, N> {
+impl<T: Clone + Scalar, N: Size<T>, F: Frame> Div<T> for DenseVec<T, N, F> {
     type Output = Self;
 
     fn div(mut self, rhs: T) -> Self {
@@ -479,7 +597,7 @@ impl<T: Clone + Scalar, N: Size<T>> Div<T> for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Div<&'a T> for DenseVec<T, N> {
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Div<&'a T> for DenseVec<T, N, F> {
     type Output = Self;
 
     fn div(mut self, rhs: &'a T) -> Self::Output {
@@ -492,8 +610,8 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Div<&'a T> for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Div<T> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Div<T> for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
     fn div(self, rhs: T) -> Self::Output {
         DenseVec::from_fn(self.elems.size(), |i| self.elems[i].clone() / rhs.clone())
@@ -501,8 +619,8 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Div<T> for &'a DenseVec<T, N> {
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Div<&'b T> for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, 'b, T: Clone + Scalar, N: Size<T>, F: Frame> Div<&'b T> for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
     fn div(self, rhs: &'b T) -> Self::Output {
         DenseVec::from_fn(self.elems.size(), |i| self.elems[i].clone() / rhs.clone())
@@ -510,8 +628,8 @@ impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Div<&'b T> for &'a DenseVec<T, N> {
 }
 
 
-impl<T: Scalar, N: Size<T>> Neg for DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<T: Scalar, N: Size<T>, F: Frame> Neg for DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
     fn neg(mut self) -> Self::Output {
         unsafe {
@@ -525,8 +643,8 @@ impl<T: Scalar, N: Size<T>> Neg for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Neg for &'a DenseVec<T, N> {
-    type Output = DenseVec<T, N>;
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Neg for &'a DenseVec<T, N, F> {
+    type Output = DenseVec<T, N, F>;
 
     fn neg(self) -> Self::Output {
         DenseVec::from_fn(self.elems.size(), |i| -self.elems[i].clone())
@@ -534,7 +652,7 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Neg for &'a DenseVec<T, N> {
 }
 
 
-impl<T: Scalar, N: Size<T>> Dot for DenseVec<T, N> {
+impl<T: Scalar, N: Size<T>, F: Frame> Dot for DenseVec<T, N, F> {
     fn dot(self, rhs: Self) -> T {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
@@ -560,8 +678,8 @@ impl<T: Scalar, N: Size<T>> Dot for DenseVec<T, N> {
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Dot<&'a DenseVec<T, N>> for DenseVec<T, N> {
-    fn dot(self, rhs: &'a DenseVec<T, N>) -> T {
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Dot<&'a DenseVec<T, N, F>> for DenseVec<T, N, F> {
+    fn dot(self, rhs: &'a DenseVec<T, N, F>) -> T {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         let n = cmp::min(self.elems.len(), rhs.elems.len());
@@ -585,8 +703,8 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Dot<&'a DenseVec<T, N>> for DenseVec<T,
 }
 
 
-impl<'a, T: Clone + Scalar, N: Size<T>> Dot<DenseVec<T, N>> for &'a DenseVec<T, N> {
-    fn dot(self, rhs: DenseVec<T, N>) -> T {
+impl<'a, T: Clone + Scalar, N: Size<T>, F: Frame> Dot<DenseVec<T, N, F>> for &'a DenseVec<T, N, F> {
+    fn dot(self, rhs: DenseVec<T, N, F>) -> T {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         let n = cmp::min(self.elems.len(), rhs.elems.len());
@@ -610,8 +728,10 @@ impl<'a, T: Clone + Scalar, N: Size<T>> Dot<DenseVec<T, N>> for &'a DenseVec<T,
 }
 
 
-impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Dot<&'b DenseVec<T, N>> for &'a DenseVec<T, N> {
-    fn dot(self, rhs: &'b DenseVec<T, N>) -> T {
+impl<'a, 'b, T: Clone + Scalar, N: Size<T>, F: Frame> Dot<&'b DenseVec<T, N, F>>
+    for &'a DenseVec<T, N, F>
+{
+    fn dot(self, rhs: &'b DenseVec<T, N, F>) -> T {
         assert_eq!(self.elems.size(), rhs.elems.size());
 
         let n = cmp::min(self.elems.len(), rhs.elems.len());
@@ -629,7 +749,7 @@ impl<'a, 'b, T: Clone + Scalar, N: Size<T>> Dot<&'b DenseVec<T, N>> for &'a Dens
 }
 
 
-impl<T: Clone + Scalar + Float, N: Size<T>> VectorNorm for DenseVec<T, N> {
+impl<T: Clone + Scalar + Float, N: Size<T>, F: Frame> VectorNorm for DenseVec<T, N, F> {
     fn norm(&self) -> T {
         let mut accum = T::zero();
 
@@ -642,7 +762,7 @@ impl<T: Clone + Scalar + Float, N: Size<T>> VectorNorm for DenseVec<T, N> {
 }
 
 
-impl<T: Clone + Scalar, N: Size<T>> DenseVec<T, N> {
+impl<T: Clone + Scalar, N: Size<T>, F: Frame> DenseVec<T, N, F> {
     pub fn project(self, rhs: Self) -> Self {
         let a_dot_b = self.dot(&rhs);
         let b_dot_b = (&rhs).dot(&rhs);
@@ -663,7 +783,7 @@ impl<T: Clone + Scalar, N: Size<T>> DenseVec<T, N> {
 }
 
 
-impl<T: Clone + Scalar + Float, N: Size<T>> DenseVec<T, N> {
+impl<T: Clone + Scalar + Float, N: Size<T>, F: Frame> DenseVec<T, N, F> {
     pub fn component(self, rhs: Self) -> T {
         let norm = rhs.norm();
         self.dot(rhs) / norm
@@ -671,7 +791,218 @@ impl<T: Clone + Scalar + Float, N: Size<T>> DenseVec<T, N> {
 }
 
 
-impl<T: Scalar, N: Size<T>> Index<usize> for DenseVec<T, N> {
+impl<T: Clone + Scalar, F: Frame> DenseVec<T, B3, F> {
+    /// The cross product `self x rhs`, orthogonal to both with a length of the area of the
+    /// parallelogram they span.
+    pub fn cross(&self, rhs: &Self) -> Self {
+        let (a, b) = (&self.elems, &rhs.elems);
+
+        DenseVec::from_fn(B3::as_data(), |i| {
+            let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+            a[j].clone() * b[k].clone() - a[k].clone() * b[j].clone()
+        })
+    }
+}
+
+
+impl<T: Clone + Scalar, F: Frame> DenseVec<T, B2, F> {
+    /// The vector rotated a quarter turn counterclockwise, `(-y, x)`.
+    pub fn perp(&self) -> Self {
+        DenseVec::from_fn(B2::as_data(), |i| match i {
+            0 => -self.elems[1].clone(),
+            _ => self.elems[0].clone(),
+        })
+    }
+
+
+    /// The two-dimensional cross product, `perp(self) . rhs`: positive if `rhs` is
+    /// counterclockwise of `self`.
+    pub fn perp_dot(&self, rhs: &Self) -> T {
+        self.elems[0].clone() * rhs.elems[1].clone() - self.elems[1].clone() * rhs.elems[0].clone()
+    }
+}
+
+
+/// The determinant of a row-major `n x n` matrix by fraction-free Bareiss elimination, which is
+/// exact for integers.
+fn bareiss_determinant<T: Clone + Scalar>(mut m: Vec<T>, n: usize) -> T {
+    let mut negate = false;
+    let mut prev = T::one();
+
+    for k in 0..n {
+        let pivot = (k..n).fold(k, |best, i| {
+            if m[i * n + k].abs() > m[best * n + k].abs() { i } else { best }
+        });
+
+        if m[pivot * n + k].eq_zero() {
+            return T::zero();
+        }
+
+        if pivot != k {
+            for j in 0..n {
+                m.swap(pivot * n + j, k * n + j);
+            }
+
+            negate = !negate;
+        }
+
+        for i in k + 1..n {
+            for j in k + 1..n {
+                m[i * n + j] = (m[i * n + j].clone() * m[k * n + k].clone() -
+                                m[i * n + k].clone() * m[k * n + j].clone()) /
+                               prev.clone();
+            }
+        }
+
+        prev = m[k * n + k].clone();
+    }
+
+    if negate { -prev } else { prev }
+}
+
+
+impl<T: Clone + Scalar, N: Size<T>, F: Frame> DenseVec<T, N, F> {
+    /// The generalized cross product of `n - 1` vectors in `n` dimensions: the vector orthogonal
+    /// to all of them whose length is the volume of the parallelotope they span, oriented so that
+    /// the vectors followed by the result have a positive determinant. It is zero if the vectors
+    /// are linearly dependent. In three dimensions, this is `a.cross(&b)`; in two, it is
+    /// `a.perp()`.
+    ///
+    /// Each element is a cofactor, computed exactly for integer scalars.
+    pub fn generalized_cross(size: N, vectors: &[Self]) -> Self {
+        let n = size.reify();
+        assert_eq!(vectors.len() + 1, n);
+
+        DenseVec::from_fn(size, |i| {
+            let minor = vectors.iter()
+                .flat_map(|v| (0..n).filter(move |&j| j != i).map(move |j| v[j].clone()))
+                .collect();
+            let det = bareiss_determinant(minor, n - 1);
+
+            if (n - 1 + i) % 2 == 0 { det } else { -det }
+        })
+    }
+
+
+    /// Linear interpolation, `self` at `t = 0` and `rhs` at `t = 1`.
+    pub fn lerp(&self, rhs: &Self, t: T) -> Self {
+        self + &((rhs - self) * t)
+    }
+
+
+    /// The component-wise minimum.
+    pub fn min(&self, rhs: &Self) -> Self {
+        assert_eq!(self.size(), rhs.size());
+
+        DenseVec::from_fn(self.size(), |i| if rhs[i] < self[i] {
+            rhs[i].clone()
+        } else {
+            self[i].clone()
+        })
+    }
+
+
+    /// The component-wise maximum.
+    pub fn max(&self, rhs: &Self) -> Self {
+        assert_eq!(self.size(), rhs.size());
+
+        DenseVec::from_fn(self.size(), |i| if rhs[i] > self[i] {
+            rhs[i].clone()
+        } else {
+            self[i].clone()
+        })
+    }
+
+
+    /// Clamps each component between the corresponding components of `lo` and `hi`.
+    pub fn clamp(&self, lo: &Self, hi: &Self) -> Self {
+        self.max(lo).min(hi)
+    }
+
+
+    /// Reflects the vector off a surface with the unit normal `normal`.
+    pub fn reflect(&self, normal: &Self) -> Self {
+        let two = T::one() + T::one();
+        self - &(normal * (self.dot(normal) * two))
+    }
+}
+
+
+impl<T: Clone + Scalar + Float, N: Size<T>, F: Frame> DenseVec<T, N, F> {
+    /// The vector scaled to unit length. The zero vector has no direction, and normalizes to NaNs
+    /// for floating-point scalars; see `try_normalize`.
+    pub fn normalize(&self) -> Self {
+        self * self.norm().recip()
+    }
+
+
+    /// The vector scaled to unit length, or `None` if its length is at most `epsilon`.
+    pub fn try_normalize(&self, epsilon: T) -> Option<Self> {
+        let norm = self.norm();
+
+        if norm <= epsilon {
+            None
+        } else {
+            Some(self * norm.recip())
+        }
+    }
+
+
+    /// The unsigned angle between two vectors, in `[0, pi]`. Computed as
+    /// `2 atan2(|a |b| - |a| b|, |a |b| + |a| b|)`, which unlike the arccosine of the normalized
+    /// dot product stays accurate for nearly parallel vectors.
+    pub fn angle_between(&self, rhs: &Self) -> T {
+        let a = self * rhs.norm();
+        let b = rhs * self.norm();
+        let two = T::one() + T::one();
+
+        (&a - &b).norm().atan2((&a + &b).norm()) * two
+    }
+
+
+    /// Refracts the unit vector `self` through a surface with the unit normal `normal`, which
+    /// should point against `self`, where `eta` is the ratio of the refractive index being left
+    /// to the one being entered. Returns `None` on total internal reflection.
+    pub fn refract(&self, normal: &Self, eta: T) -> Option<Self> {
+        let cos_i = -self.dot(normal);
+        let sin2_t = eta.clone() * eta.clone() * (T::one() - cos_i.clone() * cos_i.clone());
+
+        if sin2_t > T::one() {
+            return None;
+        }
+
+        let cos_t = (T::one() - sin2_t).sqrt();
+        Some(&(self * eta.clone()) + &(normal * (eta * cos_i - cos_t)))
+    }
+
+
+    /// Orthonormalizes a set of vectors by modified Gram–Schmidt, in order. Vectors that are
+    /// linearly dependent on their predecessors, to within a relative tolerance of the square
+    /// root of machine epsilon, are dropped, so the result is an orthonormal basis of their span.
+    pub fn gram_schmidt(vectors: &[Self]) -> Vec<Self> {
+        let tolerance = T::epsilon().sqrt();
+        let mut basis: Vec<Self> = Vec::with_capacity(vectors.len());
+
+        for v in vectors {
+            let scale = v.norm();
+            let mut u = v.clone();
+
+            for e in &basis {
+                u = &u - &(e * (&u).dot(e));
+            }
+
+            let norm = u.norm();
+            if norm > tolerance.clone() * scale {
+                basis.push(u * norm.recip());
+            }
+        }
+
+        basis
+    }
+}
+
+
+impl<T: Scalar, N: Size<T>, F: Frame> Index<usize> for DenseVec<T, N, F> {
     type Output = T;
 
     fn index(&self, idx: usize) -> &T {
@@ -680,7 +1011,7 @@ impl<T: Scalar, N: Size<T>> Index<usize> for DenseVec<T, N> {
 }
 
 
-impl<T: Scalar, N: Size<T>> IndexMut<usize> for DenseVec<T, N> {
+impl<T: Scalar, N: Size<T>, F: Frame> IndexMut<usize> for DenseVec<T, N, F> {
     fn index_mut(&mut self, idx: usize) -> &mut T {
         &mut self.elems[idx]
     }
@@ -699,7 +1030,7 @@ impl<T: Scalar, N: Size<T>> IntoIterator for DenseVec<T, N> {
 
 impl<T: Scalar> FromIterator<T> for DenseVec<T, Dyn> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        DenseVec { elems: iter.into_iter().collect() }
+        DenseVec { elems: iter.into_iter().collect(), frame: PhantomData }
     }
 }
 
@@ -708,7 +1039,7 @@ impl<T: Scalar, N: Size<T>> FromExactSizeIterator<T> for DenseVec<T, N> {
     fn from_exact_size_iter<I: IntoIterator<Item = T>>(iter: I) -> Self
         where I::IntoIter: ExactSizeIterator
     {
-        DenseVec { elems: iter.into_iter().collect_exact() }
+        DenseVec { elems: iter.into_iter().collect_exact(), frame: PhantomData }
     }
 }
 
@@ -722,3 +1053,127 @@ impl<T: Scalar, N: Size<T>> Sum for DenseVec<T, N> {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn assert_close<N: Size<f64>>(u: &DenseVec<f64, N>, v: &DenseVec<f64, N>) {
+        assert!((0..u.len()).all(|i| (u[i] - v[i]).abs() < 1e-12), "{:?} != {:?}", u, v);
+    }
+
+    #[test]
+    fn cross_and_perp() {
+        assert_eq!(Vect3!(1, 0, 0).cross(&Vect3!(0, 1, 0)), Vect3!(0, 0, 1));
+        assert_eq!(Vect3!(1, 2, 3).cross(&Vect3!(4, 5, 6)), Vect3!(-3, 6, -3));
+        assert_eq!(Vect2!(1, 2).perp(), Vect2!(-2, 1));
+        assert_eq!(Vect2!(1, 0).perp_dot(&Vect2!(0, 1)), 1);
+    }
+
+    #[test]
+    fn generalized_cross_matches_special_cases() {
+        let (a, b) = (Vect![1, 2, 3], Vect![4, 5, 6]);
+        assert_eq!(DenseVec::generalized_cross(B3::as_data(), &[a.clone(), b.clone()]),
+                   a.cross(&b));
+
+        let v = Vect![3, -1];
+        assert_eq!(DenseVec::generalized_cross(B2::as_data(), &[v.clone()]), v.perp());
+
+        let vs = [Vect![1., 0., 0., 2.], Vect![0., 1., 0., -1.], Vect![1., 1., 1., 0.]];
+        let n = DenseVec::generalized_cross(B4::as_data(), &vs);
+        for v in &vs {
+            assert!((&n).dot(v).abs() < 1e-12);
+        }
+
+        let dependent = [Vect![1, 2, 3], Vect![2, 4, 6]];
+        assert_eq!(DenseVec::generalized_cross(B3::as_data(), &dependent), Vect![0, 0, 0]);
+    }
+
+    #[test]
+    fn extend_and_truncate() {
+        let v = Vect3!(1, 2, 3).extend(4);
+        assert_eq!(v, Vect4!(1, 2, 3, 4));
+        assert_eq!(v.truncate(), Vect3!(1, 2, 3));
+        assert_eq!(Vect2!(5, 6).truncate(), Vect1!(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "empty")]
+    fn truncate_empty_panics() {
+        let empty: DenseVec<i32, Dyn> = DenseVec::from(&[][..]);
+        empty.truncate();
+    }
+
+    #[test]
+    fn lerp_min_max_clamp() {
+        assert_close(&Vect![0., 2.].lerp(&Vect![4., -2.], 0.25), &Vect![1., 1.]);
+        assert_eq!(Vect![1, 5, 3].min(&Vect![2, 4, 3]), Vect![1, 4, 3]);
+        assert_eq!(Vect![1, 5, 3].max(&Vect![2, 4, 3]), Vect![2, 5, 3]);
+        assert_eq!(Vect![-1, 5, 3].clamp(&Vect![0, 0, 0], &Vect![4, 4, 4]), Vect![0, 4, 3]);
+    }
+
+    #[test]
+    fn normalize_and_angles() {
+        assert_close(&Vect![3., 4.].normalize(), &Vect![0.6, 0.8]);
+        assert!(Vect![0., 0.].try_normalize(1e-12).is_none());
+
+        assert!((Vect![1., 0.].angle_between(&Vect![0., 3.]) - PI / 2.).abs() < 1e-12);
+        assert!((Vect![1., 1.].angle_between(&Vect![-2., -2.]) - PI).abs() < 1e-12);
+        assert!(Vect![1., 1e-9].angle_between(&Vect![1., 0.]) > 0.);
+    }
+
+    #[test]
+    fn reflect_and_refract() {
+        let normal = Vect![0., 1.];
+        assert_close(&Vect![1., -1.].reflect(&normal), &Vect![1., 1.]);
+
+        let incoming = Vect![1., -1.].normalize();
+        let straight = incoming.refract(&normal, 1.).unwrap();
+        assert_close(&straight, &incoming);
+
+        // Snell's law: sin(t) = eta sin(i).
+        let bent = incoming.refract(&normal, 0.5).unwrap();
+        assert!((bent[0] - 0.5 * incoming[0]).abs() < 1e-12);
+        assert!((bent.norm() - 1.).abs() < 1e-12);
+
+        assert!(incoming.refract(&normal, 1.5).is_none());
+    }
+
+    #[test]
+    fn gram_schmidt_drops_dependent_vectors() {
+        let vs = [Vect![1., 1., 0.], Vect![2., 2., 0.], Vect![1., 0., 0.], Vect![0., 0., 5.]];
+        let basis = DenseVec::gram_schmidt(&vs);
+
+        assert_eq!(basis.len(), 3);
+        for i in 0..3 {
+            assert!((basis[i].norm() - 1.).abs() < 1e-12);
+            for j in 0..i {
+                assert!((&basis[i]).dot(&basis[j]).abs() < 1e-12);
+            }
+        }
+        assert_close(&basis[0], &Vect![0.5f64.sqrt(), 0.5f64.sqrt(), 0.]);
+    }
+
+    #[test]
+    fn vect_codec_round_trip_and_mismatch() {
+        let v = Vect3!(1i32, -2, 3);
+
+        let mut bytes = Vec::new();
+        v.encode(&mut bytes).unwrap();
+        assert_eq!(&bytes[..8], &[3, 0, 0, 0, 0, 0, 0, 0]);
+
+        let fixed: DenseVec<i32, B3> = Decode::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(fixed, v);
+
+        let dynamic: DenseVec<i32, Dyn> = Decode::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(dynamic.as_slice(), v.as_slice());
+
+        match DenseVec::<i32, B2>::decode(&mut &bytes[..]) {
+            Err(DecodeError::DimensionMismatch(3)) => {}
+            other => panic!("unexpected result {:?}", other.map(|v| v.as_slice().to_vec())),
+        }
+    }
+}
@@ -0,0 +1,688 @@
+use std::cmp;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::ptr;
+
+use linalg::matrix::*;
+use linalg::traits::*;
+use typehack::binary::*;
+use typehack::dim::*;
+
+
+// Views are represented as a pointer to their `[[0, 0]]` element along with a pair of strides,
+// which lets a single type describe blocks of both `Row` and `Column` matrices, as well as rows,
+// columns and diagonals. Raw pointers are used rather than slices because disjoint mutable views
+// (for example, the two halves of a column-major matrix split at a row) interleave in memory.
+
+
+/// An immutable, borrowed, possibly strided view into a block of a matrix.
+///
+/// `MatView` does not implement `Matrix`: that trait requires `IndexMut` and the in-place row and
+/// column operations, which a shared borrow cannot provide. It offers the read-only half of the
+/// interface (`rows`, `cols`, `get` and `Index`) as inherent methods instead. Algorithms that only
+/// read their input can take a `MatView`; those written against `Matrix` need a `MatViewMut`, or
+/// an owned copy from `to_mat`.
+pub struct MatView<'a, T: Scalar + 'a, M: Dim, N: Dim> {
+    rows: M,
+    cols: N,
+    strides: [usize; 2],
+    ptr: *const T,
+    phantom: PhantomData<&'a T>,
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Clone for MatView<'a, T, M, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Copy for MatView<'a, T, M, N> {}
+
+
+/// A mutable, borrowed, possibly strided view into a block of a matrix. Unlike `MatView`, a
+/// `MatViewMut` implements `Matrix`, so algorithms written against `Matrix` may operate on blocks
+/// of a larger matrix in place.
+pub struct MatViewMut<'a, T: Scalar + 'a, M: Dim, N: Dim> {
+    rows: M,
+    cols: N,
+    strides: [usize; 2],
+    ptr: *mut T,
+    phantom: PhantomData<&'a mut T>,
+}
+
+
+#[inline]
+fn offset(strides: [usize; 2], row: usize, col: usize) -> isize {
+    (row * strides[0] + col * strides[1]) as isize
+}
+
+
+macro_rules! view_bounds_check {
+    ($view:expr, $row:expr, $col:expr) => {
+        assert!($row < $view.rows.reify() && $col < $view.cols.reify(),
+                "Matrix index out of bounds: [[{}, {}]] is out of bounds of [[{}, {}]]!",
+                $row,
+                $col,
+                $view.rows.reify(),
+                $view.cols.reify());
+    }
+}
+
+
+macro_rules! subview_bounds_check {
+    ($view:expr, $r0:expr, $c0:expr, $rows:expr, $cols:expr) => {
+        assert!($r0 + $rows.reify() <= $view.rows.reify() &&
+                $c0 + $cols.reify() <= $view.cols.reify(),
+                "Submatrix [[{}, {}]]..[[{}, {}]] is out of bounds of [[{}, {}]]!",
+                $r0,
+                $c0,
+                $r0 + $rows.reify(),
+                $c0 + $cols.reify(),
+                $view.rows.reify(),
+                $view.cols.reify());
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> MatView<'a, T, M, N> {
+    pub fn rows(&self) -> M {
+        self.rows
+    }
+
+
+    pub fn cols(&self) -> N {
+        self.cols
+    }
+
+
+    pub fn strides(&self) -> [usize; 2] {
+        self.strides
+    }
+
+
+    pub fn get(&self, row: usize, col: usize) -> &'a T {
+        view_bounds_check!(self, row, col);
+
+        unsafe { &*self.ptr.offset(offset(self.strides, row, col)) }
+    }
+
+
+    /// Borrows the `rows` by `cols` block whose top-left corner is at `[[r0, c0]]`.
+    pub fn view<P: Dim, Q: Dim>(self, r0: usize, c0: usize, rows: P, cols: Q) -> MatView<'a, T, P, Q> {
+        subview_bounds_check!(self, r0, c0, rows, cols);
+
+        let ptr = if rows.reify() == 0 || cols.reify() == 0 {
+            self.ptr
+        } else {
+            unsafe { self.ptr.offset(offset(self.strides, r0, c0)) }
+        };
+
+        MatView {
+            rows: rows,
+            cols: cols,
+            strides: self.strides,
+            ptr: ptr,
+            phantom: PhantomData,
+        }
+    }
+
+
+    pub fn row(self, i: usize) -> MatView<'a, T, B1, N> {
+        let cols = self.cols;
+        self.view(i, 0, B1::as_data(), cols)
+    }
+
+
+    pub fn col(self, j: usize) -> MatView<'a, T, M, B1> {
+        let rows = self.rows;
+        self.view(0, j, rows, B1::as_data())
+    }
+
+
+    /// Borrows the main diagonal as a column view.
+    pub fn diagonal(self) -> MatView<'a, T, Dyn, B1> {
+        MatView {
+            rows: Dyn(cmp::min(self.rows.reify(), self.cols.reify())),
+            cols: B1::as_data(),
+            strides: [self.strides[0] + self.strides[1], self.strides[1]],
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+
+    /// Splits this view into the rows above row `k` and the rows from `k` onwards.
+    pub fn split_at_row(self, k: usize) -> (MatView<'a, T, Dyn, N>, MatView<'a, T, Dyn, N>) {
+        assert!(k <= self.rows.reify());
+
+        let (m, cols) = (self.rows.reify(), self.cols);
+        (self.view(0, 0, Dyn(k), cols), self.view(k, 0, Dyn(m - k), cols))
+    }
+
+
+    /// Splits this view into the columns left of column `k` and the columns from `k` onwards.
+    pub fn split_at_col(self, k: usize) -> (MatView<'a, T, M, Dyn>, MatView<'a, T, M, Dyn>) {
+        assert!(k <= self.cols.reify());
+
+        let (rows, n) = (self.rows, self.cols.reify());
+        (self.view(0, 0, rows, Dyn(k)), self.view(0, k, rows, Dyn(n - k)))
+    }
+
+
+    /// Copies the viewed elements into a new, densely packed matrix.
+    pub fn to_mat<L: Layout>(&self) -> DenseMat<T, M, N, L> {
+        let mut out;
+
+        unsafe {
+            out = DenseMat::uninitialized(self.rows, self.cols);
+
+            for i in 0..self.rows.reify() {
+                for j in 0..self.cols.reify() {
+                    ptr::write(&mut out[[i, j]], self.get(i, j).clone());
+                }
+            }
+        }
+
+        out
+    }
+}
+
+
+/// An iterator over the rows of a matrix, as views.
+pub struct RowIter<'a, T: Scalar + 'a, M: Dim, N: Dim> {
+    view: MatView<'a, T, M, N>,
+    next: usize,
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Iterator for RowIter<'a, T, M, N> {
+    type Item = MatView<'a, T, B1, N>;
+
+    fn next(&mut self) -> Option<MatView<'a, T, B1, N>> {
+        if self.next < self.view.rows.reify() {
+            self.next += 1;
+            Some(self.view.row(self.next - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.view.rows.reify() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> ExactSizeIterator for RowIter<'a, T, M, N> {}
+
+
+/// An iterator over the columns of a matrix, as views.
+pub struct ColIter<'a, T: Scalar + 'a, M: Dim, N: Dim> {
+    view: MatView<'a, T, M, N>,
+    next: usize,
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Iterator for ColIter<'a, T, M, N> {
+    type Item = MatView<'a, T, M, B1>;
+
+    fn next(&mut self) -> Option<MatView<'a, T, M, B1>> {
+        if self.next < self.view.cols.reify() {
+            self.next += 1;
+            Some(self.view.col(self.next - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.view.cols.reify() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> ExactSizeIterator for ColIter<'a, T, M, N> {}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> MatView<'a, T, M, N> {
+    pub fn row_iter(self) -> RowIter<'a, T, M, N> {
+        RowIter {
+            view: self,
+            next: 0,
+        }
+    }
+
+
+    pub fn col_iter(self) -> ColIter<'a, T, M, N> {
+        ColIter {
+            view: self,
+            next: 0,
+        }
+    }
+
+
+    /// Iterates over the viewed elements, row by row.
+    pub fn iter(self) -> Box<Iterator<Item = &'a T> + 'a>
+        where M: 'a,
+              N: 'a
+    {
+        let (m, n) = (self.rows.reify(), self.cols.reify());
+        Box::new((0..m * n).map(move |k| self.get(k / n, k % n)))
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> MatViewMut<'a, T, M, N> {
+    pub fn rows(&self) -> M {
+        self.rows
+    }
+
+
+    pub fn cols(&self) -> N {
+        self.cols
+    }
+
+
+    pub fn strides(&self) -> [usize; 2] {
+        self.strides
+    }
+
+
+    pub fn as_view(&self) -> MatView<T, M, N> {
+        MatView {
+            rows: self.rows,
+            cols: self.cols,
+            strides: self.strides,
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+
+    /// Reborrows this view, so that consuming methods such as `view_mut` may be called without
+    /// giving it up.
+    pub fn reborrow(&mut self) -> MatViewMut<T, M, N> {
+        MatViewMut {
+            rows: self.rows,
+            cols: self.cols,
+            strides: self.strides,
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+
+    pub fn view_mut<P: Dim, Q: Dim>(self,
+                                    r0: usize,
+                                    c0: usize,
+                                    rows: P,
+                                    cols: Q)
+                                    -> MatViewMut<'a, T, P, Q> {
+        subview_bounds_check!(self, r0, c0, rows, cols);
+
+        let ptr = if rows.reify() == 0 || cols.reify() == 0 {
+            self.ptr
+        } else {
+            unsafe { self.ptr.offset(offset(self.strides, r0, c0)) }
+        };
+
+        MatViewMut {
+            rows: rows,
+            cols: cols,
+            strides: self.strides,
+            ptr: ptr,
+            phantom: PhantomData,
+        }
+    }
+
+
+    pub fn row_mut(self, i: usize) -> MatViewMut<'a, T, B1, N> {
+        let cols = self.cols;
+        self.view_mut(i, 0, B1::as_data(), cols)
+    }
+
+
+    pub fn col_mut(self, j: usize) -> MatViewMut<'a, T, M, B1> {
+        let rows = self.rows;
+        self.view_mut(0, j, rows, B1::as_data())
+    }
+
+
+    pub fn diagonal_mut(self) -> MatViewMut<'a, T, Dyn, B1> {
+        MatViewMut {
+            rows: Dyn(cmp::min(self.rows.reify(), self.cols.reify())),
+            cols: B1::as_data(),
+            strides: [self.strides[0] + self.strides[1], self.strides[1]],
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+
+    pub fn split_at_row_mut(self,
+                            k: usize)
+                            -> (MatViewMut<'a, T, Dyn, N>, MatViewMut<'a, T, Dyn, N>) {
+        assert!(k <= self.rows.reify());
+
+        let (m, cols) = (self.rows.reify(), self.cols);
+
+        // The two halves never alias, so it is safe to hand both out at once.
+        let lower = MatViewMut { ..self };
+        (self.view_mut(0, 0, Dyn(k), cols), lower.view_mut(k, 0, Dyn(m - k), cols))
+    }
+
+
+    pub fn split_at_col_mut(self,
+                            k: usize)
+                            -> (MatViewMut<'a, T, M, Dyn>, MatViewMut<'a, T, M, Dyn>) {
+        assert!(k <= self.cols.reify());
+
+        let (rows, n) = (self.rows, self.cols.reify());
+
+        // The two halves never alias, so it is safe to hand both out at once.
+        let right = MatViewMut { ..self };
+        (self.view_mut(0, 0, rows, Dyn(k)), right.view_mut(0, k, rows, Dyn(n - k)))
+    }
+
+
+    /// Overwrites every element of this view with the corresponding element of `src`.
+    pub fn copy_from<X: Matrix<Scalar = T, Rows = M, Cols = N>>(&mut self, src: &X) {
+        assert_eq!(self.rows, src.rows());
+        assert_eq!(self.cols, src.cols());
+
+        for i in 0..self.rows.reify() {
+            for j in 0..self.cols.reify() {
+                self[[i, j]] = src[[i, j]].clone();
+            }
+        }
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Matrix for MatViewMut<'a, T, M, N> {
+    type Rows = M;
+    type Cols = N;
+
+    type Scalar = T;
+
+
+    fn rows(&self) -> M {
+        self.rows
+    }
+
+    fn cols(&self) -> N {
+        self.cols
+    }
+
+
+    fn get(&self, row: usize, col: usize) -> &T {
+        view_bounds_check!(self, row, col);
+
+        unsafe { &*self.ptr.offset(offset(self.strides, row, col)) }
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        view_bounds_check!(self, row, col);
+
+        unsafe { &mut *self.ptr.offset(offset(self.strides, row, col)) }
+    }
+
+    fn swap(&mut self, a: [usize; 2], b: [usize; 2]) {
+        view_bounds_check!(self, a[0], a[1]);
+        view_bounds_check!(self, b[0], b[1]);
+
+        unsafe {
+            ptr::swap(self.ptr.offset(offset(self.strides, a[0], a[1])),
+                      self.ptr.offset(offset(self.strides, b[0], b[1])));
+        }
+    }
+
+
+    fn row_switch_mut(&mut self, i: usize, j: usize) {
+        for k in 0..self.cols.reify() {
+            self.swap([i, k], [j, k]);
+        }
+    }
+
+    fn row_mul_mut(&mut self, i: usize, c: &T) {
+        for k in 0..self.cols.reify() {
+            self[[i, k]] *= c.clone();
+        }
+    }
+
+    fn row_add_mut(&mut self, i: usize, j: usize, c: &T) {
+        for k in 0..self.cols.reify() {
+            let x = self[[j, k]].clone() * c.clone();
+            self[[i, k]] += x;
+        }
+    }
+
+
+    fn col_switch_mut(&mut self, i: usize, j: usize) {
+        for k in 0..self.rows.reify() {
+            self.swap([k, i], [k, j]);
+        }
+    }
+
+    fn col_mul_mut(&mut self, i: usize, c: &T) {
+        for k in 0..self.rows.reify() {
+            self[[k, i]] *= c.clone();
+        }
+    }
+
+    fn col_add_mut(&mut self, i: usize, j: usize, c: &T) {
+        for k in 0..self.rows.reify() {
+            let x = self[[k, j]].clone() * c.clone();
+            self[[k, i]] += x;
+        }
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Index<[usize; 2]> for MatView<'a, T, M, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, idx: [usize; 2]) -> &T {
+        self.get(idx[0], idx[1])
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> Index<[usize; 2]> for MatViewMut<'a, T, M, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, idx: [usize; 2]) -> &T {
+        Matrix::get(self, idx[0], idx[1])
+    }
+}
+
+
+impl<'a, T: Scalar + 'a, M: Dim, N: Dim> IndexMut<[usize; 2]> for MatViewMut<'a, T, M, N> {
+    #[inline]
+    fn index_mut(&mut self, idx: [usize; 2]) -> &mut T {
+        self.get_mut(idx[0], idx[1])
+    }
+}
+
+
+impl<T: Scalar, M: Dim, N: Dim, L: Layout> DenseMat<T, M, N, L> {
+    pub fn as_view(&self) -> MatView<T, M, N> {
+        MatView {
+            rows: self.rows(),
+            cols: self.cols(),
+            strides: L::strides(self.rows().reify(), self.cols().reify()),
+            ptr: self.as_slice().as_ptr(),
+            phantom: PhantomData,
+        }
+    }
+
+
+    pub fn as_view_mut(&mut self) -> MatViewMut<T, M, N> {
+        MatViewMut {
+            rows: self.rows(),
+            cols: self.cols(),
+            strides: L::strides(self.rows().reify(), self.cols().reify()),
+            ptr: self.as_mut_slice().as_mut_ptr(),
+            phantom: PhantomData,
+        }
+    }
+
+
+    /// Borrows the `rows` by `cols` block whose top-left corner is at `[[r0, c0]]`, without
+    /// copying.
+    pub fn view<P: Dim, Q: Dim>(&self, r0: usize, c0: usize, rows: P, cols: Q) -> MatView<T, P, Q> {
+        self.as_view().view(r0, c0, rows, cols)
+    }
+
+
+    pub fn view_mut<P: Dim, Q: Dim>(&mut self,
+                                    r0: usize,
+                                    c0: usize,
+                                    rows: P,
+                                    cols: Q)
+                                    -> MatViewMut<T, P, Q> {
+        self.as_view_mut().view_mut(r0, c0, rows, cols)
+    }
+
+
+    pub fn row(&self, i: usize) -> MatView<T, B1, N> {
+        self.as_view().row(i)
+    }
+
+
+    pub fn row_mut(&mut self, i: usize) -> MatViewMut<T, B1, N> {
+        self.as_view_mut().row_mut(i)
+    }
+
+
+    pub fn col(&self, j: usize) -> MatView<T, M, B1> {
+        self.as_view().col(j)
+    }
+
+
+    pub fn col_mut(&mut self, j: usize) -> MatViewMut<T, M, B1> {
+        self.as_view_mut().col_mut(j)
+    }
+
+
+    pub fn diagonal(&self) -> MatView<T, Dyn, B1> {
+        self.as_view().diagonal()
+    }
+
+
+    pub fn diagonal_mut(&mut self) -> MatViewMut<T, Dyn, B1> {
+        self.as_view_mut().diagonal_mut()
+    }
+
+
+    /// Iterates over the rows of this matrix. This is named `row_iter` rather than `rows` so as
+    /// not to shadow `Matrix::rows`.
+    pub fn row_iter(&self) -> RowIter<T, M, N> {
+        self.as_view().row_iter()
+    }
+
+
+    /// Iterates over the columns of this matrix. See `row_iter`.
+    pub fn col_iter(&self) -> ColIter<T, M, N> {
+        self.as_view().col_iter()
+    }
+
+
+    pub fn split_at_row(&self, k: usize) -> (MatView<T, Dyn, N>, MatView<T, Dyn, N>) {
+        self.as_view().split_at_row(k)
+    }
+
+
+    pub fn split_at_row_mut(&mut self, k: usize) -> (MatViewMut<T, Dyn, N>, MatViewMut<T, Dyn, N>) {
+        self.as_view_mut().split_at_row_mut(k)
+    }
+
+
+    pub fn split_at_col(&self, k: usize) -> (MatView<T, M, Dyn>, MatView<T, M, Dyn>) {
+        self.as_view().split_at_col(k)
+    }
+
+
+    pub fn split_at_col_mut(&mut self, k: usize) -> (MatViewMut<T, M, Dyn>, MatViewMut<T, M, Dyn>) {
+        self.as_view_mut().split_at_col_mut(k)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linalg::{Column, Row};
+
+    #[test]
+    fn view_block_column_major() {
+        let a = Mat![#column [0, 1, 2], [3, 4, 5], [6, 7, 8]];
+        let v = a.view(1, 1, B2::as_data(), B2::as_data());
+
+        assert_eq!(v[[0, 0]], 4);
+        assert_eq!(v[[0, 1]], 5);
+        assert_eq!(v[[1, 0]], 7);
+        assert_eq!(v[[1, 1]], 8);
+    }
+
+
+    #[test]
+    fn view_block_row_major() {
+        let a = Mat![#row [0, 1, 2], [3, 4, 5], [6, 7, 8]];
+        let v = a.view(0, 1, B3::as_data(), B1::as_data());
+
+        assert_eq!(v.to_mat::<Column>(), Mat![[1], [4], [7]]);
+    }
+
+
+    #[test]
+    fn view_diagonal() {
+        let a = Mat![#row [0, 1, 2], [3, 4, 5]];
+        let d = a.diagonal();
+
+        assert_eq!(d.rows(), Dyn(2));
+        assert_eq!(d[[0, 0]], 0);
+        assert_eq!(d[[1, 0]], 4);
+    }
+
+
+    #[test]
+    fn view_split_at_row_mut_is_disjoint() {
+        let mut a = Mat![#column [0, 1], [2, 3], [4, 5]];
+
+        {
+            let (mut top, mut bottom) = a.split_at_row_mut(1);
+            top.row_mul_mut(0, &10);
+            bottom.row_switch_mut(0, 1);
+        }
+
+        assert_eq!(a, Mat![#column [0, 10], [4, 5], [2, 3]]);
+    }
+
+
+    #[test]
+    fn view_row_and_col_iter() {
+        let a = Mat![#column [0, 1, 2], [3, 4, 5]];
+
+        let row_sums: Vec<i32> = a.row_iter().map(|r| r.iter().cloned().sum()).collect();
+        let col_sums: Vec<i32> = a.col_iter().map(|c| c.iter().cloned().sum()).collect();
+
+        assert_eq!(row_sums, vec![3, 12]);
+        assert_eq!(col_sums, vec![3, 5, 7]);
+    }
+
+
+    #[test]
+    fn view_row_mut_writes_through() {
+        let mut a = Mat![#row [0, 1], [2, 3]];
+        a.col_mut(1)[[1, 0]] = 9;
+
+        assert_eq!(a.row(1).to_mat::<Row>(), Mat![#row [2, 9]]);
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use linalg::Scalar;
+use num::traits::Float;
+
+
+/// A complex number `re + im i`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Complex<T: Scalar> {
+    pub re: T,
+    pub im: T,
+}
+
+
+impl<T: Scalar> Complex<T> {
+    pub fn new(re: T, im: T) -> Self {
+        Complex { re: re, im: im }
+    }
+
+
+    pub fn real(re: T) -> Self {
+        Complex::new(re, T::zero())
+    }
+
+
+    pub fn i() -> Self {
+        Complex::new(T::zero(), T::one())
+    }
+
+
+    pub fn conj(&self) -> Self {
+        Complex::new(self.re.clone(), -self.im.clone())
+    }
+
+
+    /// The squared magnitude `re^2 + im^2`.
+    pub fn norm_sqr(&self) -> T {
+        self.re.clone() * self.re.clone() + self.im.clone() * self.im.clone()
+    }
+
+
+    pub fn scale(&self, k: T) -> Self {
+        Complex::new(self.re.clone() * k.clone(), self.im.clone() * k)
+    }
+}
+
+
+impl<T: Scalar + Float> Complex<T> {
+    /// The magnitude, computed without undue overflow or underflow.
+    pub fn norm(&self) -> T {
+        let (re, im) = (self.re.abs(), self.im.abs());
+        let (big, small) = if re > im { (re, im) } else { (im, re) };
+
+        if big.eq_zero() {
+            return big;
+        }
+
+        let ratio = small / big.clone();
+        big * (T::one() + ratio.clone() * ratio).sqrt()
+    }
+
+
+    /// The argument, in `(-pi, pi]`.
+    pub fn arg(&self) -> T {
+        self.im.clone().atan2(self.re.clone())
+    }
+
+
+    /// The principal square root, with a non-negative real part.
+    pub fn sqrt(&self) -> Self {
+        let two = T::one() + T::one();
+        let re = ((self.norm() + self.re.abs()) / two.clone()).sqrt();
+
+        if re.eq_zero() {
+            return Complex::real(T::zero());
+        }
+
+        let im = self.im.clone() / (two * re.clone());
+
+        if self.re >= T::zero() {
+            Complex::new(re, im)
+        } else if self.im >= T::zero() {
+            Complex::new(im.abs(), re)
+        } else {
+            Complex::new(im.abs(), -re)
+        }
+    }
+}
+
+
+impl<T: Scalar + Display> Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.im < T::zero() {
+            write!(f, "{} - {}i", self.re, self.im.abs())
+        } else {
+            write!(f, "{} + {}i", self.re, self.im)
+        }
+    }
+}
+
+
+impl<T: Scalar> Add for Complex<T> {
+    type Output = Complex<T>;
+
+    fn add(self, rhs: Complex<T>) -> Complex<T> {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+
+impl<T: Scalar> Sub for Complex<T> {
+    type Output = Complex<T>;
+
+    fn sub(self, rhs: Complex<T>) -> Complex<T> {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+
+impl<T: Scalar> Mul for Complex<T> {
+    type Output = Complex<T>;
+
+    fn mul(self, rhs: Complex<T>) -> Complex<T> {
+        Complex::new(self.re.clone() * rhs.re.clone() - self.im.clone() * rhs.im.clone(),
+                     self.re * rhs.im + self.im * rhs.re)
+    }
+}
+
+
+impl<T: Scalar> Div for Complex<T> {
+    type Output = Complex<T>;
+
+    /// Divides using Smith's algorithm, which avoids overflow when `rhs` is large.
+    fn div(self, rhs: Complex<T>) -> Complex<T> {
+        if rhs.re.abs() >= rhs.im.abs() {
+            let ratio = rhs.im.clone() / rhs.re.clone();
+            let denominator = rhs.re + rhs.im * ratio.clone();
+
+            Complex::new((self.re.clone() + self.im.clone() * ratio.clone()) / denominator.clone(),
+                         (self.im - self.re * ratio) / denominator)
+        } else {
+            let ratio = rhs.re.clone() / rhs.im.clone();
+            let denominator = rhs.re * ratio.clone() + rhs.im;
+
+            Complex::new((self.re.clone() * ratio.clone() + self.im.clone()) / denominator.clone(),
+                         (self.im * ratio - self.re) / denominator)
+        }
+    }
+}
+
+
+impl<T: Scalar> Neg for Complex<T> {
+    type Output = Complex<T>;
+
+    fn neg(self) -> Complex<T> {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complex_arithmetic() {
+        let a = Complex::new(3., 4.);
+        let b = Complex::new(1., -2.);
+
+        assert_eq!(a * b, Complex::new(11., -2.));
+        assert_eq!(a * b / b, a);
+        assert_eq!(a.norm(), 5.);
+        assert_eq!(a.sqrt(), Complex::new(2., 1.));
+        assert_eq!(Complex::new(-4., 0.).sqrt(), Complex::new(0., 2.));
+        assert_eq!(Complex::new(-3., -4.).sqrt(), Complex::new(1., -2.));
+        assert_eq!(format!("{}", b), "1 - 2i");
+    }
+}
@@ -0,0 +1,661 @@
+//! Interpolation of one-dimensional data: cubic splines with natural, clamped and not-a-knot
+//! boundaries, Akima splines, and interpolating polynomials in Lagrange and Newton form.
+//!
+//! The splines take samples `(xs[i], ys[i])` with strictly increasing `xs`, and extrapolate past
+//! either end using the cubic of the nearest interval. The polynomials accept distinct `xs` in any
+//! order. Everything implements `Interpolant`, and so may be evaluated and differentiated.
+
+use linalg::Scalar;
+use num::traits::Float;
+
+
+/// A function interpolating some data, which can be evaluated and differentiated anywhere.
+pub trait Interpolant {
+    type Scalar: Scalar + Float;
+
+    fn eval(&self, x: Self::Scalar) -> Self::Scalar;
+
+    fn derivative(&self, x: Self::Scalar) -> Self::Scalar;
+}
+
+
+/// Whether `xs` and `ys` are the same length, hold at least two samples, and `xs` is strictly
+/// increasing.
+fn check_samples<T: Scalar>(xs: &[T], ys: &[T]) -> bool {
+    xs.len() == ys.len() && xs.len() >= 2 && xs.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+
+/// The index `i` of the interval `xs[i]..xs[i + 1]` containing `x`, clamped to the first and last
+/// intervals.
+fn interval<T: PartialOrd>(xs: &[T], x: &T) -> usize {
+    let (mut lo, mut hi) = (0, xs.len() - 1);
+
+    while hi - lo > 1 {
+        let mid = (lo + hi) / 2;
+
+        if *x < xs[mid] {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    lo
+}
+
+
+/// Solves a tridiagonal system in O(n) time with the Thomas algorithm. Row `i` of the system is
+/// `sub[i] * x[i - 1] + diag[i] * x[i] + sup[i] * x[i + 1] = rhs[i]`, so `sub[0]` and the last
+/// element of `sup` are ignored. There is no pivoting, so the system should be diagonally dominant
+/// or otherwise safe to eliminate in order.
+fn solve_tridiagonal<T: Scalar>(sub: &[T], mut diag: Vec<T>, sup: &[T], mut rhs: Vec<T>) -> Vec<T> {
+    let n = diag.len();
+
+    for i in 1..n {
+        let factor = sub[i].clone() / diag[i - 1].clone();
+        let (d, r) = (sup[i - 1].clone(), rhs[i - 1].clone());
+
+        diag[i] -= factor.clone() * d;
+        rhs[i] -= factor * r;
+    }
+
+    let last = rhs[n - 1].clone() / diag[n - 1].clone();
+    rhs[n - 1] = last;
+
+    for i in (0..n - 1).rev() {
+        let x = (rhs[i].clone() - sup[i].clone() * rhs[i + 1].clone()) / diag[i].clone();
+        rhs[i] = x;
+    }
+
+    rhs
+}
+
+
+/// The boundary conditions of a cubic spline, which pin down the two degrees of freedom left over
+/// once it is required to be twice continuously differentiable.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Boundary<T> {
+    /// A second derivative of zero at both ends.
+    Natural,
+
+    /// The given first derivatives at the first and last samples.
+    Clamped(T, T),
+
+    /// A continuous third derivative at the second and second-to-last samples, so that the first
+    /// two and last two intervals are each spanned by a single cubic. With three samples or fewer
+    /// this is the interpolating polynomial.
+    NotAKnot,
+}
+
+
+/// A piecewise cubic interpolant, stored as the value and first derivative at each sample. Over
+/// each interval it is the cubic Hermite interpolant of its ends.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CubicSpline<T: Scalar> {
+    xs: Vec<T>,
+    ys: Vec<T>,
+    slopes: Vec<T>,
+}
+
+
+impl<T: Scalar + Float> CubicSpline<T> {
+    /// The cubic spline through the samples with the given boundary conditions, or `None` unless
+    /// there are at least two samples, as many `xs` as `ys`, and the `xs` strictly increase.
+    pub fn new(xs: Vec<T>, ys: Vec<T>, boundary: Boundary<T>) -> Option<Self> {
+        if !check_samples(&xs, &ys) {
+            return None;
+        }
+
+        let n = xs.len() - 1;
+        let (two, three) = (T::from_usize(2), T::from_usize(3));
+        let h: Vec<T> = xs.windows(2).map(|pair| pair[1].clone() - pair[0].clone()).collect();
+        let d: Vec<T> = (0..n)
+            .map(|i| (ys[i + 1].clone() - ys[i].clone()) / h[i].clone())
+            .collect();
+
+        let slopes = match boundary {
+            Boundary::NotAKnot if n == 1 => vec![d[0].clone(); 2],
+
+            // A single parabola, whose slope at the middle of each interval is that interval's.
+            Boundary::NotAKnot if n == 2 => {
+                let curvature = (d[1].clone() - d[0].clone()) / (h[0].clone() + h[1].clone());
+
+                vec![d[0].clone() - curvature.clone() * h[0].clone(),
+                     d[0].clone() + curvature.clone() * h[0].clone(),
+                     d[1].clone() + curvature * h[1].clone()]
+            }
+
+            _ => {
+                // Continuity of the second derivative at each interior sample gives one row of a
+                // tridiagonal system in the slopes, and the boundary conditions give the first
+                // and last rows.
+                let mut sub = vec![T::zero(); n + 1];
+                let mut diag = vec![T::zero(); n + 1];
+                let mut sup = vec![T::zero(); n + 1];
+                let mut rhs = vec![T::zero(); n + 1];
+
+                for i in 1..n {
+                    sub[i] = h[i].clone();
+                    diag[i] = two.clone() * (h[i - 1].clone() + h[i].clone());
+                    sup[i] = h[i - 1].clone();
+                    rhs[i] = three.clone() * (h[i].clone() * d[i - 1].clone() +
+                                              h[i - 1].clone() * d[i].clone());
+                }
+
+                match boundary {
+                    Boundary::Natural => {
+                        diag[0] = two.clone();
+                        sup[0] = T::one();
+                        rhs[0] = three.clone() * d[0].clone();
+
+                        sub[n] = T::one();
+                        diag[n] = two;
+                        rhs[n] = three * d[n - 1].clone();
+                    }
+
+                    Boundary::Clamped(start, end) => {
+                        diag[0] = T::one();
+                        rhs[0] = start;
+
+                        diag[n] = T::one();
+                        rhs[n] = end;
+                    }
+
+                    Boundary::NotAKnot => {
+                        let (h0, h1) = (h[0].clone(), h[1].clone());
+                        let span = h0.clone() + h1.clone();
+
+                        diag[0] = h1.clone();
+                        sup[0] = span.clone();
+                        rhs[0] = ((h0.clone() + two.clone() * span.clone()) * h1 * d[0].clone() +
+                                  h0.clone() * h0 * d[1].clone()) / span;
+
+                        let (h0, h1) = (h[n - 2].clone(), h[n - 1].clone());
+                        let span = h0.clone() + h1.clone();
+
+                        sub[n] = span.clone();
+                        diag[n] = h0.clone();
+                        rhs[n] = (h1.clone() * h1.clone() * d[n - 2].clone() +
+                                  (two * span.clone() + h1) * h0 * d[n - 1].clone()) / span;
+                    }
+                }
+
+                solve_tridiagonal(&sub, diag, &sup, rhs)
+            }
+        };
+
+        Some(CubicSpline {
+            xs: xs,
+            ys: ys,
+            slopes: slopes,
+        })
+    }
+
+
+    /// The Akima spline through the samples, or `None` under the same conditions as `new`. Akima
+    /// splines are only once continuously differentiable, but choose each slope from the nearby
+    /// samples alone, so they do not overshoot or ring around outliers and sudden steps.
+    pub fn akima(xs: Vec<T>, ys: Vec<T>) -> Option<Self> {
+        if !check_samples(&xs, &ys) {
+            return None;
+        }
+
+        let n = xs.len() - 1;
+        let two = T::from_usize(2);
+
+        // The slopes of the intervals, extended by two more at each end by linear extrapolation.
+        let mut m = vec![T::zero(); n + 4];
+
+        for i in 0..n {
+            m[i + 2] = (ys[i + 1].clone() - ys[i].clone()) / (xs[i + 1].clone() - xs[i].clone());
+        }
+
+        if n == 1 {
+            let slope = m[2].clone();
+
+            return Some(CubicSpline {
+                xs: xs,
+                ys: ys,
+                slopes: vec![slope; 2],
+            });
+        }
+
+        m[1] = two.clone() * m[2].clone() - m[3].clone();
+        m[0] = two.clone() * m[1].clone() - m[2].clone();
+        m[n + 2] = two.clone() * m[n + 1].clone() - m[n].clone();
+        m[n + 3] = two.clone() * m[n + 2].clone() - m[n + 1].clone();
+
+        // The slope at each sample is a weighted average of the slopes of the intervals either
+        // side of it, favouring whichever side is more nearly straight.
+        let slopes = (0..n + 1)
+            .map(|i| {
+                let left = (m[i + 3].clone() - m[i + 2].clone()).abs();
+                let right = (m[i + 1].clone() - m[i].clone()).abs();
+                let total = left.clone() + right.clone();
+
+                if total.eq_zero() {
+                    (m[i + 1].clone() + m[i + 2].clone()) / two.clone()
+                } else {
+                    (left * m[i + 1].clone() + right * m[i + 2].clone()) / total
+                }
+            })
+            .collect();
+
+        Some(CubicSpline {
+            xs: xs,
+            ys: ys,
+            slopes: slopes,
+        })
+    }
+
+
+    /// The piecewise cubic Hermite interpolant with the given slopes at each sample, or `None`
+    /// under the same conditions as `new` or if there are not as many slopes as samples.
+    pub fn from_slopes(xs: Vec<T>, ys: Vec<T>, slopes: Vec<T>) -> Option<Self> {
+        if !check_samples(&xs, &ys) || slopes.len() != xs.len() {
+            return None;
+        }
+
+        Some(CubicSpline {
+            xs: xs,
+            ys: ys,
+            slopes: slopes,
+        })
+    }
+
+
+    pub fn xs(&self) -> &[T] {
+        &self.xs
+    }
+
+
+    pub fn ys(&self) -> &[T] {
+        &self.ys
+    }
+
+
+    /// The first derivative of the spline at each sample.
+    pub fn slopes(&self) -> &[T] {
+        &self.slopes
+    }
+
+
+    /// The interval containing `x`, its width, and `x` relative to it.
+    fn locate(&self, x: T) -> (usize, T, T) {
+        let i = interval(&self.xs, &x);
+        let h = self.xs[i + 1].clone() - self.xs[i].clone();
+        let t = (x - self.xs[i].clone()) / h.clone();
+
+        (i, h, t)
+    }
+
+
+    pub fn second_derivative(&self, x: T) -> T {
+        let (i, h, t) = self.locate(x);
+        let (two, four, six, twelve) =
+            (T::from_usize(2), T::from_usize(4), T::from_usize(6), T::from_usize(12));
+
+        let values = (twelve * t.clone() - six.clone()) *
+                     (self.ys[i].clone() - self.ys[i + 1].clone()) / (h.clone() * h.clone());
+        let slopes = ((six.clone() * t.clone() - four) * self.slopes[i].clone() +
+                      (six * t - two) * self.slopes[i + 1].clone()) / h;
+
+        values + slopes
+    }
+}
+
+
+impl<T: Scalar + Float> Interpolant for CubicSpline<T> {
+    type Scalar = T;
+
+    fn eval(&self, x: T) -> T {
+        let (i, h, t) = self.locate(x);
+        let (one, two, three) = (T::one(), T::from_usize(2), T::from_usize(3));
+        let t2 = t.clone() * t.clone();
+        let t3 = t2.clone() * t.clone();
+
+        (two.clone() * t3.clone() - three.clone() * t2.clone() + one) * self.ys[i].clone() +
+        (t3.clone() - two.clone() * t2.clone() + t) * h.clone() * self.slopes[i].clone() +
+        (three * t2.clone() - two * t3.clone()) * self.ys[i + 1].clone() +
+        (t3 - t2) * h * self.slopes[i + 1].clone()
+    }
+
+
+    fn derivative(&self, x: T) -> T {
+        let (i, h, t) = self.locate(x);
+        let (one, two, three, four, six) =
+            (T::one(), T::from_usize(2), T::from_usize(3), T::from_usize(4), T::from_usize(6));
+        let t2 = t.clone() * t.clone();
+
+        (six * (t2.clone() - t.clone())) * (self.ys[i].clone() - self.ys[i + 1].clone()) / h +
+        (three.clone() * t2.clone() - four * t.clone() + one) * self.slopes[i].clone() +
+        (three * t2 - two * t) * self.slopes[i + 1].clone()
+    }
+}
+
+
+/// The interpolating polynomial through a set of samples in barycentric Lagrange form, which
+/// evaluates in O(n) time and is numerically stable.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Lagrange<T: Scalar> {
+    xs: Vec<T>,
+    ys: Vec<T>,
+    weights: Vec<T>,
+}
+
+
+impl<T: Scalar + Float> Lagrange<T> {
+    /// The polynomial of degree at most `xs.len() - 1` through the samples, or `None` if there are
+    /// none, the `xs` and `ys` differ in length, or the `xs` are not distinct.
+    pub fn new(xs: Vec<T>, ys: Vec<T>) -> Option<Self> {
+        if xs.is_empty() || xs.len() != ys.len() {
+            return None;
+        }
+
+        let mut weights = Vec::with_capacity(xs.len());
+
+        for (j, x_j) in xs.iter().enumerate() {
+            let mut product = T::one();
+
+            for (k, x_k) in xs.iter().enumerate().filter(|&(k, _)| k != j) {
+                let difference = x_j.clone() - x_k.clone();
+
+                if difference == T::zero() {
+                    return None;
+                }
+
+                product *= difference;
+            }
+
+            weights.push(product.recip());
+        }
+
+        Some(Lagrange {
+            xs: xs,
+            ys: ys,
+            weights: weights,
+        })
+    }
+
+
+    pub fn xs(&self) -> &[T] {
+        &self.xs
+    }
+
+
+    pub fn ys(&self) -> &[T] {
+        &self.ys
+    }
+
+
+    /// The index of the sample at exactly `x`, if there is one.
+    fn node(&self, x: &T) -> Option<usize> {
+        self.xs.iter().position(|x_j| x_j == x)
+    }
+}
+
+
+impl<T: Scalar + Float> Interpolant for Lagrange<T> {
+    type Scalar = T;
+
+    fn eval(&self, x: T) -> T {
+        if let Some(i) = self.node(&x) {
+            return self.ys[i].clone();
+        }
+
+        let mut numerator = T::zero();
+        let mut denominator = T::zero();
+
+        for ((x_j, y_j), w_j) in self.xs.iter().zip(&self.ys).zip(&self.weights) {
+            let term = w_j.clone() / (x.clone() - x_j.clone());
+
+            numerator += term.clone() * y_j.clone();
+            denominator += term;
+        }
+
+        numerator / denominator
+    }
+
+
+    fn derivative(&self, x: T) -> T {
+        if let Some(i) = self.node(&x) {
+            let (x_i, y_i, w_i) = (&self.xs[i], &self.ys[i], &self.weights[i]);
+
+            return (0..self.xs.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    self.weights[j].clone() / w_i.clone() * (self.ys[j].clone() - y_i.clone()) /
+                    (x_i.clone() - self.xs[j].clone())
+                })
+                .sum();
+        }
+
+        let p = self.eval(x.clone());
+        let mut numerator = T::zero();
+        let mut denominator = T::zero();
+
+        for ((x_j, y_j), w_j) in self.xs.iter().zip(&self.ys).zip(&self.weights) {
+            let term = w_j.clone() / (x.clone() - x_j.clone());
+
+            numerator += term.clone() * (p.clone() - y_j.clone()) / (x.clone() - x_j.clone());
+            denominator += term;
+        }
+
+        numerator / denominator
+    }
+}
+
+
+/// The interpolating polynomial through a set of samples in Newton form, built from divided
+/// differences. Samples can be added one at a time in O(n) time each.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Newton<T: Scalar> {
+    xs: Vec<T>,
+    coefficients: Vec<T>,
+
+    // The divided differences ending at the most recent sample, `f[x_n]`, `f[x_{n-1}, x_n]`, and
+    // so on up to `f[x_0, ..., x_n]`.
+    diagonal: Vec<T>,
+}
+
+
+impl<T: Scalar + Float> Newton<T> {
+    /// The polynomial of degree at most `xs.len() - 1` through the samples, or `None` if there are
+    /// none, the `xs` and `ys` differ in length, or the `xs` are not distinct.
+    pub fn new(xs: Vec<T>, ys: Vec<T>) -> Option<Self> {
+        if xs.is_empty() || xs.len() != ys.len() ||
+           xs.iter().enumerate().any(|(j, x)| xs[..j].contains(x)) {
+            return None;
+        }
+
+        let mut newton = Newton {
+            xs: Vec::with_capacity(xs.len()),
+            coefficients: Vec::with_capacity(xs.len()),
+            diagonal: Vec::with_capacity(xs.len()),
+        };
+
+        for (x, y) in xs.into_iter().zip(ys) {
+            newton.push(x, y);
+        }
+
+        Some(newton)
+    }
+
+
+    /// Adds a sample, raising the degree of the polynomial by one. Panics if there is already a
+    /// sample at `x`.
+    pub fn push(&mut self, x: T, y: T) {
+        assert!(!self.xs.contains(&x),
+                "Newton interpolation requires distinct sample points!");
+
+        let n = self.xs.len();
+        let mut diagonal = Vec::with_capacity(n + 1);
+        diagonal.push(y);
+
+        for k in 0..n {
+            let difference = (diagonal[k].clone() - self.diagonal[k].clone()) /
+                             (x.clone() - self.xs[n - 1 - k].clone());
+            diagonal.push(difference);
+        }
+
+        self.coefficients.push(diagonal[n].clone());
+        self.xs.push(x);
+        self.diagonal = diagonal;
+    }
+
+
+    pub fn xs(&self) -> &[T] {
+        &self.xs
+    }
+
+
+    /// The coefficients `c` of the Newton form
+    /// `c[0] + c[1] (x - x[0]) + c[2] (x - x[0]) (x - x[1]) + ...`.
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+}
+
+
+impl<T: Scalar + Float> Interpolant for Newton<T> {
+    type Scalar = T;
+
+    fn eval(&self, x: T) -> T {
+        let n = self.coefficients.len();
+
+        (0..n - 1).rev().fold(self.coefficients[n - 1].clone(), |p, k| {
+            p * (x.clone() - self.xs[k].clone()) + self.coefficients[k].clone()
+        })
+    }
+
+
+    fn derivative(&self, x: T) -> T {
+        let n = self.coefficients.len();
+        let mut p = self.coefficients[n - 1].clone();
+        let mut dp = T::zero();
+
+        for k in (0..n - 1).rev() {
+            let offset = x.clone() - self.xs[k].clone();
+
+            dp = dp * offset.clone() + p.clone();
+            p = p * offset + self.coefficients[k].clone();
+        }
+
+        dp
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    fn cubic(x: f64) -> f64 {
+        x * x * x - 2. * x * x + 0.5 * x + 1.
+    }
+
+    fn cubic_derivative(x: f64) -> f64 {
+        3. * x * x - 4. * x + 0.5
+    }
+
+    #[test]
+    fn cubic_splines_reproduce_cubics() {
+        let xs = vec![-1., -0.2, 0.5, 1.1, 2., 3.];
+        let ys: Vec<f64> = xs.iter().cloned().map(cubic).collect();
+
+        let clamped = CubicSpline::new(xs.clone(),
+                                       ys.clone(),
+                                       Boundary::Clamped(cubic_derivative(-1.),
+                                                         cubic_derivative(3.)))
+            .unwrap();
+        let not_a_knot = CubicSpline::new(xs.clone(), ys.clone(), Boundary::NotAKnot).unwrap();
+
+        for &x in &[-1., -0.7, 0., 0.5, 1.6, 2.9, 3.] {
+            assert_close(clamped.eval(x), cubic(x));
+            assert_close(clamped.derivative(x), cubic_derivative(x));
+            assert_close(clamped.second_derivative(x), 6. * x - 4.);
+
+            assert_close(not_a_knot.eval(x), cubic(x));
+            assert_close(not_a_knot.derivative(x), cubic_derivative(x));
+        }
+    }
+
+    #[test]
+    fn natural_spline() {
+        let xs = vec![0., 1., 2.5, 3., 4.];
+        let ys = vec![1., 3., 2., 0., 1.];
+        let spline = CubicSpline::new(xs.clone(), ys.clone(), Boundary::Natural).unwrap();
+
+        for (&x, &y) in xs.iter().zip(&ys) {
+            assert_close(spline.eval(x), y);
+        }
+
+        assert_close(spline.second_derivative(0.), 0.);
+        assert_close(spline.second_derivative(4.), 0.);
+
+        // The second derivative is continuous at the interior samples.
+        for &x in &xs[1..4] {
+            assert!((spline.second_derivative(x - 1e-9) - spline.second_derivative(x + 1e-9))
+                .abs() < 1e-6);
+        }
+
+        assert!(CubicSpline::new(vec![0., 0.], vec![1., 2.], Boundary::Natural).is_none());
+        assert!(CubicSpline::new(vec![0.], vec![1.], Boundary::Natural).is_none());
+    }
+
+    #[test]
+    fn not_a_knot_with_three_points_is_a_parabola() {
+        let spline = CubicSpline::new(vec![0., 1., 3.], vec![0., 1., 9.], Boundary::NotAKnot)
+            .unwrap();
+
+        for &x in &[-1., 0.5, 2., 4.] {
+            assert_close(spline.eval(x), x * x);
+            assert_close(spline.derivative(x), 2. * x);
+        }
+    }
+
+    #[test]
+    fn akima_spline() {
+        let xs: Vec<f64> = (0..8).map(|i| i as f64).collect();
+        let line: Vec<f64> = xs.iter().map(|x| 2. * x - 1.).collect();
+        let spline = CubicSpline::akima(xs.clone(), line).unwrap();
+
+        assert_close(spline.eval(3.3), 5.6);
+        assert_close(spline.derivative(6.5), 2.);
+
+        // A step stays flat on either side rather than overshooting.
+        let step = vec![0., 0., 0., 0., 1., 1., 1., 1.];
+        let spline = CubicSpline::akima(xs, step).unwrap();
+
+        assert_close(spline.eval(1.5), 0.);
+        assert_close(spline.eval(5.5), 1.);
+        assert!((0..71)
+            .map(|i| spline.eval(i as f64 / 10.))
+            .all(|y| -1e-12 <= y && y <= 1. + 1e-12));
+    }
+
+    #[test]
+    fn interpolating_polynomials() {
+        let xs = vec![2., -1., 0.5, 3.];
+        let ys: Vec<f64> = xs.iter().cloned().map(cubic).collect();
+
+        let lagrange = Lagrange::new(xs.clone(), ys.clone()).unwrap();
+        let mut newton = Newton::new(xs[..3].to_vec(), ys[..3].to_vec()).unwrap();
+        newton.push(xs[3], ys[3]);
+
+        for &x in &[-2., -1., 0., 0.5, 1.7, 3., 4.] {
+            assert_close(lagrange.eval(x), cubic(x));
+            assert_close(lagrange.derivative(x), cubic_derivative(x));
+            assert_close(newton.eval(x), cubic(x));
+            assert_close(newton.derivative(x), cubic_derivative(x));
+        }
+
+        assert!(Lagrange::new(vec![1., 1.], vec![0., 1.]).is_none());
+        assert!(Newton::new(vec![1., 2., 1.], vec![0., 1., 2.]).is_none());
+    }
+}
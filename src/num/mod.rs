@@ -1 +1,9 @@
 pub mod traits;
+pub mod interpolate;
+pub mod roots;
+
+pub mod complex;
+pub use self::complex::*;
+
+pub mod polynomial;
+pub use self::polynomial::*;
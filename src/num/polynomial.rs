@@ -0,0 +1,914 @@
+//! Polynomials in one variable, and their roots.
+//!
+//! `Polynomial::roots` finds every root, real or complex, as the eigenvalues of the polynomial's
+//! companion matrix. When only the real roots of a low-degree polynomial are wanted, as is usual
+//! for time-of-impact and ballistics problems, `solve_quadratic`, `solve_cubic` and
+//! `solve_quartic` find them directly in closed form.
+
+use std::cmp::{self, Ordering};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use linalg::{Mat, Matrix, Scalar};
+use num::complex::Complex;
+use num::traits::Float;
+use typehack::dim::Dyn;
+
+
+/// The most QR iterations spent looking for any one eigenvalue of a companion matrix.
+const MAX_QR_ITERATIONS: usize = 30;
+
+/// The number of Newton iterations used to polish each root found from the companion matrix.
+const POLISH_ITERATIONS: usize = 3;
+
+
+/// A polynomial `c[0] + c[1] x + c[2] x^2 + ...`, stored by its coefficients in order of
+/// increasing degree. The coefficients never end in a zero, so the zero polynomial has none.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Polynomial<T: Scalar> {
+    coefficients: Vec<T>,
+}
+
+
+impl<T: Scalar> Polynomial<T> {
+    /// The polynomial with the given coefficients, in order of increasing degree. Trailing zero
+    /// coefficients are dropped.
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last().map_or(false, |c| *c == T::zero()) {
+            coefficients.pop();
+        }
+
+        Polynomial { coefficients: coefficients }
+    }
+
+
+    pub fn zero() -> Self {
+        Polynomial { coefficients: Vec::new() }
+    }
+
+
+    pub fn constant(c: T) -> Self {
+        Polynomial::new(vec![c])
+    }
+
+
+    /// The polynomial `x`.
+    pub fn x() -> Self {
+        Polynomial::new(vec![T::zero(), T::one()])
+    }
+
+
+    /// The monic polynomial whose roots are exactly `roots`, `(x - roots[0]) (x - roots[1]) ...`.
+    pub fn from_roots(roots: &[T]) -> Self {
+        roots.iter().fold(Polynomial::constant(T::one()), |product, root| {
+            &product * &Polynomial::new(vec![-root.clone(), T::one()])
+        })
+    }
+
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+
+    pub fn into_coefficients(self) -> Vec<T> {
+        self.coefficients
+    }
+
+
+    /// The degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+
+    /// The coefficient of the highest power of `x`, or zero for the zero polynomial.
+    pub fn leading_coefficient(&self) -> T {
+        self.coefficients.last().cloned().unwrap_or_else(T::zero)
+    }
+
+
+    /// Evaluates the polynomial at `x` using Horner's method.
+    pub fn eval(&self, x: T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::zero(), |acc, c| acc * x.clone() + c.clone())
+    }
+
+
+    /// Evaluates the polynomial at a complex `z` using Horner's method.
+    pub fn eval_complex(&self, z: Complex<T>) -> Complex<T> {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Complex::real(T::zero()),
+                  |acc, c| acc * z.clone() + Complex::real(c.clone()))
+    }
+
+
+    pub fn derivative(&self) -> Self {
+        Polynomial::new(self.coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| c.clone() * T::from_usize(i))
+            .collect())
+    }
+
+
+    /// The antiderivative of the polynomial which is `constant` at zero.
+    pub fn integral(&self, constant: T) -> Self {
+        Polynomial::new(Some(constant)
+            .into_iter()
+            .chain(self.coefficients
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c.clone() / T::from_usize(i + 1)))
+            .collect())
+    }
+
+
+    /// The composition `self(inner(x))`.
+    pub fn compose(&self, inner: &Self) -> Self {
+        self.coefficients.iter().rev().fold(Polynomial::zero(), |acc, c| {
+            &(&acc * inner) + &Polynomial::constant(c.clone())
+        })
+    }
+
+
+    /// Divides by `divisor`, returning the quotient and remainder, or `None` if `divisor` is the
+    /// zero polynomial. The remainder has a lower degree than `divisor`.
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        let d = match divisor.degree() {
+            Some(d) => d,
+            None => return None,
+        };
+
+        let n = match self.degree() {
+            Some(n) if n >= d => n,
+            _ => return Some((Polynomial::zero(), self.clone())),
+        };
+
+        let lead = divisor.leading_coefficient();
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![T::zero(); n - d + 1];
+
+        for k in (0..n - d + 1).rev() {
+            let q = remainder[k + d].clone() / lead.clone();
+
+            for (j, c) in divisor.coefficients.iter().enumerate() {
+                remainder[k + j] -= q.clone() * c.clone();
+            }
+
+            quotient[k] = q;
+        }
+
+        remainder.truncate(d);
+
+        Some((Polynomial::new(quotient), Polynomial::new(remainder)))
+    }
+
+
+    /// The companion matrix of the polynomial, whose eigenvalues are its roots, or `None` if the
+    /// polynomial is constant. It is already in upper Hessenberg form.
+    pub fn companion(&self) -> Option<Mat<T, Dyn, Dyn>> {
+        let n = match self.degree() {
+            Some(n) if n > 0 => n,
+            _ => return None,
+        };
+
+        let lead = self.leading_coefficient();
+        let mut companion = Mat::from_elem(Dyn(n), Dyn(n), T::zero());
+
+        for j in 0..n {
+            companion[[0, j]] = -self.coefficients[n - 1 - j].clone() / lead.clone();
+        }
+
+        for i in 1..n {
+            companion[[i, i - 1]] = T::one();
+        }
+
+        Some(companion)
+    }
+}
+
+
+impl<T: Scalar + Float> Polynomial<T> {
+    /// Every root of the polynomial, real or complex, repeated according to multiplicity. Roots at
+    /// zero are found exactly; the rest are the eigenvalues of the companion matrix, found by
+    /// shifted QR iteration and then polished with a few Newton iterations. Returns `None` for the
+    /// zero polynomial, or in the rare case that the QR iteration does not converge.
+    pub fn roots(&self) -> Option<Vec<Complex<T>>> {
+        if self.is_zero() {
+            return None;
+        }
+
+        let zeros = self.coefficients.iter().take_while(|c| **c == T::zero()).count();
+        let reduced = Polynomial::new(self.coefficients[zeros..].to_vec());
+        let mut roots = vec![Complex::real(T::zero()); zeros];
+
+        if let Some(companion) = reduced.companion() {
+            let eigenvalues = match hessenberg_eigenvalues(companion) {
+                Some(eigenvalues) => eigenvalues,
+                None => return None,
+            };
+
+            let derivative = reduced.derivative();
+            roots.extend(eigenvalues.into_iter().map(|z| reduced.polish(&derivative, z)));
+        }
+
+        Some(roots)
+    }
+
+
+    /// The real roots of the polynomial in ascending order, repeated according to multiplicity:
+    /// those roots whose imaginary parts are negligible. Returns `None` under the same conditions
+    /// as `roots`.
+    pub fn real_roots(&self) -> Option<Vec<T>> {
+        let roots = match self.roots() {
+            Some(roots) => roots,
+            None => return None,
+        };
+
+        let tolerance = T::epsilon().sqrt();
+        let mut real: Vec<T> = roots.into_iter()
+            .filter(|z| z.im.abs() <= tolerance.clone() * (T::one() + z.norm()))
+            .map(|z| z.re)
+            .collect();
+
+        real.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        Some(real)
+    }
+
+
+    /// Refines the root `z` with Newton's method, stopping early if a step fails to improve it.
+    fn polish(&self, derivative: &Self, mut z: Complex<T>) -> Complex<T> {
+        let mut residual = self.eval_complex(z.clone()).norm();
+
+        for _ in 0..POLISH_ITERATIONS {
+            let slope = derivative.eval_complex(z.clone());
+
+            if slope.norm().eq_zero() {
+                break;
+            }
+
+            let next = z.clone() - self.eval_complex(z.clone()) / slope;
+            let next_residual = self.eval_complex(next.clone()).norm();
+
+            if next_residual >= residual {
+                break;
+            }
+
+            z = next;
+            residual = next_residual;
+        }
+
+        z
+    }
+}
+
+
+/// Balances a square matrix by diagonal similarity transforms with powers of two, so that its rows
+/// and columns have comparable norms. This preserves the eigenvalues and Hessenberg form while
+/// making them much less sensitive to rounding, which matters for companion matrices.
+fn balance<T: Scalar + Float>(a: &mut Mat<T, Dyn, Dyn>, n: usize) {
+    let radix = T::from_usize(2);
+    let radix_squared = T::from_usize(4);
+    let mut converged = false;
+
+    while !converged {
+        converged = true;
+
+        for i in 0..n {
+            let mut c = T::zero();
+            let mut r = T::zero();
+
+            for j in (0..n).filter(|&j| j != i) {
+                c += a[[j, i]].abs();
+                r += a[[i, j]].abs();
+            }
+
+            if c.eq_zero() || r.eq_zero() {
+                continue;
+            }
+
+            let sum = c.clone() + r.clone();
+            let mut f = T::one();
+
+            let g = r.clone() / radix.clone();
+            while c < g {
+                f *= radix.clone();
+                c *= radix_squared.clone();
+            }
+
+            let g = r.clone() * radix.clone();
+            while c > g {
+                f /= radix.clone();
+                c /= radix_squared.clone();
+            }
+
+            if (c + r) / f.clone() < T::from_f64(0.95) * sum {
+                converged = false;
+
+                let g = f.clone().recip();
+
+                for j in 0..n {
+                    a[[i, j]] *= g.clone();
+                    a[[j, i]] *= f.clone();
+                }
+            }
+        }
+    }
+}
+
+
+/// The eigenvalues of an upper Hessenberg matrix, found by the Francis double-shift QR algorithm
+/// as in EISPACK's `hqr`. Returns `None` if some eigenvalue does not converge.
+fn hessenberg_eigenvalues<T: Scalar + Float>(mut a: Mat<T, Dyn, Dyn>) -> Option<Vec<Complex<T>>> {
+    let n = a.rows().0;
+
+    balance(&mut a, n);
+
+    // The algorithm is most naturally written with indices starting from one.
+    let at = |i: usize, j: usize| [i - 1, j - 1];
+
+    let mut wr = vec![T::zero(); n + 1];
+    let mut wi = vec![T::zero(); n + 1];
+
+    let mut norm = T::zero();
+    for i in 1..n + 1 {
+        for j in cmp::max(i - 1, 1)..n + 1 {
+            norm += a[at(i, j)].abs();
+        }
+    }
+
+    let mut nn = n;
+    let mut shift = T::zero();
+
+    while nn >= 1 {
+        let mut its = 0;
+
+        loop {
+            // Look for a single small subdiagonal element, splitting the matrix there.
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[at(l - 1, l - 1)].abs() + a[at(l, l)].abs();
+                if s.eq_zero() {
+                    s = norm.clone();
+                }
+
+                if a[at(l, l - 1)].abs() + s.clone() == s {
+                    a[at(l, l - 1)] = T::zero();
+                    break;
+                }
+
+                l -= 1;
+            }
+
+            let mut x = a[at(nn, nn)].clone();
+
+            if l == nn {
+                // One root found.
+                wr[nn] = x + shift.clone();
+                wi[nn] = T::zero();
+                nn -= 1;
+                break;
+            }
+
+            let mut y = a[at(nn - 1, nn - 1)].clone();
+            let mut w = a[at(nn, nn - 1)].clone() * a[at(nn - 1, nn)].clone();
+
+            if l == nn - 1 {
+                // Two roots found, from the trailing 2x2 block.
+                let p = (y - x.clone()) / T::from_usize(2);
+                let q = p.clone() * p.clone() + w.clone();
+                let z = q.abs().sqrt();
+                x += shift.clone();
+
+                if q >= T::zero() {
+                    let z = p.clone() + sign_of(z, &p);
+                    wr[nn - 1] = x.clone() + z.clone();
+                    wr[nn] = if z.eq_zero() {
+                        wr[nn - 1].clone()
+                    } else {
+                        x - w / z
+                    };
+                    wi[nn - 1] = T::zero();
+                    wi[nn] = T::zero();
+                } else {
+                    wr[nn - 1] = x.clone() + p.clone();
+                    wr[nn] = x + p;
+                    wi[nn - 1] = -z.clone();
+                    wi[nn] = z;
+                }
+
+                nn -= 2;
+                break;
+            }
+
+            if its == MAX_QR_ITERATIONS {
+                return None;
+            }
+
+            if its == 10 || its == 20 {
+                // An exceptional shift, to break out of cycles.
+                shift += x.clone();
+
+                for i in 1..nn + 1 {
+                    a[at(i, i)] -= x.clone();
+                }
+
+                let s = a[at(nn, nn - 1)].abs() + a[at(nn - 1, nn - 2)].abs();
+                x = T::from_f64(0.75) * s.clone();
+                y = x.clone();
+                w = T::from_f64(-0.4375) * s.clone() * s;
+            }
+
+            its += 1;
+
+            // Look for two consecutive small subdiagonal elements, and form the first column of
+            // the double-shifted matrix there.
+            let (mut p, mut q, mut r);
+            let mut m = nn - 2;
+
+            loop {
+                let z = a[at(m, m)].clone();
+                let rr = x.clone() - z.clone();
+                let ss = y.clone() - z.clone();
+
+                p = (rr.clone() * ss.clone() - w.clone()) / a[at(m + 1, m)].clone() +
+                    a[at(m, m + 1)].clone();
+                q = a[at(m + 1, m + 1)].clone() - z.clone() - rr - ss;
+                r = a[at(m + 2, m + 1)].clone();
+
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s.clone();
+                q /= s.clone();
+                r /= s;
+
+                if m == l {
+                    break;
+                }
+
+                let u = a[at(m, m - 1)].abs() * (q.abs() + r.abs());
+                let v = p.abs() *
+                        (a[at(m - 1, m - 1)].abs() + z.abs() + a[at(m + 1, m + 1)].abs());
+
+                if u + v.clone() == v {
+                    break;
+                }
+
+                m -= 1;
+            }
+
+            for i in m + 2..nn + 1 {
+                a[at(i, i - 2)] = T::zero();
+
+                if i != m + 2 {
+                    a[at(i, i - 3)] = T::zero();
+                }
+            }
+
+            // Chase the bulge down the matrix with Householder reflections.
+            for k in m..nn {
+                if k != m {
+                    p = a[at(k, k - 1)].clone();
+                    q = a[at(k + 1, k - 1)].clone();
+                    r = if k != nn - 1 {
+                        a[at(k + 2, k - 1)].clone()
+                    } else {
+                        T::zero()
+                    };
+
+                    x = p.abs() + q.abs() + r.abs();
+
+                    if !x.eq_zero() {
+                        p /= x.clone();
+                        q /= x.clone();
+                        r /= x.clone();
+                    }
+                }
+
+                let length = p.clone() * p.clone() + q.clone() * q.clone() + r.clone() * r.clone();
+                let s = sign_of(length.sqrt(), &p);
+
+                if s.eq_zero() {
+                    continue;
+                }
+
+                if k == m {
+                    if l != m {
+                        let negated = -a[at(k, k - 1)].clone();
+                        a[at(k, k - 1)] = negated;
+                    }
+                } else {
+                    a[at(k, k - 1)] = -s.clone() * x.clone();
+                }
+
+                p += s.clone();
+                x = p.clone() / s.clone();
+                y = q.clone() / s.clone();
+                let z = r.clone() / s;
+                q /= p.clone();
+                r /= p.clone();
+
+                for j in k..nn + 1 {
+                    p = a[at(k, j)].clone() + q.clone() * a[at(k + 1, j)].clone();
+
+                    if k != nn - 1 {
+                        p += r.clone() * a[at(k + 2, j)].clone();
+                        a[at(k + 2, j)] -= p.clone() * z.clone();
+                    }
+
+                    a[at(k + 1, j)] -= p.clone() * y.clone();
+                    a[at(k, j)] -= p.clone() * x.clone();
+                }
+
+                for i in l..cmp::min(nn, k + 3) + 1 {
+                    p = x.clone() * a[at(i, k)].clone() + y.clone() * a[at(i, k + 1)].clone();
+
+                    if k != nn - 1 {
+                        p += z.clone() * a[at(i, k + 2)].clone();
+                        a[at(i, k + 2)] -= p.clone() * r.clone();
+                    }
+
+                    a[at(i, k + 1)] -= p.clone() * q.clone();
+                    a[at(i, k)] -= p.clone();
+                }
+            }
+        }
+    }
+
+    Some(wr.into_iter().zip(wi).skip(1).map(|(re, im)| Complex::new(re, im)).collect())
+}
+
+
+/// Sorts real roots into ascending order.
+fn sorted_real_roots<T: Scalar + Float>(mut roots: Vec<T>) -> Vec<T> {
+    roots.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    roots
+}
+
+
+/// Refines a real root of `coefficients`, given in order of increasing degree, with a Newton step
+/// if that improves it.
+fn polish_real<T: Scalar + Float>(coefficients: &[T], x: T) -> T {
+    let (value, slope) = coefficients.iter().rev().fold((T::zero(), T::zero()), |(p, dp), c| {
+        (p.clone() * x.clone() + c.clone(), dp * x.clone() + p)
+    });
+
+    if slope.eq_zero() {
+        return x;
+    }
+
+    let next = x.clone() - value.clone() / slope;
+    let next_value = coefficients.iter().rev().fold(T::zero(), |p, c| p * next.clone() + c.clone());
+
+    if next_value.abs() < value.abs() { next } else { x }
+}
+
+
+/// The distinct real roots of `a x^2 + b x + c` in ascending order. If `a` is zero, the roots of
+/// `b x + c`; if every coefficient is zero, no roots are returned.
+pub fn solve_quadratic<T: Scalar + Float>(a: T, b: T, c: T) -> Vec<T> {
+    if a.eq_zero() {
+        return if b.eq_zero() { Vec::new() } else { vec![-c / b] };
+    }
+
+    let two = T::from_usize(2);
+    let discriminant = b.clone() * b.clone() - T::from_usize(4) * a.clone() * c.clone();
+
+    if discriminant < T::zero() {
+        Vec::new()
+    } else if discriminant.eq_zero() {
+        vec![-b / (two * a)]
+    } else {
+        // Avoid cancellation by never subtracting nearly equal quantities.
+        let q = -(b.clone() + sign_of(discriminant.sqrt(), &b)) / two;
+        sorted_real_roots(vec![q.clone() / a, c / q])
+    }
+}
+
+
+/// The real roots of `a x^3 + b x^2 + c x + d` in ascending order. If `a` is zero, the roots of
+/// the quadratic `b x^2 + c x + d`. Roots of multiplicity greater than one are returned once for
+/// each time they are found, which under rounding may be once or several times.
+pub fn solve_cubic<T: Scalar + Float>(a: T, b: T, c: T, d: T) -> Vec<T> {
+    if a.eq_zero() {
+        return solve_quadratic(b, c, d);
+    }
+
+    let coefficients = [d.clone(), c.clone(), b.clone(), a.clone()];
+    let (b, c, d) = (b / a.clone(), c / a.clone(), d / a);
+    let (two, three) = (T::from_usize(2), T::from_usize(3));
+
+    // Substituting `x = t - b / 3` gives the depressed cubic `t^3 + p t + q`.
+    let offset = b.clone() / three.clone();
+    let p = c.clone() - b.clone() * b.clone() / three.clone();
+    let q = two.clone() * b.clone() * b.clone() * b.clone() / T::from_usize(27) -
+            b * c / three.clone() + d;
+
+    let half_q = q.clone() / two.clone();
+    let third_p = p.clone() / three.clone();
+    let discriminant = half_q.clone() * half_q.clone() +
+                       third_p.clone() * third_p.clone() * third_p.clone();
+
+    let roots = if p.eq_zero() && q.eq_zero() {
+        vec![T::zero()]
+    } else if discriminant > T::zero() {
+        // One real root, by Cardano's formula.
+        let root = discriminant.sqrt();
+        vec![(root.clone() - half_q.clone()).cbrt() - (root + half_q).cbrt()]
+    } else {
+        // Three real roots, by the trigonometric method.
+        let radius = two * (-third_p.clone()).sqrt();
+        let cosine = half_q / (third_p.clone() * (-third_p).sqrt());
+        let cosine = if cosine > T::one() {
+            T::one()
+        } else if cosine < -T::one() {
+            -T::one()
+        } else {
+            cosine
+        };
+        let angle = cosine.acos() / three.clone();
+        let step = T::from_usize(2) * T::pi() / three;
+
+        (0..3)
+            .map(|k| radius.clone() * (angle.clone() - step.clone() * T::from_usize(k)).cos())
+            .collect()
+    };
+
+    sorted_real_roots(roots.into_iter()
+        .map(|t| polish_real(&coefficients, t - offset.clone()))
+        .collect())
+}
+
+
+/// The real roots of `a x^4 + b x^3 + c x^2 + d x + e` in ascending order, by Ferrari's method. If
+/// `a` is zero, the roots of the cubic `b x^3 + c x^2 + d x + e`. As with `solve_cubic`, repeated
+/// roots may be returned once or several times.
+pub fn solve_quartic<T: Scalar + Float>(a: T, b: T, c: T, d: T, e: T) -> Vec<T> {
+    if a.eq_zero() {
+        return solve_cubic(b, c, d, e);
+    }
+
+    let coefficients = [e.clone(), d.clone(), c.clone(), b.clone(), a.clone()];
+    let (b, c, d, e) = (b / a.clone(), c / a.clone(), d / a.clone(), e / a);
+    let (two, four, eight) = (T::from_usize(2), T::from_usize(4), T::from_usize(8));
+
+    // Substituting `x = y - b / 4` gives the depressed quartic `y^4 + p y^2 + q y + r`.
+    let offset = b.clone() / four.clone();
+    let b2 = b.clone() * b.clone();
+    let p = c.clone() - T::from_usize(3) * b2.clone() / eight.clone();
+    let q = d.clone() - b.clone() * c.clone() / two.clone() +
+            b2.clone() * b.clone() / eight.clone();
+    let r = e - b.clone() * d / four.clone() + b2.clone() * c / T::from_usize(16) -
+            T::from_usize(3) * b2.clone() * b2 / T::from_usize(256);
+
+    let mut roots = Vec::new();
+
+    if q.abs() <= T::epsilon() * (T::one() + p.abs() + r.abs()) {
+        // A biquadratic, which is a quadratic in `y^2`.
+        for z in solve_quadratic(T::one(), p, r) {
+            if z >= T::zero() {
+                let y = z.sqrt();
+                roots.push(-y.clone());
+                roots.push(y);
+            }
+        }
+    } else {
+        // Completing the square as `(y^2 + p / 2 + m)^2 = 2 m (y - q / 4m)^2` needs a positive
+        // root `m` of the resolvent cubic, which exists since the cubic is negative at zero.
+        let m = solve_cubic(eight.clone(),
+                            eight.clone() * p.clone(),
+                            two.clone() * p.clone() * p.clone() - eight * r,
+                            -q.clone() * q.clone())
+            .into_iter()
+            .fold(T::zero(), |max, m| if m > max { m } else { max });
+
+        let s = (two.clone() * m.clone()).sqrt();
+        let base = p / two.clone() + m;
+        let skew = q / (two * s.clone());
+
+        roots.extend(solve_quadratic(T::one(), -s.clone(), base.clone() + skew.clone()));
+        roots.extend(solve_quadratic(T::one(), s, base - skew));
+    }
+
+    sorted_real_roots(roots.into_iter()
+        .map(|y| polish_real(&coefficients, y - offset.clone()))
+        .collect())
+}
+
+
+/// `magnitude` with the sign of `of`.
+fn sign_of<T: Scalar>(magnitude: T, of: &T) -> T {
+    if *of >= T::zero() {
+        magnitude.abs()
+    } else {
+        -magnitude.abs()
+    }
+}
+
+
+impl<'a, 'b, T: Scalar> Add<&'b Polynomial<T>> for &'a Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, rhs: &'b Polynomial<T>) -> Polynomial<T> {
+        let n = cmp::max(self.coefficients.len(), rhs.coefficients.len());
+
+        Polynomial::new((0..n)
+            .map(|i| {
+                self.coefficients.get(i).cloned().unwrap_or_else(T::zero) +
+                rhs.coefficients.get(i).cloned().unwrap_or_else(T::zero)
+            })
+            .collect())
+    }
+}
+
+
+impl<'a, 'b, T: Scalar> Sub<&'b Polynomial<T>> for &'a Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, rhs: &'b Polynomial<T>) -> Polynomial<T> {
+        let n = cmp::max(self.coefficients.len(), rhs.coefficients.len());
+
+        Polynomial::new((0..n)
+            .map(|i| {
+                self.coefficients.get(i).cloned().unwrap_or_else(T::zero) -
+                rhs.coefficients.get(i).cloned().unwrap_or_else(T::zero)
+            })
+            .collect())
+    }
+}
+
+
+impl<'a, 'b, T: Scalar> Mul<&'b Polynomial<T>> for &'a Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, rhs: &'b Polynomial<T>) -> Polynomial<T> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let mut product = vec![T::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                product[i + j] += a.clone() * b.clone();
+            }
+        }
+
+        Polynomial::new(product)
+    }
+}
+
+
+/// Polynomial division, discarding the remainder. Panics if `rhs` is the zero polynomial.
+impl<'a, 'b, T: Scalar> Div<&'b Polynomial<T>> for &'a Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn div(self, rhs: &'b Polynomial<T>) -> Polynomial<T> {
+        self.div_rem(rhs).expect("Polynomial division by zero!").0
+    }
+}
+
+
+/// The remainder of polynomial division. Panics if `rhs` is the zero polynomial.
+impl<'a, 'b, T: Scalar> Rem<&'b Polynomial<T>> for &'a Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn rem(self, rhs: &'b Polynomial<T>) -> Polynomial<T> {
+        self.div_rem(rhs).expect("Polynomial division by zero!").1
+    }
+}
+
+
+macro_rules! impl_polynomial_op {
+    ($($op:ident, $method:ident);*) => {
+        $(impl<T: Scalar> $op for Polynomial<T> {
+            type Output = Polynomial<T>;
+
+            fn $method(self, rhs: Polynomial<T>) -> Polynomial<T> {
+                (&self).$method(&rhs)
+            }
+        })*
+    }
+}
+
+impl_polynomial_op!(Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem);
+
+
+impl<T: Scalar> Neg for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.into_iter().map(|c| -c).collect())
+    }
+}
+
+
+/// Scales every coefficient by `rhs`.
+impl<T: Scalar> Mul<T> for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, rhs: T) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.into_iter().map(|c| c * rhs.clone()).collect())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(coefficients: &[f64]) -> Polynomial<f64> {
+        Polynomial::new(coefficients.to_vec())
+    }
+
+    fn assert_roots(actual: Vec<f64>, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn polynomial_arithmetic() {
+        let a = poly(&[1., 1.]);
+        let b = poly(&[-1., 1.]);
+
+        assert_eq!(&a * &b, poly(&[-1., 0., 1.]));
+        assert_eq!(&a + &b, poly(&[0., 2.]));
+        assert_eq!(&a - &a, Polynomial::zero());
+        assert_eq!(Polynomial::from_roots(&[1., -1.]), &a * &b);
+
+        let (q, r) = poly(&[-1., 0., 0., 1.]).div_rem(&b).unwrap();
+        assert_eq!(q, poly(&[1., 1., 1.]));
+        assert!(r.is_zero());
+
+        let (q, r) = poly(&[3., 0., 2.]).div_rem(&poly(&[1., 1.])).unwrap();
+        assert_eq!(q, poly(&[-2., 2.]));
+        assert_eq!(r, poly(&[5.]));
+        assert_eq!(&(&q * &poly(&[1., 1.])) + &r, poly(&[3., 0., 2.]));
+
+        assert!(a.div_rem(&Polynomial::zero()).is_none());
+    }
+
+    #[test]
+    fn calculus_and_composition() {
+        let p = poly(&[1., -3., 0., 2.]);
+
+        assert_eq!(p.eval(2.), 11.);
+        assert_eq!(p.derivative(), poly(&[-3., 0., 6.]));
+        assert_eq!(p.derivative().integral(1.), p);
+        assert_eq!(poly(&[2., 3.]).integral(0.), poly(&[0., 2., 1.5]));
+
+        let composed = p.compose(&poly(&[1., 1.]));
+        for &x in &[-2., 0., 0.5, 3.] {
+            assert_eq!(composed.eval(x), p.eval(x + 1.));
+        }
+    }
+
+    #[test]
+    fn roots_from_companion_matrix() {
+        let p = Polynomial::from_roots(&[3., -4., 1., 2., 0.5]);
+        assert_roots(p.real_roots().unwrap(), &[-4., 0.5, 1., 2., 3.]);
+
+        // x^4 + 4x^2 + 3 = (x^2 + 1)(x^2 + 3) has only imaginary roots.
+        let p = poly(&[3., 0., 4., 0., 1.]);
+        let roots = p.roots().unwrap();
+        assert_eq!(roots.len(), 4);
+        assert!(roots.iter().all(|z| z.re.abs() < 1e-9 && p.eval_complex(*z).norm() < 1e-9));
+        assert!(p.real_roots().unwrap().is_empty());
+
+        let p = poly(&[0., 0., -1., 1.]);
+        assert_roots(p.real_roots().unwrap(), &[0., 0., 1.]);
+
+        assert!(Polynomial::<f64>::zero().roots().is_none());
+        assert!(poly(&[2.]).roots().unwrap().is_empty());
+    }
+
+    #[test]
+    fn closed_form_solvers() {
+        assert_roots(solve_quadratic(2., -8., 6.), &[1., 3.]);
+        assert_roots(solve_quadratic(1., 0., 1.), &[]);
+        assert_roots(solve_quadratic(1., -2., 1.), &[1.]);
+        assert_roots(solve_quadratic(0., 2., -1.), &[0.5]);
+
+        assert_roots(solve_cubic(2., -4., -10., 12.), &[-2., 1., 3.]);
+        assert_roots(solve_cubic(1., 0., 0., -8.), &[2.]);
+        assert_roots(solve_cubic(1., -3., 3., -1.), &[1.]);
+
+        // (x - 1)(x + 2)(x - 3)(x + 0.5)
+        assert_roots(solve_quartic(1., -1.5, -6., 3.5, 3.), &[-2., -0.5, 1., 3.]);
+        assert_roots(solve_quartic(1., 0., -5., 0., 4.), &[-2., -1., 1., 2.]);
+        assert_roots(solve_quartic(1., 0., 1., 0., 1.), &[]);
+    }
+}
@@ -0,0 +1,627 @@
+//! Iterative root finding, for scalar functions and for systems of equations.
+//!
+//! The scalar methods are bisection and Brent's method, which need a bracketing interval, and
+//! Newton's method, which needs a derivative. Systems `f(x) = 0` with `f: Vect<T, N> -> Vect<T, N>`
+//! are solved by damped Newton iteration, either with an exact Jacobian (`newton_system`) or with
+//! Broyden's rank-one approximation to it (`broyden`). Every method returns a `Solution` holding
+//! its best estimate of the root, why it stopped and how many iterations it took.
+
+use linalg::{Dot, Mat, Scalar, Vect, VectorNorm};
+use linalg::algorithm::solve::gaussian::GaussianEliminationExt;
+use num::traits::Float;
+use typehack::data::Size;
+
+
+/// The most times the step of a damped Newton iteration will be halved looking for a decrease in
+/// the residual.
+const MAX_BACKTRACKS: usize = 30;
+
+/// The fraction of the decrease in the residual predicted by the linearization which a damped
+/// Newton step must achieve to be accepted.
+const SUFFICIENT_DECREASE: f64 = 1e-4;
+
+
+/// Why a root finder stopped.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    /// The tolerance was met.
+    Converged,
+
+    /// The iteration limit was reached before the tolerance was met.
+    MaxIterations,
+
+    /// The function has the same sign at both ends of the interval, so it may not contain a root.
+    NotBracketed,
+
+    /// The derivative was zero or the Jacobian singular, so no step could be taken.
+    Singular,
+
+    /// No damped step decreased the residual, which usually means the iteration is stuck near a
+    /// local minimum of `|f|` which is not a root.
+    Stalled,
+
+    /// The function returned a value which is not finite.
+    Diverged,
+}
+
+
+/// The outcome of a root finder: its best estimate of the root, why it stopped, and how many
+/// iterations it took to get there.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Solution<X> {
+    pub root: X,
+    pub status: Status,
+    pub iterations: usize,
+}
+
+
+impl<X> Solution<X> {
+    fn new(root: X, status: Status, iterations: usize) -> Self {
+        Solution {
+            root: root,
+            status: status,
+            iterations: iterations,
+        }
+    }
+
+
+    pub fn converged(&self) -> bool {
+        self.status == Status::Converged
+    }
+
+
+    /// The root, if the tolerance was met.
+    pub fn ok(self) -> Option<X> {
+        if self.converged() { Some(self.root) } else { None }
+    }
+}
+
+
+/// When to stop iterating. A root finder converges once its step, or the width of its bracket, is
+/// at most `x * (1 + |root|)`, or the magnitude of the function at its estimate is at most `f`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tolerance<T> {
+    pub x: T,
+    pub f: T,
+    pub max_iterations: usize,
+}
+
+
+impl<T: Scalar + Float> Tolerance<T> {
+    pub fn new(x: T, f: T, max_iterations: usize) -> Self {
+        Tolerance {
+            x: x,
+            f: f,
+            max_iterations: max_iterations,
+        }
+    }
+
+
+    fn step_converged(&self, step: T, root: T) -> bool {
+        step.abs() <= self.x.clone() * (T::one() + root.abs())
+    }
+}
+
+
+/// A step tolerance of a small multiple of machine epsilon, no residual tolerance, and at most
+/// a hundred iterations.
+impl<T: Scalar + Float> Default for Tolerance<T> {
+    fn default() -> Self {
+        Tolerance::new(T::epsilon() * T::from_usize(64), T::zero(), 100)
+    }
+}
+
+
+/// Whether `a` and `b` are both negative or both non-negative.
+fn same_sign<T: Scalar>(a: &T, b: &T) -> bool {
+    (*a < T::zero()) == (*b < T::zero())
+}
+
+
+/// Finds a root of `f` between `a` and `b` by bisection, which gains one bit of the root per
+/// iteration no matter how badly behaved `f` is. `f(a)` and `f(b)` must differ in sign.
+pub fn bisection<T, F>(f: F, a: T, b: T, tolerance: &Tolerance<T>) -> Solution<T>
+    where T: Scalar + Float,
+          F: Fn(T) -> T
+{
+    let (mut lo, mut hi) = (a, b);
+    let mut f_lo = f(lo.clone());
+    let f_hi = f(hi.clone());
+
+    if f_lo.abs() <= tolerance.f {
+        return Solution::new(lo, Status::Converged, 0);
+    } else if f_hi.abs() <= tolerance.f {
+        return Solution::new(hi, Status::Converged, 0);
+    } else if same_sign(&f_lo, &f_hi) {
+        return Solution::new(lo, Status::NotBracketed, 0);
+    }
+
+    let two = T::one() + T::one();
+    let mut mid = lo.clone();
+
+    for i in 1..tolerance.max_iterations + 1 {
+        let half = (hi.clone() - lo.clone()) / two.clone();
+        mid = lo.clone() + half.clone();
+        let f_mid = f(mid.clone());
+
+        if !f_mid.clone().is_finite() {
+            return Solution::new(mid, Status::Diverged, i);
+        } else if f_mid.abs() <= tolerance.f || tolerance.step_converged(half, mid.clone()) {
+            return Solution::new(mid, Status::Converged, i);
+        }
+
+        if same_sign(&f_mid, &f_lo) {
+            lo = mid.clone();
+            f_lo = f_mid;
+        } else {
+            hi = mid.clone();
+        }
+    }
+
+    Solution::new(mid, Status::MaxIterations, tolerance.max_iterations)
+}
+
+
+/// Finds a root of `f` between `a` and `b` by Brent's method, which combines inverse quadratic
+/// interpolation and the secant method with bisection. It converges superlinearly on smooth
+/// functions while never taking many more iterations than bisection would. `f(a)` and `f(b)` must
+/// differ in sign.
+pub fn brent<T, F>(f: F, a: T, b: T, tolerance: &Tolerance<T>) -> Solution<T>
+    where T: Scalar + Float,
+          F: Fn(T) -> T
+{
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a.clone()), f(b.clone()));
+
+    if fa.abs() <= tolerance.f {
+        return Solution::new(a, Status::Converged, 0);
+    } else if fb.abs() <= tolerance.f {
+        return Solution::new(b, Status::Converged, 0);
+    } else if same_sign(&fa, &fb) {
+        return Solution::new(a, Status::NotBracketed, 0);
+    }
+
+    let (two, three) = (T::from_usize(2), T::from_usize(3));
+
+    // `b` is the best estimate so far, `a` the previous one, and `c` the far end of the bracket.
+    // `d` is the last step taken and `e` the one before.
+    let (mut c, mut fc) = (b.clone(), fb.clone());
+    let mut d = b.clone() - a.clone();
+    let mut e = d.clone();
+
+    for i in 1..tolerance.max_iterations + 1 {
+        if same_sign(&fb, &fc) {
+            c = a.clone();
+            fc = fa.clone();
+            d = b.clone() - a.clone();
+            e = d.clone();
+        }
+
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a.clone();
+            fa = fb;
+            fb = fc;
+            fc = fa.clone();
+        }
+
+        let tol = two.clone() * T::epsilon() * b.abs() +
+                  tolerance.x.clone() * (T::one() + b.abs()) / two.clone();
+        let half = (c.clone() - b.clone()) / two.clone();
+
+        if half.abs() <= tol || fb.abs() <= tolerance.f {
+            return Solution::new(b, Status::Converged, i - 1);
+        }
+
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            // Try interpolating, inversely quadratically if we have three distinct points and
+            // by the secant method if not.
+            let s = fb.clone() / fa.clone();
+            let (mut p, mut q);
+
+            if a == c {
+                p = two.clone() * half.clone() * s.clone();
+                q = T::one() - s;
+            } else {
+                let q0 = fa.clone() / fc.clone();
+                let r = fb.clone() / fc.clone();
+                p = s.clone() *
+                    (two.clone() * half.clone() * q0.clone() * (q0.clone() - r.clone()) -
+                     (b.clone() - a.clone()) * (r.clone() - T::one()));
+                q = (q0 - T::one()) * (r - T::one()) * (s - T::one());
+            }
+
+            if p > T::zero() {
+                q = -q;
+            }
+
+            p = p.abs();
+
+            // Accept the interpolated step only if it falls well within the bracket and is
+            // shrinking fast enough; otherwise bisect.
+            let bound = three.clone() * half.clone() * q.clone() - (tol.clone() * q.clone()).abs();
+            let previous = (e.clone() * q.clone()).abs();
+            let limit = if bound < previous { bound } else { previous };
+
+            if two.clone() * p.clone() < limit {
+                e = d;
+                d = p / q;
+            } else {
+                d = half.clone();
+                e = d.clone();
+            }
+        } else {
+            d = half.clone();
+            e = d.clone();
+        }
+
+        a = b.clone();
+        fa = fb;
+
+        b = if d.abs() > tol {
+            b + d.clone()
+        } else if half > T::zero() {
+            b + tol
+        } else {
+            b - tol
+        };
+
+        fb = f(b.clone());
+
+        if !fb.clone().is_finite() {
+            return Solution::new(b, Status::Diverged, i);
+        }
+    }
+
+    Solution::new(b, Status::MaxIterations, tolerance.max_iterations)
+}
+
+
+/// Finds a root of `f` by Newton's method from the initial guess `x0`, given the derivative
+/// `df` of `f`. Convergence is quadratic near a simple root, but not guaranteed from afar.
+pub fn newton<T, F, D>(f: F, df: D, x0: T, tolerance: &Tolerance<T>) -> Solution<T>
+    where T: Scalar + Float,
+          F: Fn(T) -> T,
+          D: Fn(T) -> T
+{
+    let mut x = x0;
+
+    for i in 0..tolerance.max_iterations {
+        let fx = f(x.clone());
+
+        if !fx.clone().is_finite() {
+            return Solution::new(x, Status::Diverged, i);
+        } else if fx.abs() <= tolerance.f {
+            return Solution::new(x, Status::Converged, i);
+        }
+
+        let slope = df(x.clone());
+
+        if slope.eq_zero() || !slope.clone().is_finite() {
+            return Solution::new(x, Status::Singular, i);
+        }
+
+        let step = fx / slope;
+        x = x - step.clone();
+
+        if tolerance.step_converged(step, x.clone()) {
+            return Solution::new(x, Status::Converged, i + 1);
+        }
+    }
+
+    Solution::new(x, Status::MaxIterations, tolerance.max_iterations)
+}
+
+
+/// A point accepted by the line search, with the value of the function there and its norm.
+struct Accepted<T: Scalar, N: Size<T>> {
+    x: Vect<T, N>,
+    fx: Vect<T, N>,
+    norm: T,
+}
+
+
+/// Backtracks along the Newton step `step` from `x`, halving it until the norm of the residual
+/// decreases sufficiently from `norm`. Returns `None` if it never does.
+fn line_search<T, N, F>(f: &F,
+                        x: &Vect<T, N>,
+                        norm: &T,
+                        step: &Vect<T, N>)
+                        -> Option<Accepted<T, N>>
+    where T: Scalar + Float,
+          N: Size<T>,
+          F: Fn(Vect<T, N>) -> Vect<T, N>
+{
+    let decrease = T::from_f64(SUFFICIENT_DECREASE);
+    let mut lambda = T::one();
+
+    for _ in 0..MAX_BACKTRACKS {
+        let candidate = x + &(step * lambda.clone());
+        let fx = f(candidate.clone());
+        let candidate_norm = fx.norm();
+
+        if candidate_norm.clone().is_finite() &&
+           candidate_norm <= (T::one() - decrease.clone() * lambda.clone()) * norm.clone() {
+            return Some(Accepted {
+                x: candidate,
+                fx: fx,
+                norm: candidate_norm,
+            });
+        }
+
+        lambda = lambda / (T::one() + T::one());
+    }
+
+    None
+}
+
+
+/// Approximates the Jacobian of `f` at `x`, where `f(x) = fx`, by forward differences.
+fn finite_difference_jacobian<T, N, F>(f: &F, x: &Vect<T, N>, fx: &Vect<T, N>) -> Mat<T, N, N>
+    where T: Scalar + Float,
+          N: Size<T>,
+          F: Fn(Vect<T, N>) -> Vect<T, N>
+{
+    let n = x.len();
+    let mut jacobian = Mat::from_elem(x.size(), x.size(), T::zero());
+
+    for k in 0..n {
+        let h = T::epsilon().sqrt() * (T::one() + x[k].abs());
+        let mut shifted = x.clone();
+        shifted[k] += h.clone();
+
+        let column = (f(shifted) - fx) / h;
+
+        for i in 0..n {
+            jacobian[[i, k]] = column[i].clone();
+        }
+    }
+
+    jacobian
+}
+
+
+/// Finds a root of the system `f(x) = 0` by damped Newton iteration from the initial guess `x0`,
+/// given the Jacobian of `f`. Each step solves the linearized system by Gaussian elimination, then
+/// is halved until it decreases the norm of `f`, which makes the iteration far more likely to
+/// converge from a poor initial guess than Newton's method alone.
+pub fn newton_system<T, N, F, J>(f: F,
+                                 jacobian: J,
+                                 x0: Vect<T, N>,
+                                 tolerance: &Tolerance<T>)
+                                 -> Solution<Vect<T, N>>
+    where T: Scalar + Float,
+          N: Size<T>,
+          F: Fn(Vect<T, N>) -> Vect<T, N>,
+          J: Fn(Vect<T, N>) -> Mat<T, N, N>
+{
+    let mut x = x0;
+    let mut fx = f(x.clone());
+    let mut norm = fx.norm();
+
+    if !norm.clone().is_finite() {
+        return Solution::new(x, Status::Diverged, 0);
+    } else if norm <= tolerance.f {
+        return Solution::new(x, Status::Converged, 0);
+    }
+
+    for i in 1..tolerance.max_iterations + 1 {
+        let step = jacobian(x.clone()).ge_solve(-fx);
+        let length = step.norm();
+
+        if !length.clone().is_finite() {
+            return Solution::new(x, Status::Singular, i - 1);
+        } else if tolerance.step_converged(length, x.norm()) {
+            // Both `x` and `x + step` are within the tolerance of the root, so stop at whichever
+            // has the smaller residual, as long as `f` is finite there.
+            let root = &x + &step;
+            let root_norm = f(root.clone()).norm();
+            let root = if root_norm.clone().is_finite() && root_norm <= norm { root } else { x };
+
+            return Solution::new(root, Status::Converged, i);
+        }
+
+        match line_search(&f, &x, &norm, &step) {
+            Some(accepted) => {
+                let moved = (&accepted.x - &x).norm();
+
+                x = accepted.x;
+                fx = accepted.fx;
+                norm = accepted.norm;
+
+                if norm <= tolerance.f || tolerance.step_converged(moved, x.norm()) {
+                    return Solution::new(x, Status::Converged, i);
+                }
+            }
+            None => return Solution::new(x, Status::Stalled, i - 1),
+        }
+    }
+
+    Solution::new(x, Status::MaxIterations, tolerance.max_iterations)
+}
+
+
+/// Finds a root of the system `f(x) = 0` by damped Newton iteration from the initial guess `x0`,
+/// without needing the Jacobian of `f`. The Jacobian is approximated by finite differences at
+/// `x0`, and after that by Broyden's rank-one updates, so each iteration evaluates `f` only once
+/// unless the step must be damped. Should the approximation go so bad that no damped step
+/// decreases the residual, it is recomputed by finite differences before giving up.
+pub fn broyden<T, N, F>(f: F, x0: Vect<T, N>, tolerance: &Tolerance<T>) -> Solution<Vect<T, N>>
+    where T: Scalar + Float,
+          N: Size<T>,
+          F: Fn(Vect<T, N>) -> Vect<T, N>
+{
+    let mut x = x0;
+    let mut fx = f(x.clone());
+    let mut norm = fx.norm();
+
+    if !norm.clone().is_finite() {
+        return Solution::new(x, Status::Diverged, 0);
+    } else if norm <= tolerance.f {
+        return Solution::new(x, Status::Converged, 0);
+    }
+
+    let n = x.len();
+    let mut jacobian = finite_difference_jacobian(&f, &x, &fx);
+    let mut fresh = true;
+
+    for i in 1..tolerance.max_iterations + 1 {
+        let step = jacobian.clone().ge_solve(-fx.clone());
+        let length = step.norm();
+
+        let accepted = if !length.clone().is_finite() {
+            None
+        } else if tolerance.step_converged(length.clone(), x.norm()) {
+            let root = &x + &step;
+            let root_norm = f(root.clone()).norm();
+
+            if root_norm.clone().is_finite() && root_norm <= norm {
+                return Solution::new(root, Status::Converged, i);
+            } else if fresh {
+                return Solution::new(x, Status::Converged, i);
+            }
+
+            // A stale approximation can make the step tiny without `x + step` being any closer
+            // to the root, so recompute it before deciding.
+            jacobian = finite_difference_jacobian(&f, &x, &fx);
+            fresh = true;
+            continue;
+        } else {
+            line_search(&f, &x, &norm, &step)
+        };
+
+        let accepted = match accepted {
+            Some(accepted) => accepted,
+            None if !fresh => {
+                jacobian = finite_difference_jacobian(&f, &x, &fx);
+                fresh = true;
+                continue;
+            }
+            None if !length.clone().is_finite() => return Solution::new(x, Status::Singular, i - 1),
+            None => return Solution::new(x, Status::Stalled, i - 1),
+        };
+
+        // Broyden's update is the smallest change to the Jacobian for which
+        // `jacobian * dx = df`.
+        let dx = &accepted.x - &x;
+        let df = &accepted.fx - &fx;
+        let dx_dot_dx = (&dx).dot(&dx);
+
+        if dx_dot_dx > T::zero() {
+            let residual = df - &jacobian * &dx;
+
+            for r in 0..n {
+                for c in 0..n {
+                    jacobian[[r, c]] += residual[r].clone() * dx[c].clone() / dx_dot_dx.clone();
+                }
+            }
+        }
+
+        fresh = false;
+
+        let moved = dx.norm();
+        x = accepted.x;
+        fx = accepted.fx;
+        norm = accepted.norm;
+
+        if norm <= tolerance.f || tolerance.step_converged(moved, x.norm()) {
+            return Solution::new(x, Status::Converged, i);
+        }
+    }
+
+    Solution::new(x, Status::MaxIterations, tolerance.max_iterations)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typehack::binary::B2;
+
+    fn cubic(x: f64) -> f64 {
+        x * x * x - 2. * x - 5.
+    }
+
+    const CUBIC_ROOT: f64 = 2.0945514815423265;
+
+    #[test]
+    fn scalar_root_finders() {
+        let tolerance = Tolerance::default();
+
+        let bisected = bisection(cubic, 2., 3., &tolerance);
+        assert!(bisected.converged());
+        assert!((bisected.root - CUBIC_ROOT).abs() < 1e-12);
+
+        let brented = brent(cubic, 2., 3., &tolerance);
+        assert!(brented.converged());
+        assert!((brented.root - CUBIC_ROOT).abs() < 1e-12);
+        assert!(brented.iterations < bisected.iterations);
+
+        let newtoned = newton(cubic, |x| 3. * x * x - 2., 2., &tolerance);
+        assert!(newtoned.converged());
+        assert!((newtoned.root - CUBIC_ROOT).abs() < 1e-12);
+        assert!(newtoned.iterations < brented.iterations);
+    }
+
+    #[test]
+    fn scalar_failures() {
+        let tolerance = Tolerance::default();
+
+        assert_eq!(bisection(|x: f64| x * x + 1., -1., 1., &tolerance).status,
+                   Status::NotBracketed);
+        assert_eq!(brent(|x: f64| x * x + 1., -1., 1., &tolerance).status,
+                   Status::NotBracketed);
+        assert_eq!(newton(|x: f64| x * x - 1., |x| 2. * x, 0., &tolerance).status,
+                   Status::Singular);
+
+        let short = Tolerance::new(1e-15, 0., 3);
+        let solution = bisection(cubic, 2., 3., &short);
+        assert_eq!(solution.status, Status::MaxIterations);
+        assert_eq!(solution.iterations, 3);
+        assert!(solution.ok().is_none());
+    }
+
+    // The intersections of the circle `x^2 + y^2 = 4` with the parabola `y = x^2 - 1`.
+    fn system(v: Vect<f64, B2>) -> Vect<f64, B2> {
+        Vect![v[0] * v[0] + v[1] * v[1] - 4., v[1] - v[0] * v[0] + 1.]
+    }
+
+    fn system_jacobian(v: Vect<f64, B2>) -> Mat<f64, B2, B2> {
+        Mat![[2. * v[0], 2. * v[1]], [-2. * v[0], 1.]]
+    }
+
+    #[test]
+    fn newton_systems() {
+        let tolerance = Tolerance::default();
+
+        let solution = newton_system(system, system_jacobian, Vect![1., 1.], &tolerance);
+        assert!(solution.converged(), "{:?}", solution);
+        assert!(system(solution.root.clone()).norm() < 1e-12);
+        assert!(solution.root[0] > 0.);
+
+        let solution = broyden(system, Vect![1., 1.], &tolerance);
+        assert!(solution.converged(), "{:?}", solution);
+        assert!(system(solution.root.clone()).norm() < 1e-10);
+
+        // Started far away, the undamped iteration would overshoot badly.
+        let solution = newton_system(system, system_jacobian, Vect![-20., 30.], &tolerance);
+        assert!(solution.converged(), "{:?}", solution);
+        assert!(system(solution.root).norm() < 1e-12);
+    }
+
+    #[test]
+    fn tiny_steps_are_evaluated() {
+        // Undefined from `x = 1` on, so the step from `x = 0.9999` overshoots into `NaN`.
+        let edge = |v: Vect<f64, B2>| {
+            Vect![if v[0] < 1. { v[0] - 1. } else { ::std::f64::NAN }, v[1]]
+        };
+        let halved = |_| Mat![[0.5, 0.], [0., 0.5]];
+        let tolerance = Tolerance::new(1e-3, 0., 100);
+
+        let solution = newton_system(edge, halved, Vect![0.9999, 0.], &tolerance);
+        assert!(solution.converged(), "{:?}", solution);
+        assert_eq!(solution.root, Vect![0.9999, 0.]);
+        assert!(edge(solution.root).norm().is_finite());
+    }
+}
@@ -1,6 +1,20 @@
 pub trait Float {
     fn sqrt(self) -> Self;
+    fn cbrt(self) -> Self;
     fn recip(self) -> Self;
+
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, Self) -> Self;
+
+    fn is_finite(self) -> bool;
+
+    fn epsilon() -> Self;
+    fn pi() -> Self;
+
+    fn from_f64(f64) -> Self;
 }
 
 
@@ -12,10 +26,60 @@ macro_rules! impl_float {
                 self.sqrt()
             }
 
+            #[inline]
+            fn cbrt(self) -> $t {
+                self.cbrt()
+            }
+
             #[inline]
             fn recip(self) -> $t {
                 self.recip()
             }
+
+            #[inline]
+            fn sin(self) -> $t {
+                self.sin()
+            }
+
+            #[inline]
+            fn cos(self) -> $t {
+                self.cos()
+            }
+
+            #[inline]
+            fn tan(self) -> $t {
+                self.tan()
+            }
+
+            #[inline]
+            fn acos(self) -> $t {
+                self.acos()
+            }
+
+            #[inline]
+            fn atan2(self, other: $t) -> $t {
+                self.atan2(other)
+            }
+
+            #[inline]
+            fn is_finite(self) -> bool {
+                self.is_finite()
+            }
+
+            #[inline]
+            fn epsilon() -> $t {
+                ::std::$t::EPSILON
+            }
+
+            #[inline]
+            fn pi() -> $t {
+                ::std::$t::consts::PI
+            }
+
+            #[inline]
+            fn from_f64(x: f64) -> $t {
+                x as $t
+            }
         })*
     }
 }
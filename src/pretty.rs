@@ -0,0 +1,126 @@
+//! Helpers shared by the `Display` impls of matrices, vectors, points and arrays.
+//!
+//! Every element is formatted with the precision of the outer format spec, so `{:.3}` prints a
+//! matrix with three decimal places. The width of the outer spec, if any, is used as a minimum
+//! column width. Axes longer than `TRUNCATE_ABOVE` are elided down to their first and last
+//! `EDGE_ITEMS` entries, unless the alternate flag (`{:#}`) is given.
+
+use std::cmp;
+use std::fmt::{self, Display, Formatter};
+use std::iter;
+
+
+/// Any axis with more entries than this is truncated when displayed.
+const TRUNCATE_ABOVE: usize = 16;
+
+/// The number of entries kept at either end of a truncated axis.
+const EDGE_ITEMS: usize = 4;
+
+const ELLIPSIS: &'static str = "...";
+
+
+/// Formats a single element with the given precision.
+pub fn elem<T: Display>(x: &T, precision: Option<usize>) -> String {
+    match precision {
+        Some(p) => format!("{:.*}", p, x),
+        None => format!("{}", x),
+    }
+}
+
+
+/// The indices to display along an axis of length `n`, with `None` standing in for the elided
+/// middle of a truncated axis.
+pub fn axis(n: usize, full: bool) -> Vec<Option<usize>> {
+    if full || n <= TRUNCATE_ABOVE {
+        (0..n).map(Some).collect()
+    } else {
+        (0..EDGE_ITEMS)
+            .map(Some)
+            .chain(iter::once(None))
+            .chain((n - EDGE_ITEMS..n).map(Some))
+            .collect()
+    }
+}
+
+
+/// Writes a list of elements between the given delimiters, separated by commas.
+pub fn write_list<F>(f: &mut Formatter,
+                     len: usize,
+                     open: &str,
+                     close: &str,
+                     elem: F)
+                     -> fmt::Result
+    where F: Fn(usize) -> String
+{
+    let items: Vec<String> = axis(len, f.alternate())
+        .into_iter()
+        .map(|i| i.map_or_else(|| String::from(ELLIPSIS), &elem))
+        .collect();
+
+    write!(f, "{}{}{}", open, items.join(", "), close)
+}
+
+
+/// Writes a grid of elements one row per line, with each column right-aligned to its widest
+/// element.
+pub fn write_grid<F>(f: &mut Formatter, rows: usize, cols: usize, elem: F) -> fmt::Result
+    where F: Fn(usize, usize) -> String
+{
+    let full = f.alternate();
+    let (row_axis, col_axis) = (axis(rows, full), axis(cols, full));
+
+    if row_axis.is_empty() || col_axis.is_empty() {
+        return write!(f, "[]");
+    }
+
+    let cells: Vec<Vec<String>> = row_axis.iter()
+        .map(|&i| {
+            col_axis.iter()
+                .map(|&j| match (i, j) {
+                    (Some(i), Some(j)) => elem(i, j),
+                    _ => String::from(ELLIPSIS),
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut widths = vec![f.width().unwrap_or(0); col_axis.len()];
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = cmp::max(*width, cell.chars().count());
+        }
+    }
+
+    for (k, row) in cells.iter().enumerate() {
+        if k > 0 {
+            write!(f, "\n")?;
+        }
+
+        write!(f, "[")?;
+        for (l, (cell, &width)) in row.iter().zip(&widths).enumerate() {
+            if l > 0 {
+                write!(f, "  ")?;
+            }
+
+            write!(f, "{:>1$}", cell, width)?;
+        }
+        write!(f, "]")?;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_truncation() {
+        assert_eq!(axis(3, false), vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(axis(100, false),
+                   vec![Some(0), Some(1), Some(2), Some(3), None, Some(96), Some(97), Some(98),
+                        Some(99)]);
+        assert_eq!(axis(100, true).len(), 100);
+    }
+}
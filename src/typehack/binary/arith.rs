@@ -10,6 +10,10 @@ impl<M: Nat, N: Nat> NatAdd<N> for M {
     default type Result = N;
 }
 
+impl NatAdd<P> for P {
+    type Result = P;
+}
+
 impl<N: Nat> NatAdd<P> for O<N> {
     type Result = O<N>;
 }
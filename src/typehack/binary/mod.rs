@@ -98,21 +98,60 @@ impl<N: Nat> Nat for I<N> {
     }
 }
 
-impl<N: Nat> Nat for O<N> {
-    type Succ = I<N>;
-    type Pred = <N::Pred as Nat>::DoublePlusOne;
+/// Zero gets its own implementation so that doubling it stays canonical: `2 * 0` is `O` rather
+/// than `O<O>`, and `2 * 0 + 1` is `I` rather than `I<O>`. Without it, `B2::Pred` would be `I<O>`,
+/// which has the value of `B1` but is a different type.
+impl Nat for O<P> {
+    type Succ = I;
+    type Pred = O;
 
-    type Double = O<O<N>>;
-    type DoublePlusOne = I<O<N>>;
+    type Double = O;
+    type DoublePlusOne = I;
 
-    type ShrOnce = N;
+    type ShrOnce = P;
+
+    fn as_usize() -> usize {
+        0
+    }
+
+    fn as_data() -> Self {
+        O(PhantomData::<P>)
+    }
+}
+
+impl<N: Nat> Nat for O<I<N>> {
+    type Succ = I<I<N>>;
+    type Pred = <<I<N> as Nat>::Pred as Nat>::DoublePlusOne;
+
+    type Double = O<O<I<N>>>;
+    type DoublePlusOne = I<O<I<N>>>;
+
+    type ShrOnce = I<N>;
+
+    fn as_usize() -> usize {
+        <I<N>>::as_usize() * 2
+    }
+
+    fn as_data() -> Self {
+        O(PhantomData::<I<N>>)
+    }
+}
+
+impl<N: Nat> Nat for O<O<N>> {
+    type Succ = I<O<N>>;
+    type Pred = <<O<N> as Nat>::Pred as Nat>::DoublePlusOne;
+
+    type Double = O<O<O<N>>>;
+    type DoublePlusOne = I<O<O<N>>>;
+
+    type ShrOnce = O<N>;
 
     fn as_usize() -> usize {
-        N::as_usize() * 2
+        <O<N>>::as_usize() * 2
     }
 
     fn as_data() -> Self {
-        O(PhantomData::<N>)
+        O(PhantomData::<O<N>>)
     }
 }
 
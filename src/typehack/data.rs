@@ -163,8 +163,17 @@ impl<E> Raw<E> for P {
     type Data = ();
 }
 
-impl<E, N: Nat> Raw<E> for O<N> {
-    type Data = EmptyNode<<N as Raw<E>>::Data>;
+// `O` is split the same way as its `Nat` implementation, so that `ShrOnce` is known here.
+impl<E> Raw<E> for O<P> {
+    type Data = EmptyNode<<P as Raw<E>>::Data>;
+}
+
+impl<E, N: Nat> Raw<E> for O<I<N>> {
+    type Data = EmptyNode<<I<N> as Raw<E>>::Data>;
+}
+
+impl<E, N: Nat> Raw<E> for O<O<N>> {
+    type Data = EmptyNode<<O<N> as Raw<E>>::Data>;
 }
 
 impl<E, N: Nat> Raw<E> for I<N> {
@@ -0,0 +1,30 @@
+extern crate leviathan as lev;
+
+
+use lev::geometry::frame::{Frame, Transform};
+use lev::geometry::transform::Affine;
+use lev::typehack::binary::{Nat, B2};
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct World;
+impl Frame for World {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Body;
+impl Frame for Body {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Sensor;
+impl Frame for Sensor {}
+
+
+fn main() {
+    let sensor_to_body: Transform<f64, B2, Sensor, Body> =
+        Transform::new(Affine::identity(B2::as_data()));
+    let sensor_to_world: Transform<f64, B2, Sensor, World> =
+        Transform::new(Affine::identity(B2::as_data()));
+
+    // Composing requires the first transform's destination to be the second's source.
+    let _ = sensor_to_world * sensor_to_body; //~ERROR [E0277]
+}
@@ -0,0 +1,24 @@
+extern crate leviathan as lev;
+
+
+use lev::geometry::frame::Frame;
+use lev::geometry::primitive::Point;
+use lev::linalg::Vect;
+use lev::typehack::binary::B2;
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct World;
+impl Frame for World {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Local;
+impl Frame for Local {}
+
+
+fn main() {
+    let p: Point<f64, B2, World> = Point::from(Vect::<f64, B2>::from(&[1., 2.][..])).in_frame();
+    let v: Vect<f64, B2, Local> = Vect::<f64, B2>::from(&[0., 1.][..]).in_frame();
+
+    let _ = p + v; //~ERROR [E0277]
+}
@@ -0,0 +1,26 @@
+extern crate leviathan as lev;
+
+
+use lev::geometry::frame::{Frame, Transform};
+use lev::geometry::primitive::Point;
+use lev::geometry::transform::Affine;
+use lev::linalg::Vect;
+use lev::typehack::binary::{Nat, B2};
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct World;
+impl Frame for World {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Local;
+impl Frame for Local {}
+
+
+fn main() {
+    let to_world: Transform<f64, B2, Local, World> =
+        Transform::new(Affine::identity(B2::as_data()));
+    let p: Point<f64, B2, World> = Point::from(Vect::<f64, B2>::from(&[1., 2.][..])).in_frame();
+
+    let _ = to_world.transform_point(&p); //~ERROR mismatched types [E0308]
+}